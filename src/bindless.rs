@@ -0,0 +1,250 @@
+//! Bindless descriptor heap
+
+use crate::{
+    descriptor::{
+        DescriptorHeapFlags, DescriptorHeapSlice, DescriptorHeapType, RootSignatureFlags,
+        ShaderVisible,
+    },
+    CpuDescriptor, DescriptorHeap, Device, Fence, HRESULT,
+};
+use std::collections::VecDeque;
+use winapi::shared::winerror;
+
+/// Stable index of a descriptor in a `BindlessHeap`, tagged with the generation
+/// of the slot to detect use after free.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct BindlessHandle {
+    index: u32,
+    generation: u32,
+}
+
+impl BindlessHandle {
+    /// Index of the descriptor in the heap, as used by shaders.
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+}
+
+/// Slot bookkeeping of a `BindlessHeap`, independent of any heap.
+///
+/// Freed slots are only handed out again after the fence value passed to `free`
+/// has completed.
+#[derive(Debug)]
+pub struct BindlessSlots {
+    generations: Vec<u32>,
+    free: Vec<u32>,
+    pending: VecDeque<(u64, u32)>,
+}
+
+impl BindlessSlots {
+    pub fn new(capacity: u32) -> Self {
+        BindlessSlots {
+            generations: vec![0; capacity as usize],
+            free: (0..capacity).rev().collect(),
+            pending: VecDeque::new(),
+        }
+    }
+
+    pub fn capacity(&self) -> u32 {
+        self.generations.len() as u32
+    }
+
+    pub fn allocate(&mut self) -> Option<BindlessHandle> {
+        let index = self.free.pop()?;
+        Some(BindlessHandle {
+            index,
+            generation: self.generations[index as usize],
+        })
+    }
+
+    /// Returns true if `handle` has not been freed.
+    pub fn is_valid(&self, handle: BindlessHandle) -> bool {
+        self.generations.get(handle.index as usize) == Some(&handle.generation)
+    }
+
+    /// Invalidates `handle` and recycles its slot once `fence_value` has completed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `handle` has already been freed.
+    pub fn free(&mut self, handle: BindlessHandle, fence_value: u64) {
+        assert!(self.is_valid(handle), "Stale bindless handle {:?}", handle);
+        let generation = &mut self.generations[handle.index as usize];
+        *generation = generation.wrapping_add(1);
+        self.pending.push_back((fence_value, handle.index));
+    }
+
+    /// Recycles the slots freed with a fence value less or equal to `completed_value`.
+    pub fn reclaim(&mut self, completed_value: u64) {
+        // Values may be freed out of order, so check every pending entry.
+        let free = &mut self.free;
+        self.pending.retain(|&(value, index)| {
+            if value <= completed_value {
+                free.push(index);
+                false
+            } else {
+                true
+            }
+        });
+    }
+}
+
+/// Large shader visible CBV/SRV/UAV heap with stable descriptor indices.
+///
+/// Shaders index the heap through `ResourceDescriptorHeap` (shader model 6.6), which
+/// requires `ROOT_SIGNATURE_FLAGS`, or through `DescriptorRange::unbounded`.
+#[derive(Debug)]
+pub struct BindlessHeap {
+    device: Device,
+    heap: DescriptorHeap,
+    slice: DescriptorHeapSlice<ShaderVisible>,
+    slots: BindlessSlots,
+}
+
+impl BindlessHeap {
+    /// Root signature flags required to index the heap directly from shaders.
+    pub const ROOT_SIGNATURE_FLAGS: RootSignatureFlags =
+        RootSignatureFlags::CBV_SRV_UAV_HEAP_DIRECTLY_INDEXED;
+
+    pub fn new(device: Device, capacity: u32) -> Result<Self, HRESULT> {
+        let (heap, hr) = device.create_descriptor_heap(
+            capacity,
+            DescriptorHeapType::CbvSrvUav,
+            DescriptorHeapFlags::SHADER_VISIBLE,
+            0,
+        );
+        if !winerror::SUCCEEDED(hr) {
+            return Err(hr);
+        }
+        let slice = DescriptorHeapSlice::from_heap(&device, &heap).expect("Shader visible heap");
+
+        Ok(BindlessHeap {
+            device,
+            heap,
+            slice,
+            slots: BindlessSlots::new(capacity),
+        })
+    }
+
+    /// The heap to bind with `set_descriptor_heaps`.
+    pub fn heap(&self) -> &DescriptorHeap {
+        &self.heap
+    }
+
+    pub fn allocate(&mut self) -> Option<BindlessHandle> {
+        self.slots.allocate()
+    }
+
+    /// Allocates a slot and copies the descriptor at `src` into it.
+    pub fn insert(&mut self, src: CpuDescriptor) -> Option<BindlessHandle> {
+        let handle = self.allocate()?;
+        self.device.copy_descriptors_simple(
+            1,
+            self.cpu_handle(handle),
+            src,
+            DescriptorHeapType::CbvSrvUav,
+        );
+        Some(handle)
+    }
+
+    pub fn is_valid(&self, handle: BindlessHandle) -> bool {
+        self.slots.is_valid(handle)
+    }
+
+    /// Returns the CPU handle of the slot, e.g. to create a view in place.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `handle` has been freed.
+    pub fn cpu_handle(&self, handle: BindlessHandle) -> CpuDescriptor {
+        assert!(self.is_valid(handle), "Stale bindless handle {:?}", handle);
+        self.slice.cpu_handle(handle.index)
+    }
+
+    /// Frees the slot once the GPU has signaled `fence_value`.
+    pub fn free(&mut self, handle: BindlessHandle, fence_value: u64) {
+        self.slots.free(handle, fence_value);
+    }
+
+    /// Recycles the slots whose frees have been completed by `fence`.
+    pub fn reclaim(&mut self, fence: &Fence) {
+        self.slots.reclaim(fence.get_value());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn freed_handles_are_invalid() {
+        let mut slots = BindlessSlots::new(1);
+        let handle = slots.allocate().unwrap();
+        assert!(slots.is_valid(handle));
+
+        slots.free(handle, 1);
+        assert!(!slots.is_valid(handle));
+
+        slots.reclaim(1);
+        let reused = slots.allocate().unwrap();
+        assert_eq!(reused.index(), handle.index());
+        assert!(slots.is_valid(reused));
+        assert!(!slots.is_valid(handle));
+    }
+
+    #[test]
+    #[should_panic(expected = "Stale bindless handle")]
+    fn double_free_panics() {
+        let mut slots = BindlessSlots::new(1);
+        let handle = slots.allocate().unwrap();
+        slots.free(handle, 1);
+        slots.free(handle, 2);
+    }
+
+    #[test]
+    fn slots_are_not_reused_before_reclaim() {
+        let mut slots = BindlessSlots::new(1);
+        let handle = slots.allocate().unwrap();
+        slots.free(handle, 2);
+        assert_eq!(slots.allocate(), None);
+
+        slots.reclaim(1);
+        assert_eq!(slots.allocate(), None);
+
+        slots.reclaim(2);
+        let reused = slots.allocate().unwrap();
+        assert_eq!(reused.index(), handle.index());
+        assert_eq!(reused.generation(), handle.generation() + 1);
+    }
+
+    #[test]
+    fn out_of_order_frees_are_reclaimed() {
+        let mut slots = BindlessSlots::new(3);
+        let a = slots.allocate().unwrap();
+        let b = slots.allocate().unwrap();
+        let c = slots.allocate().unwrap();
+        slots.free(a, 3);
+        slots.free(b, 1);
+        slots.free(c, 2);
+
+        slots.reclaim(1);
+        assert_eq!(
+            slots.allocate().map(|handle| handle.index()),
+            Some(b.index())
+        );
+        assert_eq!(slots.allocate(), None);
+
+        slots.reclaim(3);
+        let mut reused = vec![
+            slots.allocate().unwrap().index(),
+            slots.allocate().unwrap().index(),
+        ];
+        reused.sort_unstable();
+        assert_eq!(reused, [a.index(), c.index()]);
+        assert_eq!(slots.allocate(), None);
+    }
+}
@@ -1,12 +1,15 @@
 //! Graphics command list
 
 use crate::{
-    com::ComPtr, resource::DiscardRegion, CommandAllocator, CpuDescriptor, DescriptorHeap, Format,
-    GpuAddress, GpuDescriptor, IndexCount, InstanceCount, PipelineState, Rect, Resource, RootIndex,
-    RootSignature, Subresource, VertexCount, VertexOffset, WorkGroupCount, HRESULT,
+    com::ComPtr,
+    ffi,
+    resource::{DiscardRegion, TextureCopyLocation},
+    CommandAllocator, CpuDescriptor, DescriptorHeap, Format, GpuAddress, GpuDescriptor, IndexCount,
+    InstanceCount, PipelineState, Rect, Resource, RootIndex, RootSignature, Subresource,
+    VertexCount, VertexOffset, WorkGroupCount, HRESULT,
 };
 use std::{mem, ptr};
-use winapi::um::d3d12;
+use winapi::{shared::winerror, um::d3d12, Interface};
 
 #[repr(u32)]
 #[derive(Clone, Copy)]
@@ -19,6 +22,20 @@ pub enum CmdListType {
     // VideoProcess = d3d12::D3D12_COMMAND_LIST_TYPE_VIDEO_PROCESS,
 }
 
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ResolveMode {
+    Decompress = d3d12::D3D12_RESOLVE_MODE_DECOMPRESS,
+    Min = d3d12::D3D12_RESOLVE_MODE_MIN,
+    Max = d3d12::D3D12_RESOLVE_MODE_MAX,
+    Average = d3d12::D3D12_RESOLVE_MODE_AVERAGE,
+    /// Encodes an `R8_UINT` MinMip texture into a sampler feedback map.
+    EncodeSamplerFeedback = ffi::D3D12_RESOLVE_MODE_ENCODE_SAMPLER_FEEDBACK,
+    /// Decodes a sampler feedback map into an `R8_UINT` texture or buffer, see
+    /// `MinMipFeedback`.
+    DecodeSamplerFeedback = ffi::D3D12_RESOLVE_MODE_DECODE_SAMPLER_FEEDBACK,
+}
+
 bitflags::bitflags! {
     #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
     pub struct ClearFlags: u32 {
@@ -143,6 +160,69 @@ impl ResourceBarrier {
 pub type CommandSignature = ComPtr<d3d12::ID3D12CommandSignature>;
 pub type CommandList = ComPtr<d3d12::ID3D12CommandList>;
 pub type GraphicsCommandList = ComPtr<d3d12::ID3D12GraphicsCommandList>;
+pub type GraphicsCommandList1 = ComPtr<ffi::ID3D12GraphicsCommandList1>;
+pub type GraphicsCommandList2 = ComPtr<ffi::ID3D12GraphicsCommandList2>;
+pub type GraphicsCommandList3 = ComPtr<ffi::ID3D12GraphicsCommandList3>;
+pub type GraphicsCommandList4 = ComPtr<ffi::ID3D12GraphicsCommandList4>;
+pub type GraphicsCommandList5 = ComPtr<ffi::ID3D12GraphicsCommandList5>;
+pub type GraphicsCommandList6 = ComPtr<ffi::ID3D12GraphicsCommandList6>;
+pub type GraphicsCommandList7 = ComPtr<ffi::ID3D12GraphicsCommandList7>;
+pub type ProtectedResourceSession = ComPtr<ffi::ID3D12ProtectedResourceSession>;
+
+crate::com_inheritance_chain! {
+    #[derive(Debug, Clone, PartialEq, Hash)]
+    pub enum VersionedGraphicsCommandList {
+        GraphicsCommandList(d3d12::ID3D12GraphicsCommandList), from_graphics_command_list, as_graphics_command_list, graphics_command_list;
+        GraphicsCommandList1(ffi::ID3D12GraphicsCommandList1), from_graphics_command_list1, as_graphics_command_list1, unwrap_graphics_command_list1;
+        GraphicsCommandList2(ffi::ID3D12GraphicsCommandList2), from_graphics_command_list2, as_graphics_command_list2, unwrap_graphics_command_list2;
+        GraphicsCommandList3(ffi::ID3D12GraphicsCommandList3), from_graphics_command_list3, as_graphics_command_list3, unwrap_graphics_command_list3;
+        GraphicsCommandList4(ffi::ID3D12GraphicsCommandList4), from_graphics_command_list4, as_graphics_command_list4, unwrap_graphics_command_list4;
+        GraphicsCommandList5(ffi::ID3D12GraphicsCommandList5), from_graphics_command_list5, as_graphics_command_list5, unwrap_graphics_command_list5;
+        GraphicsCommandList6(ffi::ID3D12GraphicsCommandList6), from_graphics_command_list6, as_graphics_command_list6, unwrap_graphics_command_list6;
+        GraphicsCommandList7(ffi::ID3D12GraphicsCommandList7), from_graphics_command_list7, as_graphics_command_list7, unwrap_graphics_command_list7;
+    }
+}
+
+impl VersionedGraphicsCommandList {
+    /// Wraps `list` as the highest interface version it implements.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `list` is null.
+    pub fn upcast(list: GraphicsCommandList) -> Self {
+        assert!(!list.is_null());
+
+        fn cast<I: Interface>(list: &GraphicsCommandList) -> Option<ComPtr<I>> {
+            let (cast, hr) = unsafe { list.cast::<I>() };
+            if winerror::SUCCEEDED(hr) {
+                Some(cast)
+            } else {
+                None
+            }
+        }
+
+        // SAFETY: each pointer has been queried for the interface of its variant.
+        unsafe {
+            if let Some(list) = cast(&list) {
+                Self::from_graphics_command_list7(list)
+            } else if let Some(list) = cast(&list) {
+                Self::from_graphics_command_list6(list)
+            } else if let Some(list) = cast(&list) {
+                Self::from_graphics_command_list5(list)
+            } else if let Some(list) = cast(&list) {
+                Self::from_graphics_command_list4(list)
+            } else if let Some(list) = cast(&list) {
+                Self::from_graphics_command_list3(list)
+            } else if let Some(list) = cast(&list) {
+                Self::from_graphics_command_list2(list)
+            } else if let Some(list) = cast(&list) {
+                Self::from_graphics_command_list1(list)
+            } else {
+                Self::from_graphics_command_list(list)
+            }
+        }
+    }
+}
 
 impl GraphicsCommandList {
     pub fn as_list(&self) -> CommandList {
@@ -172,6 +252,48 @@ impl GraphicsCommandList {
         }
     }
 
+    pub fn copy_buffer_region(
+        &self,
+        dst: &Resource,
+        dst_offset: u64,
+        src: &Resource,
+        src_offset: u64,
+        size: u64,
+    ) {
+        unsafe {
+            self.CopyBufferRegion(
+                dst.as_mut_ptr(),
+                dst_offset,
+                src.as_mut_ptr(),
+                src_offset,
+                size,
+            );
+        }
+    }
+
+    pub fn copy_texture_region(
+        &self,
+        dst: &TextureCopyLocation,
+        dst_offset: [u32; 3],
+        src: &TextureCopyLocation,
+        src_box: Option<&d3d12::D3D12_BOX>,
+    ) {
+        let src_box = match src_box {
+            Some(b) => b as *const _,
+            None => ptr::null(),
+        };
+        unsafe {
+            self.CopyTextureRegion(
+                &dst.0,
+                dst_offset[0],
+                dst_offset[1],
+                dst_offset[2],
+                &src.0,
+                src_box,
+            );
+        }
+    }
+
     pub fn clear_depth_stencil_view(
         &self,
         dsv: CpuDescriptor,
@@ -404,3 +526,123 @@ impl GraphicsCommandList {
         }
     }
 }
+
+impl GraphicsCommandList1 {
+    pub fn set_depth_bounds(&self, min: f32, max: f32) {
+        unsafe { self.OMSetDepthBounds(min, max) }
+    }
+
+    /// Sets the sample positions of the following draws, given per sample for
+    /// `num_pixels` pixels. An empty slice restores the default positions.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `positions` is not empty and does not hold `samples_per_pixel * num_pixels`
+    /// positions.
+    pub fn set_sample_positions(
+        &self,
+        samples_per_pixel: u32,
+        num_pixels: u32,
+        positions: &[d3d12::D3D12_SAMPLE_POSITION],
+    ) {
+        if positions.is_empty() {
+            unsafe { self.SetSamplePositions(0, 0, ptr::null_mut()) }
+            return;
+        }
+
+        assert_eq!(
+            positions.len(),
+            samples_per_pixel as usize * num_pixels as usize
+        );
+        unsafe { self.SetSamplePositions(samples_per_pixel, num_pixels, positions.as_ptr() as _) }
+    }
+
+    pub fn resolve_subresource_region(
+        &self,
+        dst: &Resource,
+        dst_subresource: Subresource,
+        dst_offset: [u32; 2],
+        src: &Resource,
+        src_subresource: Subresource,
+        src_rect: Option<Rect>,
+        format: Format,
+        mode: ResolveMode,
+    ) {
+        let mut rect = src_rect.unwrap_or(Rect {
+            left: 0,
+            top: 0,
+            right: 0,
+            bottom: 0,
+        });
+        unsafe {
+            self.ResolveSubresourceRegion(
+                dst.as_mut_ptr(),
+                dst_subresource,
+                dst_offset[0],
+                dst_offset[1],
+                src.as_mut_ptr(),
+                src_subresource,
+                if src_rect.is_some() {
+                    &mut rect
+                } else {
+                    ptr::null_mut()
+                },
+                format,
+                mode as _,
+            )
+        }
+    }
+
+    pub fn set_view_instance_mask(&self, mask: u32) {
+        unsafe { self.SetViewInstanceMask(mask) }
+    }
+}
+
+impl GraphicsCommandList2 {
+    /// Writes each 32-bit value to its GPU address. `modes` is either empty or
+    /// has one entry per parameter.
+    pub fn write_buffer_immediate(
+        &self,
+        params: &[ffi::D3D12_WRITEBUFFERIMMEDIATE_PARAMETER],
+        modes: &[ffi::D3D12_WRITEBUFFERIMMEDIATE_MODE],
+    ) {
+        assert!(modes.is_empty() || modes.len() == params.len());
+        unsafe {
+            self.WriteBufferImmediate(
+                params.len() as _,
+                params.as_ptr(),
+                if modes.is_empty() {
+                    ptr::null()
+                } else {
+                    modes.as_ptr()
+                },
+            )
+        }
+    }
+}
+
+impl GraphicsCommandList3 {
+    /// Sets the protected session of the list, or clears it with a null session.
+    pub fn set_protected_resource_session(&self, session: &ProtectedResourceSession) {
+        unsafe { self.SetProtectedResourceSession(session.as_mut_ptr()) }
+    }
+}
+
+impl GraphicsCommandList4 {
+    pub fn dispatch_rays(&self, desc: &ffi::D3D12_DISPATCH_RAYS_DESC) {
+        unsafe { self.DispatchRays(desc) }
+    }
+}
+
+impl GraphicsCommandList6 {
+    pub fn dispatch_mesh(&self, count: WorkGroupCount) {
+        unsafe { self.DispatchMesh(count[0], count[1], count[2]) }
+    }
+}
+
+impl GraphicsCommandList7 {
+    /// Records enhanced barriers, which require `EnhancedBarriersSupported` in `DeviceCapabilities::options12`.
+    pub fn barrier(&self, groups: &[ffi::D3D12_BARRIER_GROUP]) {
+        unsafe { self.Barrier(groups.len() as _, groups.as_ptr()) }
+    }
+}
@@ -1,5 +1,5 @@
-use crate::{com::ComPtr, Blob, D3DResult, Error, TextureAddressMode};
-use std::{fmt, mem, ops::Range};
+use crate::{com::ComPtr, ffi, Blob, D3DResult, Device, Error, GpuAddress, TextureAddressMode};
+use std::{fmt, marker::PhantomData, mem, ops::Range};
 use winapi::{shared::dxgiformat, um::d3d12};
 
 pub type CpuDescriptor = d3d12::D3D12_CPU_DESCRIPTOR_HANDLE;
@@ -12,7 +12,7 @@ pub struct Binding {
 }
 
 #[repr(u32)]
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum DescriptorHeapType {
     CbvSrvUav = d3d12::D3D12_DESCRIPTOR_HEAP_TYPE_CBV_SRV_UAV,
     Sampler = d3d12::D3D12_DESCRIPTOR_HEAP_TYPE_SAMPLER,
@@ -37,6 +37,197 @@ impl DescriptorHeap {
     pub fn start_gpu_descriptor(&self) -> GpuDescriptor {
         unsafe { self.GetGPUDescriptorHandleForHeapStart() }
     }
+
+    pub fn get_desc(&self) -> d3d12::D3D12_DESCRIPTOR_HEAP_DESC {
+        unsafe { self.GetDesc() }
+    }
+}
+
+/// Visibility of a descriptor heap, tracked at the type level.
+pub trait DescriptorHeapVisibility {
+    const FLAGS: DescriptorHeapFlags;
+}
+
+/// Descriptor heap which is only accessible by the CPU.
+#[derive(Clone, Copy, Debug)]
+pub enum CpuOnly {}
+impl DescriptorHeapVisibility for CpuOnly {
+    const FLAGS: DescriptorHeapFlags = DescriptorHeapFlags::empty();
+}
+
+/// Descriptor heap which can be bound with `set_descriptor_heaps`.
+#[derive(Clone, Copy, Debug)]
+pub enum ShaderVisible {}
+impl DescriptorHeapVisibility for ShaderVisible {
+    const FLAGS: DescriptorHeapFlags = DescriptorHeapFlags::SHADER_VISIBLE;
+}
+
+/// Contiguous range of descriptors inside a descriptor heap.
+///
+/// GPU handles are only available for slices of shader visible heaps.
+#[derive(Debug)]
+pub struct DescriptorHeapSlice<V> {
+    heap_type: DescriptorHeapType,
+    increment_size: u32,
+    cpu_start: usize,
+    gpu_start: u64,
+    len: u32,
+    visibility: PhantomData<V>,
+}
+
+impl<V> Clone for DescriptorHeapSlice<V> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<V> Copy for DescriptorHeapSlice<V> {}
+
+impl<V: DescriptorHeapVisibility> DescriptorHeapSlice<V> {
+    /// Returns a slice covering all descriptors of `heap`, or `None` if the
+    /// visibility of `heap` doesn't match `V`.
+    pub fn from_heap(device: &Device, heap: &DescriptorHeap) -> Option<Self> {
+        let desc = heap.get_desc();
+        let flags = DescriptorHeapFlags::from_bits_truncate(desc.Flags);
+        if flags != V::FLAGS {
+            return None;
+        }
+
+        let heap_type = match desc.Type {
+            d3d12::D3D12_DESCRIPTOR_HEAP_TYPE_CBV_SRV_UAV => DescriptorHeapType::CbvSrvUav,
+            d3d12::D3D12_DESCRIPTOR_HEAP_TYPE_SAMPLER => DescriptorHeapType::Sampler,
+            d3d12::D3D12_DESCRIPTOR_HEAP_TYPE_RTV => DescriptorHeapType::Rtv,
+            d3d12::D3D12_DESCRIPTOR_HEAP_TYPE_DSV => DescriptorHeapType::Dsv,
+            _ => return None,
+        };
+        let gpu_start = if flags.contains(DescriptorHeapFlags::SHADER_VISIBLE) {
+            heap.start_gpu_descriptor().ptr
+        } else {
+            0
+        };
+
+        Some(DescriptorHeapSlice {
+            heap_type,
+            increment_size: device.get_descriptor_increment_size(heap_type),
+            cpu_start: heap.start_cpu_descriptor().ptr,
+            gpu_start,
+            len: desc.NumDescriptors,
+            visibility: PhantomData,
+        })
+    }
+}
+
+impl<V> DescriptorHeapSlice<V> {
+    /// # Safety
+    ///
+    /// - `cpu_start` and `gpu_start` must point to `len` descriptors of `heap_type`
+    ///   with `increment_size` spacing, inside a heap matching the visibility `V`.
+    pub unsafe fn from_raw_parts(
+        heap_type: DescriptorHeapType,
+        increment_size: u32,
+        cpu_start: CpuDescriptor,
+        gpu_start: GpuDescriptor,
+        len: u32,
+    ) -> Self {
+        DescriptorHeapSlice {
+            heap_type,
+            increment_size,
+            cpu_start: cpu_start.ptr,
+            gpu_start: gpu_start.ptr,
+            len,
+            visibility: PhantomData,
+        }
+    }
+
+    pub fn heap_type(&self) -> DescriptorHeapType {
+        self.heap_type
+    }
+
+    pub fn increment_size(&self) -> u32 {
+        self.increment_size
+    }
+
+    pub fn len(&self) -> u32 {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the CPU handle of the descriptor at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn cpu_handle(&self, index: u32) -> CpuDescriptor {
+        assert!(index < self.len, "descriptor {} out of bounds", index);
+        CpuDescriptor {
+            ptr: self.cpu_start + index as usize * self.increment_size as usize,
+        }
+    }
+
+    /// Returns the index of the descriptor at `handle` if it lies inside this slice.
+    pub fn index_of(&self, handle: CpuDescriptor) -> Option<u32> {
+        let offset = handle.ptr.checked_sub(self.cpu_start)?;
+        let index = offset / self.increment_size as usize;
+        if offset % self.increment_size as usize != 0 || index >= self.len as usize {
+            return None;
+        }
+        Some(index as u32)
+    }
+
+    /// Returns the slice starting `count` descriptors after the start of this one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `count` is larger than the length.
+    pub fn offset(&self, count: u32) -> Self {
+        self.range(count..self.len)
+    }
+
+    /// Returns the descriptors of `range`, relative to the start of this slice.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is out of bounds.
+    pub fn range(&self, range: Range<u32>) -> Self {
+        assert!(
+            range.start <= range.end && range.end <= self.len,
+            "descriptor range {:?} out of bounds of {}",
+            range,
+            self.len
+        );
+        DescriptorHeapSlice {
+            cpu_start: self.cpu_start + range.start as usize * self.increment_size as usize,
+            gpu_start: self.gpu_start + range.start as u64 * self.increment_size as u64,
+            len: range.end - range.start,
+            ..*self
+        }
+    }
+
+    /// Splits the slice into `[0, mid)` and `[mid, len)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mid` is larger than the length.
+    pub fn split_at(&self, mid: u32) -> (Self, Self) {
+        (self.range(0..mid), self.range(mid..self.len))
+    }
+}
+
+impl DescriptorHeapSlice<ShaderVisible> {
+    /// Returns the GPU handle of the descriptor at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn gpu_handle(&self, index: u32) -> GpuDescriptor {
+        assert!(index < self.len, "descriptor {} out of bounds", index);
+        GpuDescriptor {
+            ptr: self.gpu_start + index as u64 * self.increment_size as u64,
+        }
+    }
 }
 
 #[repr(u32)]
@@ -71,6 +262,11 @@ impl DescriptorRange {
             OffsetInDescriptorsFromTableStart: offset,
         })
     }
+
+    /// Range of descriptors whose size is only bounded by the descriptor heap.
+    pub fn unbounded(ty: DescriptorRangeType, base_binding: Binding, offset: u32) -> Self {
+        Self::new(ty, !0, base_binding, offset)
+    }
 }
 
 impl fmt::Debug for DescriptorRange {
@@ -206,6 +402,227 @@ impl fmt::Debug for RootParameter {
     }
 }
 
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum FilterType {
+    Point = d3d12::D3D12_FILTER_TYPE_POINT,
+    Linear = d3d12::D3D12_FILTER_TYPE_LINEAR,
+}
+
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum FilterReduction {
+    Standard = d3d12::D3D12_FILTER_REDUCTION_TYPE_STANDARD,
+    Comparison = d3d12::D3D12_FILTER_REDUCTION_TYPE_COMPARISON,
+    Minimum = d3d12::D3D12_FILTER_REDUCTION_TYPE_MINIMUM,
+    Maximum = d3d12::D3D12_FILTER_REDUCTION_TYPE_MAXIMUM,
+}
+
+/// Texture filtering, encoded into a `D3D12_FILTER` with `encode`.
+///
+/// Anisotropic filtering implies linear filtering of all three stages.
+///
+/// ```rust
+/// # use d3d12::{Filter, FilterReduction, FilterType};
+/// # use winapi::um::d3d12 as raw;
+/// let filter = Filter::linear().with_reduction(FilterReduction::Comparison);
+/// assert_eq!(filter.encode(), raw::D3D12_FILTER_COMPARISON_MIN_MAG_MIP_LINEAR);
+/// assert_eq!(Filter::decode(raw::D3D12_FILTER_MIN_LINEAR_MAG_MIP_POINT), Some(Filter {
+///     min: FilterType::Linear,
+///     mag: FilterType::Point,
+///     mip: FilterType::Point,
+///     reduction: FilterReduction::Standard,
+///     anisotropic: false,
+/// }));
+/// assert_eq!(Filter::decode(0x2), None);
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Filter {
+    pub min: FilterType,
+    pub mag: FilterType,
+    pub mip: FilterType,
+    pub reduction: FilterReduction,
+    pub anisotropic: bool,
+}
+
+impl Filter {
+    pub fn new(min: FilterType, mag: FilterType, mip: FilterType) -> Self {
+        Filter {
+            min,
+            mag,
+            mip,
+            reduction: FilterReduction::Standard,
+            anisotropic: false,
+        }
+    }
+
+    pub fn point() -> Self {
+        Self::new(FilterType::Point, FilterType::Point, FilterType::Point)
+    }
+
+    pub fn linear() -> Self {
+        Self::new(FilterType::Linear, FilterType::Linear, FilterType::Linear)
+    }
+
+    pub fn anisotropic() -> Self {
+        Filter {
+            anisotropic: true,
+            ..Self::linear()
+        }
+    }
+
+    pub fn with_reduction(mut self, reduction: FilterReduction) -> Self {
+        self.reduction = reduction;
+        self
+    }
+
+    /// Equivalent of `D3D12_ENCODE_BASIC_FILTER` and `D3D12_ENCODE_ANISOTROPIC_FILTER`.
+    pub fn encode(&self) -> d3d12::D3D12_FILTER {
+        let (min, mag, mip) = if self.anisotropic {
+            (FilterType::Linear, FilterType::Linear, FilterType::Linear)
+        } else {
+            (self.min, self.mag, self.mip)
+        };
+        let anisotropic = if self.anisotropic {
+            d3d12::D3D12_ANISOTROPIC_FILTERING_BIT
+        } else {
+            0
+        };
+
+        (min as u32) << d3d12::D3D12_MIN_FILTER_SHIFT
+            | (mag as u32) << d3d12::D3D12_MAG_FILTER_SHIFT
+            | (mip as u32) << d3d12::D3D12_MIP_FILTER_SHIFT
+            | (self.reduction as u32) << d3d12::D3D12_FILTER_REDUCTION_TYPE_SHIFT
+            | anisotropic
+    }
+
+    /// Returns `None` if `filter` is not a valid `D3D12_FILTER`.
+    pub fn decode(filter: d3d12::D3D12_FILTER) -> Option<Self> {
+        fn filter_type(filter: d3d12::D3D12_FILTER, shift: u32) -> Option<FilterType> {
+            match (filter >> shift) & d3d12::D3D12_FILTER_TYPE_MASK {
+                d3d12::D3D12_FILTER_TYPE_POINT => Some(FilterType::Point),
+                d3d12::D3D12_FILTER_TYPE_LINEAR => Some(FilterType::Linear),
+                _ => None,
+            }
+        }
+
+        let reduction = match (filter >> d3d12::D3D12_FILTER_REDUCTION_TYPE_SHIFT)
+            & d3d12::D3D12_FILTER_REDUCTION_TYPE_MASK
+        {
+            d3d12::D3D12_FILTER_REDUCTION_TYPE_STANDARD => FilterReduction::Standard,
+            d3d12::D3D12_FILTER_REDUCTION_TYPE_COMPARISON => FilterReduction::Comparison,
+            d3d12::D3D12_FILTER_REDUCTION_TYPE_MINIMUM => FilterReduction::Minimum,
+            _ => FilterReduction::Maximum,
+        };
+        let decoded = Filter {
+            min: filter_type(filter, d3d12::D3D12_MIN_FILTER_SHIFT)?,
+            mag: filter_type(filter, d3d12::D3D12_MAG_FILTER_SHIFT)?,
+            mip: filter_type(filter, d3d12::D3D12_MIP_FILTER_SHIFT)?,
+            reduction,
+            anisotropic: filter & d3d12::D3D12_ANISOTROPIC_FILTERING_BIT != 0,
+        };
+
+        // Rejects unknown bits and anisotropic filters with non linear stages.
+        if decoded.encode() == filter {
+            Some(decoded)
+        } else {
+            None
+        }
+    }
+}
+
+impl Default for Filter {
+    fn default() -> Self {
+        Self::linear()
+    }
+}
+
+/// Sampler state, used by `Device::create_sampler` and `StaticSampler::from_desc`.
+#[repr(transparent)]
+#[derive(Clone, Copy)]
+pub struct SamplerDesc(pub(crate) d3d12::D3D12_SAMPLER_DESC);
+
+impl SamplerDesc {
+    /// Creates a sampler with wrapping addressing and no LOD clamping.
+    pub fn new(filter: Filter) -> Self {
+        SamplerDesc(d3d12::D3D12_SAMPLER_DESC {
+            Filter: filter.encode(),
+            AddressU: d3d12::D3D12_TEXTURE_ADDRESS_MODE_WRAP,
+            AddressV: d3d12::D3D12_TEXTURE_ADDRESS_MODE_WRAP,
+            AddressW: d3d12::D3D12_TEXTURE_ADDRESS_MODE_WRAP,
+            MipLODBias: 0.0,
+            MaxAnisotropy: if filter.anisotropic {
+                d3d12::D3D12_DEFAULT_MAX_ANISOTROPY
+            } else {
+                1
+            },
+            ComparisonFunc: d3d12::D3D12_COMPARISON_FUNC_NEVER,
+            BorderColor: [0.0; 4],
+            MinLOD: 0.0,
+            MaxLOD: d3d12::D3D12_FLOAT32_MAX,
+        })
+    }
+
+    pub fn filter(&self) -> Filter {
+        Filter::decode(self.0.Filter).expect("Invalid filter")
+    }
+
+    pub fn with_address_mode(mut self, address_mode: TextureAddressMode) -> Self {
+        self.0.AddressU = address_mode[0];
+        self.0.AddressV = address_mode[1];
+        self.0.AddressW = address_mode[2];
+        self
+    }
+
+    pub fn with_mip_lod_bias(mut self, mip_lod_bias: f32) -> Self {
+        self.0.MipLODBias = mip_lod_bias;
+        self
+    }
+
+    /// Only used by anisotropic filters, clamped to `1..=16` by the runtime.
+    pub fn with_max_anisotropy(mut self, max_anisotropy: u32) -> Self {
+        self.0.MaxAnisotropy = max_anisotropy;
+        self
+    }
+
+    /// Only used by filters with `FilterReduction::Comparison`.
+    pub fn with_comparison_op(mut self, comparison_op: d3d12::D3D12_COMPARISON_FUNC) -> Self {
+        self.0.ComparisonFunc = comparison_op;
+        self
+    }
+
+    pub fn with_border_color(mut self, border_color: [f32; 4]) -> Self {
+        self.0.BorderColor = border_color;
+        self
+    }
+
+    pub fn with_lod(mut self, lod: Range<f32>) -> Self {
+        self.0.MinLOD = lod.start;
+        self.0.MaxLOD = lod.end;
+        self
+    }
+
+    /// Returns the static border color equal to the border color, if any.
+    pub fn static_border_color(&self) -> Option<StaticBorderColor> {
+        let color = self.0.BorderColor;
+        if color == [0.0, 0.0, 0.0, 0.0] {
+            Some(StaticBorderColor::TransparentBlack)
+        } else if color == [0.0, 0.0, 0.0, 1.0] {
+            Some(StaticBorderColor::OpaqueBlack)
+        } else if color == [1.0, 1.0, 1.0, 1.0] {
+            Some(StaticBorderColor::OpaqueWhite)
+        } else {
+            None
+        }
+    }
+}
+
+impl Default for SamplerDesc {
+    fn default() -> Self {
+        Self::new(Filter::default())
+    }
+}
+
 #[repr(u32)]
 #[derive(Copy, Clone, Debug)]
 pub enum StaticBorderColor {
@@ -244,6 +661,31 @@ impl StaticSampler {
             ShaderVisibility: visibility as _,
         })
     }
+
+    /// Returns `None` if the border color of `desc` is not one of the `StaticBorderColor`s.
+    pub fn from_desc(
+        visibility: ShaderVisibility,
+        binding: Binding,
+        desc: &SamplerDesc,
+    ) -> Option<Self> {
+        let border_color = desc.static_border_color()?;
+        let desc = desc.0;
+        Some(StaticSampler(d3d12::D3D12_STATIC_SAMPLER_DESC {
+            Filter: desc.Filter,
+            AddressU: desc.AddressU,
+            AddressV: desc.AddressV,
+            AddressW: desc.AddressW,
+            MipLODBias: desc.MipLODBias,
+            MaxAnisotropy: desc.MaxAnisotropy,
+            ComparisonFunc: desc.ComparisonFunc,
+            BorderColor: border_color as _,
+            MinLOD: desc.MinLOD,
+            MaxLOD: desc.MaxLOD,
+            ShaderRegister: binding.register,
+            RegisterSpace: binding.space,
+            ShaderVisibility: visibility as _,
+        }))
+    }
 }
 
 #[repr(u32)]
@@ -262,6 +704,12 @@ bitflags::bitflags! {
         const DENY_DS_ROOT_ACCESS = d3d12::D3D12_ROOT_SIGNATURE_FLAG_DENY_DOMAIN_SHADER_ROOT_ACCESS;
         const DENY_GS_ROOT_ACCESS = d3d12::D3D12_ROOT_SIGNATURE_FLAG_DENY_GEOMETRY_SHADER_ROOT_ACCESS;
         const DENY_PS_ROOT_ACCESS = d3d12::D3D12_ROOT_SIGNATURE_FLAG_DENY_PIXEL_SHADER_ROOT_ACCESS;
+        const ALLOW_STREAM_OUTPUT = d3d12::D3D12_ROOT_SIGNATURE_FLAG_ALLOW_STREAM_OUTPUT;
+        const LOCAL_ROOT_SIGNATURE = ffi::D3D12_ROOT_SIGNATURE_FLAG_LOCAL_ROOT_SIGNATURE;
+        const DENY_AS_ROOT_ACCESS = ffi::D3D12_ROOT_SIGNATURE_FLAG_DENY_AMPLIFICATION_SHADER_ROOT_ACCESS;
+        const DENY_MS_ROOT_ACCESS = ffi::D3D12_ROOT_SIGNATURE_FLAG_DENY_MESH_SHADER_ROOT_ACCESS;
+        const CBV_SRV_UAV_HEAP_DIRECTLY_INDEXED = ffi::D3D12_ROOT_SIGNATURE_FLAG_CBV_SRV_UAV_HEAP_DIRECTLY_INDEXED;
+        const SAMPLER_HEAP_DIRECTLY_INDEXED = ffi::D3D12_ROOT_SIGNATURE_FLAG_SAMPLER_HEAP_DIRECTLY_INDEXED;
     }
 }
 
@@ -354,4 +802,642 @@ impl RenderTargetViewDesc {
 
         RenderTargetViewDesc(desc)
     }
+
+    pub fn buffer(format: dxgiformat::DXGI_FORMAT, first_element: u64, num_elements: u32) -> Self {
+        let mut desc = d3d12::D3D12_RENDER_TARGET_VIEW_DESC {
+            Format: format,
+            ViewDimension: d3d12::D3D12_RTV_DIMENSION_BUFFER,
+            ..unsafe { mem::zeroed() }
+        };
+
+        *unsafe { desc.u.Buffer_mut() } = d3d12::D3D12_BUFFER_RTV {
+            FirstElement: first_element,
+            NumElements: num_elements,
+        };
+
+        RenderTargetViewDesc(desc)
+    }
+
+    pub fn texture_1d(format: dxgiformat::DXGI_FORMAT, mip_slice: u32) -> Self {
+        let mut desc = d3d12::D3D12_RENDER_TARGET_VIEW_DESC {
+            Format: format,
+            ViewDimension: d3d12::D3D12_RTV_DIMENSION_TEXTURE1D,
+            ..unsafe { mem::zeroed() }
+        };
+
+        *unsafe { desc.u.Texture1D_mut() } = d3d12::D3D12_TEX1D_RTV {
+            MipSlice: mip_slice,
+        };
+
+        RenderTargetViewDesc(desc)
+    }
+
+    pub fn texture_1d_array(
+        format: dxgiformat::DXGI_FORMAT,
+        mip_slice: u32,
+        first_array_slice: u32,
+        array_size: u32,
+    ) -> Self {
+        let mut desc = d3d12::D3D12_RENDER_TARGET_VIEW_DESC {
+            Format: format,
+            ViewDimension: d3d12::D3D12_RTV_DIMENSION_TEXTURE1DARRAY,
+            ..unsafe { mem::zeroed() }
+        };
+
+        *unsafe { desc.u.Texture1DArray_mut() } = d3d12::D3D12_TEX1D_ARRAY_RTV {
+            MipSlice: mip_slice,
+            FirstArraySlice: first_array_slice,
+            ArraySize: array_size,
+        };
+
+        RenderTargetViewDesc(desc)
+    }
+
+    pub fn texture_2d_array(
+        format: dxgiformat::DXGI_FORMAT,
+        mip_slice: u32,
+        first_array_slice: u32,
+        array_size: u32,
+        plane_slice: u32,
+    ) -> Self {
+        let mut desc = d3d12::D3D12_RENDER_TARGET_VIEW_DESC {
+            Format: format,
+            ViewDimension: d3d12::D3D12_RTV_DIMENSION_TEXTURE2DARRAY,
+            ..unsafe { mem::zeroed() }
+        };
+
+        *unsafe { desc.u.Texture2DArray_mut() } = d3d12::D3D12_TEX2D_ARRAY_RTV {
+            MipSlice: mip_slice,
+            FirstArraySlice: first_array_slice,
+            ArraySize: array_size,
+            PlaneSlice: plane_slice,
+        };
+
+        RenderTargetViewDesc(desc)
+    }
+
+    pub fn texture_2d_ms(format: dxgiformat::DXGI_FORMAT) -> Self {
+        RenderTargetViewDesc(d3d12::D3D12_RENDER_TARGET_VIEW_DESC {
+            Format: format,
+            ViewDimension: d3d12::D3D12_RTV_DIMENSION_TEXTURE2DMS,
+            ..unsafe { mem::zeroed() }
+        })
+    }
+
+    pub fn texture_2d_ms_array(
+        format: dxgiformat::DXGI_FORMAT,
+        first_array_slice: u32,
+        array_size: u32,
+    ) -> Self {
+        let mut desc = d3d12::D3D12_RENDER_TARGET_VIEW_DESC {
+            Format: format,
+            ViewDimension: d3d12::D3D12_RTV_DIMENSION_TEXTURE2DMSARRAY,
+            ..unsafe { mem::zeroed() }
+        };
+
+        *unsafe { desc.u.Texture2DMSArray_mut() } = d3d12::D3D12_TEX2DMS_ARRAY_RTV {
+            FirstArraySlice: first_array_slice,
+            ArraySize: array_size,
+        };
+
+        RenderTargetViewDesc(desc)
+    }
+
+    pub fn texture_3d(
+        format: dxgiformat::DXGI_FORMAT,
+        mip_slice: u32,
+        first_w_slice: u32,
+        w_size: u32,
+    ) -> Self {
+        let mut desc = d3d12::D3D12_RENDER_TARGET_VIEW_DESC {
+            Format: format,
+            ViewDimension: d3d12::D3D12_RTV_DIMENSION_TEXTURE3D,
+            ..unsafe { mem::zeroed() }
+        };
+
+        *unsafe { desc.u.Texture3D_mut() } = d3d12::D3D12_TEX3D_RTV {
+            MipSlice: mip_slice,
+            FirstWSlice: first_w_slice,
+            WSize: w_size,
+        };
+
+        RenderTargetViewDesc(desc)
+    }
+}
+
+bitflags::bitflags! {
+    #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    pub struct DepthStencilViewFlags: u32 {
+        const READ_ONLY_DEPTH = d3d12::D3D12_DSV_FLAG_READ_ONLY_DEPTH;
+        const READ_ONLY_STENCIL = d3d12::D3D12_DSV_FLAG_READ_ONLY_STENCIL;
+    }
+}
+
+#[repr(transparent)]
+pub struct DepthStencilViewDesc(pub(crate) d3d12::D3D12_DEPTH_STENCIL_VIEW_DESC);
+
+impl DepthStencilViewDesc {
+    pub fn texture_1d(format: dxgiformat::DXGI_FORMAT, mip_slice: u32) -> Self {
+        let mut desc = d3d12::D3D12_DEPTH_STENCIL_VIEW_DESC {
+            Format: format,
+            ViewDimension: d3d12::D3D12_DSV_DIMENSION_TEXTURE1D,
+            ..unsafe { mem::zeroed() }
+        };
+
+        *unsafe { desc.u.Texture1D_mut() } = d3d12::D3D12_TEX1D_DSV {
+            MipSlice: mip_slice,
+        };
+
+        DepthStencilViewDesc(desc)
+    }
+
+    pub fn texture_1d_array(
+        format: dxgiformat::DXGI_FORMAT,
+        mip_slice: u32,
+        first_array_slice: u32,
+        array_size: u32,
+    ) -> Self {
+        let mut desc = d3d12::D3D12_DEPTH_STENCIL_VIEW_DESC {
+            Format: format,
+            ViewDimension: d3d12::D3D12_DSV_DIMENSION_TEXTURE1DARRAY,
+            ..unsafe { mem::zeroed() }
+        };
+
+        *unsafe { desc.u.Texture1DArray_mut() } = d3d12::D3D12_TEX1D_ARRAY_DSV {
+            MipSlice: mip_slice,
+            FirstArraySlice: first_array_slice,
+            ArraySize: array_size,
+        };
+
+        DepthStencilViewDesc(desc)
+    }
+
+    pub fn texture_2d(format: dxgiformat::DXGI_FORMAT, mip_slice: u32) -> Self {
+        let mut desc = d3d12::D3D12_DEPTH_STENCIL_VIEW_DESC {
+            Format: format,
+            ViewDimension: d3d12::D3D12_DSV_DIMENSION_TEXTURE2D,
+            ..unsafe { mem::zeroed() }
+        };
+
+        *unsafe { desc.u.Texture2D_mut() } = d3d12::D3D12_TEX2D_DSV {
+            MipSlice: mip_slice,
+        };
+
+        DepthStencilViewDesc(desc)
+    }
+
+    pub fn texture_2d_array(
+        format: dxgiformat::DXGI_FORMAT,
+        mip_slice: u32,
+        first_array_slice: u32,
+        array_size: u32,
+    ) -> Self {
+        let mut desc = d3d12::D3D12_DEPTH_STENCIL_VIEW_DESC {
+            Format: format,
+            ViewDimension: d3d12::D3D12_DSV_DIMENSION_TEXTURE2DARRAY,
+            ..unsafe { mem::zeroed() }
+        };
+
+        *unsafe { desc.u.Texture2DArray_mut() } = d3d12::D3D12_TEX2D_ARRAY_DSV {
+            MipSlice: mip_slice,
+            FirstArraySlice: first_array_slice,
+            ArraySize: array_size,
+        };
+
+        DepthStencilViewDesc(desc)
+    }
+
+    pub fn texture_2d_ms(format: dxgiformat::DXGI_FORMAT) -> Self {
+        DepthStencilViewDesc(d3d12::D3D12_DEPTH_STENCIL_VIEW_DESC {
+            Format: format,
+            ViewDimension: d3d12::D3D12_DSV_DIMENSION_TEXTURE2DMS,
+            ..unsafe { mem::zeroed() }
+        })
+    }
+
+    pub fn texture_2d_ms_array(
+        format: dxgiformat::DXGI_FORMAT,
+        first_array_slice: u32,
+        array_size: u32,
+    ) -> Self {
+        let mut desc = d3d12::D3D12_DEPTH_STENCIL_VIEW_DESC {
+            Format: format,
+            ViewDimension: d3d12::D3D12_DSV_DIMENSION_TEXTURE2DMSARRAY,
+            ..unsafe { mem::zeroed() }
+        };
+
+        *unsafe { desc.u.Texture2DMSArray_mut() } = d3d12::D3D12_TEX2DMS_ARRAY_DSV {
+            FirstArraySlice: first_array_slice,
+            ArraySize: array_size,
+        };
+
+        DepthStencilViewDesc(desc)
+    }
+
+    pub fn with_flags(mut self, flags: DepthStencilViewFlags) -> Self {
+        self.0.Flags = flags.bits();
+        self
+    }
+}
+
+/// Source of a single component returned when sampling a shader resource view.
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ShaderComponent {
+    Red = d3d12::D3D12_SHADER_COMPONENT_MAPPING_FROM_MEMORY_COMPONENT_0,
+    Green = d3d12::D3D12_SHADER_COMPONENT_MAPPING_FROM_MEMORY_COMPONENT_1,
+    Blue = d3d12::D3D12_SHADER_COMPONENT_MAPPING_FROM_MEMORY_COMPONENT_2,
+    Alpha = d3d12::D3D12_SHADER_COMPONENT_MAPPING_FROM_MEMORY_COMPONENT_3,
+    Zero = d3d12::D3D12_SHADER_COMPONENT_MAPPING_FORCE_VALUE_0,
+    One = d3d12::D3D12_SHADER_COMPONENT_MAPPING_FORCE_VALUE_1,
+}
+
+/// Sources of the red, green, blue and alpha components of a shader resource view.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct ComponentMapping(pub [ShaderComponent; 4]);
+
+impl ComponentMapping {
+    pub const IDENTITY: Self = ComponentMapping([
+        ShaderComponent::Red,
+        ShaderComponent::Green,
+        ShaderComponent::Blue,
+        ShaderComponent::Alpha,
+    ]);
+
+    /// Encodes the mapping like `D3D12_ENCODE_SHADER_4_COMPONENT_MAPPING`.
+    pub fn encode(self) -> u32 {
+        self.0.iter().enumerate().fold(
+            d3d12::D3D12_SHADER_COMPONENT_MAPPING_ALWAYS_SET_BIT_AVOIDING_ZEROMEM_MISTAKES,
+            |bits, (i, &component)| {
+                bits | (component as u32 & d3d12::D3D12_SHADER_COMPONENT_MAPPING_MASK)
+                    << (i as u32 * d3d12::D3D12_SHADER_COMPONENT_MAPPING_SHIFT)
+            },
+        )
+    }
+}
+
+impl Default for ComponentMapping {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+#[repr(transparent)]
+pub struct ShaderResourceViewDesc(pub(crate) d3d12::D3D12_SHADER_RESOURCE_VIEW_DESC);
+
+impl ShaderResourceViewDesc {
+    fn new(format: dxgiformat::DXGI_FORMAT, dimension: d3d12::D3D12_SRV_DIMENSION) -> Self {
+        ShaderResourceViewDesc(d3d12::D3D12_SHADER_RESOURCE_VIEW_DESC {
+            Format: format,
+            ViewDimension: dimension,
+            Shader4ComponentMapping: ComponentMapping::IDENTITY.encode(),
+            ..unsafe { mem::zeroed() }
+        })
+    }
+
+    fn buffer(
+        format: dxgiformat::DXGI_FORMAT,
+        first_element: u64,
+        num_elements: u32,
+        structure_byte_stride: u32,
+        flags: d3d12::D3D12_BUFFER_SRV_FLAGS,
+    ) -> Self {
+        let mut desc = Self::new(format, d3d12::D3D12_SRV_DIMENSION_BUFFER);
+        *unsafe { desc.0.u.Buffer_mut() } = d3d12::D3D12_BUFFER_SRV {
+            FirstElement: first_element,
+            NumElements: num_elements,
+            StructureByteStride: structure_byte_stride,
+            Flags: flags,
+        };
+        desc
+    }
+
+    pub fn typed_buffer(
+        format: dxgiformat::DXGI_FORMAT,
+        first_element: u64,
+        num_elements: u32,
+    ) -> Self {
+        Self::buffer(
+            format,
+            first_element,
+            num_elements,
+            0,
+            d3d12::D3D12_BUFFER_SRV_FLAG_NONE,
+        )
+    }
+
+    pub fn structured_buffer(
+        first_element: u64,
+        num_elements: u32,
+        structure_byte_stride: u32,
+    ) -> Self {
+        Self::buffer(
+            dxgiformat::DXGI_FORMAT_UNKNOWN,
+            first_element,
+            num_elements,
+            structure_byte_stride,
+            d3d12::D3D12_BUFFER_SRV_FLAG_NONE,
+        )
+    }
+
+    /// View of a `ByteAddressBuffer`, with elements of 4 bytes.
+    pub fn raw_buffer(first_element: u64, num_elements: u32) -> Self {
+        Self::buffer(
+            dxgiformat::DXGI_FORMAT_R32_TYPELESS,
+            first_element,
+            num_elements,
+            0,
+            d3d12::D3D12_BUFFER_SRV_FLAG_RAW,
+        )
+    }
+
+    pub fn texture_1d(
+        format: dxgiformat::DXGI_FORMAT,
+        most_detailed_mip: u32,
+        mip_levels: u32,
+    ) -> Self {
+        let mut desc = Self::new(format, d3d12::D3D12_SRV_DIMENSION_TEXTURE1D);
+        *unsafe { desc.0.u.Texture1D_mut() } = d3d12::D3D12_TEX1D_SRV {
+            MostDetailedMip: most_detailed_mip,
+            MipLevels: mip_levels,
+            ResourceMinLODClamp: 0.0,
+        };
+        desc
+    }
+
+    pub fn texture_1d_array(
+        format: dxgiformat::DXGI_FORMAT,
+        most_detailed_mip: u32,
+        mip_levels: u32,
+        first_array_slice: u32,
+        array_size: u32,
+    ) -> Self {
+        let mut desc = Self::new(format, d3d12::D3D12_SRV_DIMENSION_TEXTURE1DARRAY);
+        *unsafe { desc.0.u.Texture1DArray_mut() } = d3d12::D3D12_TEX1D_ARRAY_SRV {
+            MostDetailedMip: most_detailed_mip,
+            MipLevels: mip_levels,
+            FirstArraySlice: first_array_slice,
+            ArraySize: array_size,
+            ResourceMinLODClamp: 0.0,
+        };
+        desc
+    }
+
+    pub fn texture_2d(
+        format: dxgiformat::DXGI_FORMAT,
+        most_detailed_mip: u32,
+        mip_levels: u32,
+        plane_slice: u32,
+    ) -> Self {
+        let mut desc = Self::new(format, d3d12::D3D12_SRV_DIMENSION_TEXTURE2D);
+        *unsafe { desc.0.u.Texture2D_mut() } = d3d12::D3D12_TEX2D_SRV {
+            MostDetailedMip: most_detailed_mip,
+            MipLevels: mip_levels,
+            PlaneSlice: plane_slice,
+            ResourceMinLODClamp: 0.0,
+        };
+        desc
+    }
+
+    pub fn texture_2d_array(
+        format: dxgiformat::DXGI_FORMAT,
+        most_detailed_mip: u32,
+        mip_levels: u32,
+        first_array_slice: u32,
+        array_size: u32,
+        plane_slice: u32,
+    ) -> Self {
+        let mut desc = Self::new(format, d3d12::D3D12_SRV_DIMENSION_TEXTURE2DARRAY);
+        *unsafe { desc.0.u.Texture2DArray_mut() } = d3d12::D3D12_TEX2D_ARRAY_SRV {
+            MostDetailedMip: most_detailed_mip,
+            MipLevels: mip_levels,
+            FirstArraySlice: first_array_slice,
+            ArraySize: array_size,
+            PlaneSlice: plane_slice,
+            ResourceMinLODClamp: 0.0,
+        };
+        desc
+    }
+
+    pub fn texture_2d_ms(format: dxgiformat::DXGI_FORMAT) -> Self {
+        Self::new(format, d3d12::D3D12_SRV_DIMENSION_TEXTURE2DMS)
+    }
+
+    pub fn texture_2d_ms_array(
+        format: dxgiformat::DXGI_FORMAT,
+        first_array_slice: u32,
+        array_size: u32,
+    ) -> Self {
+        let mut desc = Self::new(format, d3d12::D3D12_SRV_DIMENSION_TEXTURE2DMSARRAY);
+        *unsafe { desc.0.u.Texture2DMSArray_mut() } = d3d12::D3D12_TEX2DMS_ARRAY_SRV {
+            FirstArraySlice: first_array_slice,
+            ArraySize: array_size,
+        };
+        desc
+    }
+
+    pub fn texture_3d(
+        format: dxgiformat::DXGI_FORMAT,
+        most_detailed_mip: u32,
+        mip_levels: u32,
+    ) -> Self {
+        let mut desc = Self::new(format, d3d12::D3D12_SRV_DIMENSION_TEXTURE3D);
+        *unsafe { desc.0.u.Texture3D_mut() } = d3d12::D3D12_TEX3D_SRV {
+            MostDetailedMip: most_detailed_mip,
+            MipLevels: mip_levels,
+            ResourceMinLODClamp: 0.0,
+        };
+        desc
+    }
+
+    pub fn texture_cube(
+        format: dxgiformat::DXGI_FORMAT,
+        most_detailed_mip: u32,
+        mip_levels: u32,
+    ) -> Self {
+        let mut desc = Self::new(format, d3d12::D3D12_SRV_DIMENSION_TEXTURECUBE);
+        *unsafe { desc.0.u.TextureCube_mut() } = d3d12::D3D12_TEXCUBE_SRV {
+            MostDetailedMip: most_detailed_mip,
+            MipLevels: mip_levels,
+            ResourceMinLODClamp: 0.0,
+        };
+        desc
+    }
+
+    pub fn texture_cube_array(
+        format: dxgiformat::DXGI_FORMAT,
+        most_detailed_mip: u32,
+        mip_levels: u32,
+        first_2d_array_face: u32,
+        num_cubes: u32,
+    ) -> Self {
+        let mut desc = Self::new(format, d3d12::D3D12_SRV_DIMENSION_TEXTURECUBEARRAY);
+        *unsafe { desc.0.u.TextureCubeArray_mut() } = d3d12::D3D12_TEXCUBE_ARRAY_SRV {
+            MostDetailedMip: most_detailed_mip,
+            MipLevels: mip_levels,
+            First2DArrayFace: first_2d_array_face,
+            NumCubes: num_cubes,
+            ResourceMinLODClamp: 0.0,
+        };
+        desc
+    }
+
+    pub fn with_component_mapping(mut self, mapping: ComponentMapping) -> Self {
+        self.0.Shader4ComponentMapping = mapping.encode();
+        self
+    }
+}
+
+#[repr(transparent)]
+pub struct UnorderedAccessViewDesc(pub(crate) d3d12::D3D12_UNORDERED_ACCESS_VIEW_DESC);
+
+impl UnorderedAccessViewDesc {
+    fn new(format: dxgiformat::DXGI_FORMAT, dimension: d3d12::D3D12_UAV_DIMENSION) -> Self {
+        UnorderedAccessViewDesc(d3d12::D3D12_UNORDERED_ACCESS_VIEW_DESC {
+            Format: format,
+            ViewDimension: dimension,
+            ..unsafe { mem::zeroed() }
+        })
+    }
+
+    fn buffer(
+        format: dxgiformat::DXGI_FORMAT,
+        first_element: u64,
+        num_elements: u32,
+        structure_byte_stride: u32,
+        counter_offset: u64,
+        flags: d3d12::D3D12_BUFFER_UAV_FLAGS,
+    ) -> Self {
+        let mut desc = Self::new(format, d3d12::D3D12_UAV_DIMENSION_BUFFER);
+        *unsafe { desc.0.u.Buffer_mut() } = d3d12::D3D12_BUFFER_UAV {
+            FirstElement: first_element,
+            NumElements: num_elements,
+            StructureByteStride: structure_byte_stride,
+            CounterOffsetInBytes: counter_offset,
+            Flags: flags,
+        };
+        desc
+    }
+
+    pub fn typed_buffer(
+        format: dxgiformat::DXGI_FORMAT,
+        first_element: u64,
+        num_elements: u32,
+    ) -> Self {
+        Self::buffer(
+            format,
+            first_element,
+            num_elements,
+            0,
+            0,
+            d3d12::D3D12_BUFFER_UAV_FLAG_NONE,
+        )
+    }
+
+    /// `counter_offset` is the offset of the counter in the counter resource passed to
+    /// `Device::create_unordered_access_view`.
+    pub fn structured_buffer(
+        first_element: u64,
+        num_elements: u32,
+        structure_byte_stride: u32,
+        counter_offset: u64,
+    ) -> Self {
+        Self::buffer(
+            dxgiformat::DXGI_FORMAT_UNKNOWN,
+            first_element,
+            num_elements,
+            structure_byte_stride,
+            counter_offset,
+            d3d12::D3D12_BUFFER_UAV_FLAG_NONE,
+        )
+    }
+
+    /// View of a `RWByteAddressBuffer`, with elements of 4 bytes.
+    pub fn raw_buffer(first_element: u64, num_elements: u32) -> Self {
+        Self::buffer(
+            dxgiformat::DXGI_FORMAT_R32_TYPELESS,
+            first_element,
+            num_elements,
+            0,
+            0,
+            d3d12::D3D12_BUFFER_UAV_FLAG_RAW,
+        )
+    }
+
+    pub fn texture_1d(format: dxgiformat::DXGI_FORMAT, mip_slice: u32) -> Self {
+        let mut desc = Self::new(format, d3d12::D3D12_UAV_DIMENSION_TEXTURE1D);
+        *unsafe { desc.0.u.Texture1D_mut() } = d3d12::D3D12_TEX1D_UAV {
+            MipSlice: mip_slice,
+        };
+        desc
+    }
+
+    pub fn texture_1d_array(
+        format: dxgiformat::DXGI_FORMAT,
+        mip_slice: u32,
+        first_array_slice: u32,
+        array_size: u32,
+    ) -> Self {
+        let mut desc = Self::new(format, d3d12::D3D12_UAV_DIMENSION_TEXTURE1DARRAY);
+        *unsafe { desc.0.u.Texture1DArray_mut() } = d3d12::D3D12_TEX1D_ARRAY_UAV {
+            MipSlice: mip_slice,
+            FirstArraySlice: first_array_slice,
+            ArraySize: array_size,
+        };
+        desc
+    }
+
+    pub fn texture_2d(format: dxgiformat::DXGI_FORMAT, mip_slice: u32, plane_slice: u32) -> Self {
+        let mut desc = Self::new(format, d3d12::D3D12_UAV_DIMENSION_TEXTURE2D);
+        *unsafe { desc.0.u.Texture2D_mut() } = d3d12::D3D12_TEX2D_UAV {
+            MipSlice: mip_slice,
+            PlaneSlice: plane_slice,
+        };
+        desc
+    }
+
+    pub fn texture_2d_array(
+        format: dxgiformat::DXGI_FORMAT,
+        mip_slice: u32,
+        first_array_slice: u32,
+        array_size: u32,
+        plane_slice: u32,
+    ) -> Self {
+        let mut desc = Self::new(format, d3d12::D3D12_UAV_DIMENSION_TEXTURE2DARRAY);
+        *unsafe { desc.0.u.Texture2DArray_mut() } = d3d12::D3D12_TEX2D_ARRAY_UAV {
+            MipSlice: mip_slice,
+            FirstArraySlice: first_array_slice,
+            ArraySize: array_size,
+            PlaneSlice: plane_slice,
+        };
+        desc
+    }
+
+    pub fn texture_3d(
+        format: dxgiformat::DXGI_FORMAT,
+        mip_slice: u32,
+        first_w_slice: u32,
+        w_size: u32,
+    ) -> Self {
+        let mut desc = Self::new(format, d3d12::D3D12_UAV_DIMENSION_TEXTURE3D);
+        *unsafe { desc.0.u.Texture3D_mut() } = d3d12::D3D12_TEX3D_UAV {
+            MipSlice: mip_slice,
+            FirstWSlice: first_w_slice,
+            WSize: w_size,
+        };
+        desc
+    }
+}
+
+#[repr(transparent)]
+pub struct ConstantBufferViewDesc(pub(crate) d3d12::D3D12_CONSTANT_BUFFER_VIEW_DESC);
+
+impl ConstantBufferViewDesc {
+    /// `size_in_bytes` must be a multiple of 256.
+    pub fn new(buffer_location: GpuAddress, size_in_bytes: u32) -> Self {
+        ConstantBufferViewDesc(d3d12::D3D12_CONSTANT_BUFFER_VIEW_DESC {
+            BufferLocation: buffer_location,
+            SizeInBytes: size_in_bytes,
+        })
+    }
 }
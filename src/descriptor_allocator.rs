@@ -0,0 +1,544 @@
+//! Descriptor allocators
+
+use crate::{
+    descriptor::{
+        CpuOnly, DescriptorHeapFlags, DescriptorHeapSlice, DescriptorHeapType, ShaderVisible,
+    },
+    upload::RingAllocator,
+    CpuDescriptor, DescriptorHeap, Device, Fence, HRESULT,
+};
+use std::{collections::HashMap, ops::Range};
+use winapi::shared::winerror;
+
+/// First-fit allocator of index ranges in `[0, size)`.
+#[derive(Debug)]
+pub struct FreeList {
+    size: u32,
+    // Sorted and coalesced.
+    free: Vec<Range<u32>>,
+}
+
+impl FreeList {
+    pub fn new(size: u32) -> Self {
+        let mut free = Vec::new();
+        if size > 0 {
+            free.push(0..size);
+        }
+        FreeList { size, free }
+    }
+
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+
+    /// Number of indices which are not allocated.
+    pub fn available(&self) -> u32 {
+        self.free.iter().map(|range| range.end - range.start).sum()
+    }
+
+    /// Allocates `count` contiguous indices and returns the first one.
+    pub fn allocate(&mut self, count: u32) -> Option<u32> {
+        let position = self
+            .free
+            .iter()
+            .position(|range| range.end - range.start >= count)?;
+        let range = &mut self.free[position];
+        let start = range.start;
+        range.start += count;
+        if range.start == range.end {
+            self.free.remove(position);
+        }
+        Some(start)
+    }
+
+    /// Returns `count` indices starting at `start` to the free list.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the indices is already free.
+    pub fn free(&mut self, start: u32, count: u32) {
+        if count == 0 {
+            return;
+        }
+        let end = start + count;
+        assert!(end <= self.size, "range {}..{} out of bounds", start, end);
+
+        let position = self.free.partition_point(|range| range.start < start);
+        let merge_prev = match position.checked_sub(1).map(|i| &self.free[i]) {
+            Some(prev) => {
+                assert!(
+                    prev.end <= start,
+                    "range {}..{} is already free",
+                    start,
+                    end
+                );
+                prev.end == start
+            }
+            None => false,
+        };
+        let merge_next = match self.free.get(position) {
+            Some(next) => {
+                assert!(
+                    end <= next.start,
+                    "range {}..{} is already free",
+                    start,
+                    end
+                );
+                end == next.start
+            }
+            None => false,
+        };
+
+        match (merge_prev, merge_next) {
+            (true, true) => {
+                let next = self.free.remove(position);
+                self.free[position - 1].end = next.end;
+            }
+            (true, false) => self.free[position - 1].end = end,
+            (false, true) => self.free[position].start = start,
+            (false, false) => self.free.insert(position, start..end),
+        }
+    }
+}
+
+/// Page bookkeeping of a growing descriptor allocator, independent of any heap.
+#[derive(Debug)]
+pub struct DescriptorPages {
+    page_size: u32,
+    pages: Vec<FreeList>,
+}
+
+impl DescriptorPages {
+    pub fn new(page_size: u32) -> Self {
+        DescriptorPages {
+            page_size,
+            pages: Vec::new(),
+        }
+    }
+
+    pub fn page_size(&self) -> u32 {
+        self.page_size
+    }
+
+    pub fn page_count(&self) -> usize {
+        self.pages.len()
+    }
+
+    /// Allocates `count` contiguous descriptors from the existing pages and
+    /// returns the page and the index inside the page.
+    pub fn allocate(&mut self, count: u32) -> Option<(usize, u32)> {
+        self.pages
+            .iter_mut()
+            .enumerate()
+            .find_map(|(page, list)| list.allocate(count).map(|index| (page, index)))
+    }
+
+    /// Adds an empty page and returns its index.
+    pub fn add_page(&mut self) -> usize {
+        self.pages.push(FreeList::new(self.page_size));
+        self.pages.len() - 1
+    }
+
+    pub fn free(&mut self, page: usize, index: u32, count: u32) {
+        self.pages[page].free(index, count);
+    }
+}
+
+/// Contiguous descriptors handed out by a `CpuDescriptorAllocator`.
+#[derive(Debug)]
+pub struct DescriptorAllocation {
+    page: usize,
+    index: u32,
+    slice: DescriptorHeapSlice<CpuOnly>,
+}
+
+impl DescriptorAllocation {
+    pub fn slice(&self) -> &DescriptorHeapSlice<CpuOnly> {
+        &self.slice
+    }
+
+    pub fn len(&self) -> u32 {
+        self.slice.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.slice.is_empty()
+    }
+
+    /// Returns the CPU handle of the descriptor at `index` inside the allocation.
+    pub fn cpu_handle(&self, index: u32) -> CpuDescriptor {
+        self.slice.cpu_handle(index)
+    }
+}
+
+/// Growing allocator of descriptors in non shader visible heaps of a single type.
+///
+/// New heaps of `page_size` descriptors are created when the existing ones are exhausted.
+#[derive(Debug)]
+pub struct CpuDescriptorAllocator {
+    device: Device,
+    heap_type: DescriptorHeapType,
+    heaps: Vec<(DescriptorHeap, DescriptorHeapSlice<CpuOnly>)>,
+    pages: DescriptorPages,
+}
+
+impl CpuDescriptorAllocator {
+    pub fn new(device: Device, heap_type: DescriptorHeapType, page_size: u32) -> Self {
+        CpuDescriptorAllocator {
+            device,
+            heap_type,
+            heaps: Vec::new(),
+            pages: DescriptorPages::new(page_size),
+        }
+    }
+
+    pub fn heap_type(&self) -> DescriptorHeapType {
+        self.heap_type
+    }
+
+    /// Allocates `count` contiguous descriptors.
+    ///
+    /// Fails with `E_INVALIDARG` if `count` is zero or larger than the page size.
+    pub fn allocate(&mut self, count: u32) -> Result<DescriptorAllocation, HRESULT> {
+        if count == 0 || count > self.pages.page_size() {
+            return Err(winerror::E_INVALIDARG);
+        }
+
+        let (page, index) = match self.pages.allocate(count) {
+            Some(allocation) => allocation,
+            None => {
+                let (heap, hr) = self.device.create_descriptor_heap(
+                    self.pages.page_size(),
+                    self.heap_type,
+                    DescriptorHeapFlags::empty(),
+                    0,
+                );
+                if !winerror::SUCCEEDED(hr) {
+                    return Err(hr);
+                }
+                let slice = DescriptorHeapSlice::from_heap(&self.device, &heap)
+                    .expect("Non shader visible heap");
+                self.heaps.push((heap, slice));
+
+                self.pages.add_page();
+                self.pages.allocate(count).unwrap()
+            }
+        };
+
+        Ok(DescriptorAllocation {
+            page,
+            index,
+            slice: self.heaps[page].1.range(index..index + count),
+        })
+    }
+
+    /// Allocates a single descriptor.
+    pub fn allocate_one(&mut self) -> Result<DescriptorAllocation, HRESULT> {
+        self.allocate(1)
+    }
+
+    /// Returns the descriptors of `allocation` to the allocator.
+    pub fn free(&mut self, allocation: DescriptorAllocation) {
+        self.pages
+            .free(allocation.page, allocation.index, allocation.len());
+    }
+}
+
+/// Table bookkeeping of a `GpuDescriptorRing`, independent of any heap.
+///
+/// Tables are linearly suballocated from a ring of descriptors, and cached tables
+/// are shared by all lookups of the same key until the frame is submitted.
+#[derive(Debug)]
+pub struct DescriptorTableRing {
+    allocator: RingAllocator,
+    cached: HashMap<Vec<usize>, u32>,
+}
+
+impl DescriptorTableRing {
+    /// # Panics
+    ///
+    /// Panics if `size` is zero.
+    pub fn new(size: u32) -> Self {
+        DescriptorTableRing {
+            allocator: RingAllocator::new(size as _),
+            cached: HashMap::new(),
+        }
+    }
+
+    /// Allocates a table of `count` descriptors for the current frame and returns
+    /// its first index.
+    pub fn allocate(&mut self, count: u32) -> Option<u32> {
+        self.allocator
+            .allocate(count as _, 1)
+            .map(|start| start as u32)
+    }
+
+    /// Returns the first index of the table cached with `key` in the current frame,
+    /// or allocates a table of `key.len()` descriptors and caches it.
+    ///
+    /// The flag is true if the table was allocated, and must then be written.
+    pub fn allocate_cached(&mut self, key: &[usize]) -> Option<(u32, bool)> {
+        if let Some(&start) = self.cached.get(key) {
+            return Some((start, false));
+        }
+
+        let start = self.allocate(key.len() as u32)?;
+        self.cached.insert(key.to_vec(), start);
+        Some((start, true))
+    }
+
+    /// Ends the current frame, whose tables stay in use until `fence_value` is
+    /// completed, and clears the cached tables.
+    pub fn submit(&mut self, fence_value: u64) {
+        self.cached.clear();
+        self.allocator.submit(fence_value);
+    }
+
+    /// Recycles the tables of all frames whose fence value is less or equal
+    /// to `completed_value`.
+    pub fn reclaim(&mut self, completed_value: u64) {
+        self.allocator.reclaim(completed_value);
+    }
+}
+
+/// Shader visible descriptor heap, linearly suballocated into descriptor tables
+/// which are recycled once the frame using them has completed.
+///
+/// Tables are filled by copying from non shader visible descriptors. The `_cached`
+/// variants share one table between identical writes within a frame, identifying
+/// tables by the addresses of their source descriptors only: sources written through
+/// them must not be rewritten until the frame is submitted, otherwise later writes
+/// of the frame return the previous contents.
+#[derive(Debug)]
+pub struct GpuDescriptorRing {
+    device: Device,
+    heap: DescriptorHeap,
+    slice: DescriptorHeapSlice<ShaderVisible>,
+    tables: DescriptorTableRing,
+}
+
+impl GpuDescriptorRing {
+    /// Creates a shader visible heap of `size` descriptors of `heap_type`, which must
+    /// be `CbvSrvUav` or `Sampler`.
+    pub fn new(device: Device, heap_type: DescriptorHeapType, size: u32) -> Result<Self, HRESULT> {
+        if size == 0 {
+            return Err(winerror::E_INVALIDARG);
+        }
+        let (heap, hr) =
+            device.create_descriptor_heap(size, heap_type, DescriptorHeapFlags::SHADER_VISIBLE, 0);
+        if !winerror::SUCCEEDED(hr) {
+            return Err(hr);
+        }
+        let slice = DescriptorHeapSlice::from_heap(&device, &heap).expect("Shader visible heap");
+
+        Ok(GpuDescriptorRing {
+            device,
+            heap,
+            slice,
+            tables: DescriptorTableRing::new(size),
+        })
+    }
+
+    /// The heap to bind with `set_descriptor_heaps`.
+    pub fn heap(&self) -> &DescriptorHeap {
+        &self.heap
+    }
+
+    /// Allocates a table of `count` descriptors for the current frame without writing it.
+    pub fn allocate(&mut self, count: u32) -> Option<DescriptorHeapSlice<ShaderVisible>> {
+        let start = self.tables.allocate(count)?;
+        Some(self.slice.range(start..start + count))
+    }
+
+    fn copy_table(&self, table: &DescriptorHeapSlice<ShaderVisible>, sources: &[CpuDescriptor]) {
+        self.device.copy_descriptors(
+            &[table.cpu_handle(0)],
+            &[table.len()],
+            sources,
+            &[],
+            self.slice.heap_type(),
+        );
+    }
+
+    fn copy_range(
+        &self,
+        table: &DescriptorHeapSlice<ShaderVisible>,
+        source: &DescriptorHeapSlice<CpuOnly>,
+    ) {
+        self.device.copy_descriptors_simple(
+            table.len(),
+            table.cpu_handle(0),
+            source.cpu_handle(0),
+            self.slice.heap_type(),
+        );
+    }
+
+    /// Returns the cached table of `key`, allocating it and calling `write` if it
+    /// is new.
+    fn write_cached(
+        &mut self,
+        key: &[usize],
+        write: impl FnOnce(&Self, &DescriptorHeapSlice<ShaderVisible>),
+    ) -> Option<DescriptorHeapSlice<ShaderVisible>> {
+        let (start, is_new) = self.tables.allocate_cached(key)?;
+        let table = self.slice.range(start..start + key.len() as u32);
+        if is_new {
+            write(self, &table);
+        }
+        Some(table)
+    }
+
+    /// Copies the descriptors at `sources` into a new table.
+    ///
+    /// Returns `None` if `sources` is empty or the ring is full.
+    pub fn write_table(
+        &mut self,
+        sources: &[CpuDescriptor],
+    ) -> Option<DescriptorHeapSlice<ShaderVisible>> {
+        if sources.is_empty() {
+            return None;
+        }
+
+        let table = self.allocate(sources.len() as u32)?;
+        self.copy_table(&table, sources);
+        Some(table)
+    }
+
+    /// Like `write_table`, but returns the table already written from the same
+    /// source descriptors in the current frame, if any.
+    pub fn write_table_cached(
+        &mut self,
+        sources: &[CpuDescriptor],
+    ) -> Option<DescriptorHeapSlice<ShaderVisible>> {
+        if sources.is_empty() {
+            return None;
+        }
+
+        let key = sources.iter().map(|handle| handle.ptr).collect::<Vec<_>>();
+        self.write_cached(&key, |ring, table| ring.copy_table(table, sources))
+    }
+
+    /// Copies the contiguous descriptors of `source` into a new table.
+    ///
+    /// Returns `None` if `source` is empty or the ring is full.
+    pub fn write_range(
+        &mut self,
+        source: &DescriptorHeapSlice<CpuOnly>,
+    ) -> Option<DescriptorHeapSlice<ShaderVisible>> {
+        if source.is_empty() {
+            return None;
+        }
+
+        let table = self.allocate(source.len())?;
+        self.copy_range(&table, source);
+        Some(table)
+    }
+
+    /// Like `write_range`, but returns the table already written from the same
+    /// source descriptors in the current frame, if any.
+    pub fn write_range_cached(
+        &mut self,
+        source: &DescriptorHeapSlice<CpuOnly>,
+    ) -> Option<DescriptorHeapSlice<ShaderVisible>> {
+        if source.is_empty() {
+            return None;
+        }
+
+        let key = (0..source.len())
+            .map(|index| source.cpu_handle(index).ptr)
+            .collect::<Vec<_>>();
+        self.write_cached(&key, |ring, table| ring.copy_range(table, source))
+    }
+
+    /// Ends the current frame, whose tables stay in use until `fence_value` is signaled.
+    pub fn submit(&mut self, fence_value: u64) {
+        self.tables.submit(fence_value);
+    }
+
+    /// Recycles the tables of all frames completed by `fence`.
+    pub fn reclaim(&mut self, fence: &Fence) {
+        self.tables.reclaim(fence.get_value());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn allocate_first_fit() {
+        let mut list = FreeList::new(16);
+        for start in (0..16).step_by(4) {
+            assert_eq!(list.allocate(4), Some(start));
+        }
+        list.free(0, 4);
+        list.free(8, 4);
+
+        // Both holes fit, the first one is used.
+        assert_eq!(list.allocate(2), Some(0));
+        // Only the second hole fits.
+        assert_eq!(list.allocate(3), Some(8));
+        assert_eq!(list.allocate(3), None);
+        assert_eq!(list.allocate(2), Some(2));
+        assert_eq!(list.available(), 1);
+    }
+
+    #[test]
+    fn free_coalesces_with_both_neighbours() {
+        let mut list = FreeList::new(12);
+        assert_eq!(list.allocate(4), Some(0));
+        assert_eq!(list.allocate(4), Some(4));
+        assert_eq!(list.allocate(4), Some(8));
+        list.free(0, 4);
+        list.free(8, 4);
+        assert_eq!(list.allocate(8), None);
+
+        list.free(4, 4);
+        assert_eq!(list.available(), 12);
+        assert_eq!(list.allocate(12), Some(0));
+    }
+
+    #[test]
+    fn cached_tables_are_shared_within_a_frame() {
+        let mut ring = DescriptorTableRing::new(16);
+        assert_eq!(ring.allocate_cached(&[10, 11]), Some((0, true)));
+        assert_eq!(ring.allocate_cached(&[20]), Some((2, true)));
+        assert_eq!(ring.allocate_cached(&[10, 11]), Some((0, false)));
+        // Same sources in a different order are a different table.
+        assert_eq!(ring.allocate_cached(&[11, 10]), Some((3, true)));
+        // Uncached tables are never shared.
+        assert_eq!(ring.allocate(2), Some(5));
+        assert_eq!(ring.allocate_cached(&[10, 11]), Some((0, false)));
+    }
+
+    #[test]
+    fn submit_clears_cached_tables() {
+        let mut ring = DescriptorTableRing::new(16);
+        assert_eq!(ring.allocate_cached(&[10, 11]), Some((0, true)));
+        ring.submit(1);
+
+        assert_eq!(ring.allocate_cached(&[10, 11]), Some((2, true)));
+        assert_eq!(ring.allocate_cached(&[10, 11]), Some((2, false)));
+    }
+
+    #[test]
+    fn tables_are_recycled_after_reclaim() {
+        let mut ring = DescriptorTableRing::new(4);
+        assert_eq!(ring.allocate(3), Some(0));
+        ring.submit(1);
+        assert_eq!(ring.allocate(2), None);
+        assert_eq!(ring.allocate_cached(&[10, 11]), None);
+
+        ring.reclaim(1);
+        assert_eq!(ring.allocate_cached(&[10, 11]), Some((0, true)));
+    }
+
+    #[test]
+    #[should_panic(expected = "already free")]
+    fn free_twice_panics() {
+        let mut list = FreeList::new(8);
+        list.allocate(4).unwrap();
+        list.free(0, 4);
+        list.free(2, 2);
+    }
+}
@@ -3,16 +3,102 @@
 use crate::{
     com::ComPtr,
     command_list::{CmdListType, CommandSignature, IndirectArgument},
-    descriptor::{CpuDescriptor, DescriptorHeapFlags, DescriptorHeapType, RenderTargetViewDesc},
+    descriptor::{
+        ConstantBufferViewDesc, CpuDescriptor, DepthStencilViewDesc, DescriptorHeapFlags,
+        DescriptorHeapType, RenderTargetViewDesc, SamplerDesc, ShaderResourceViewDesc,
+        UnorderedAccessViewDesc,
+    },
+    feature::{feature_levels_query, DeviceCapabilities, Feature, ShaderModel, FEATURE_LEVELS},
+    ffi,
     heap::{Heap, HeapFlags, HeapProperties},
-    pso, query, queue, Blob, CachedPSO, CommandAllocator, CommandQueue, D3DResult, DescriptorHeap,
+    pso, query, queue,
+    resource::{CopyableFootprint, ResourceDesc, Subresource},
+    tiled::{PackedMipInfo, ResourceTiling, SubresourceTiling, TileShape},
+    Blob, CachedPSO, CommandAllocator, CommandQueue, D3DResult, DescriptorHeap, FeatureLevel,
     Fence, GraphicsCommandList, NodeMask, PipelineState, QueryHeap, Resource, RootSignature,
-    Shader, TextureAddressMode,
+    Shader, HRESULT,
+};
+use std::{convert::TryFrom, mem, ops::Range, ptr};
+use winapi::{
+    shared::{dxgiformat, winerror},
+    um::d3d12,
+    Interface,
 };
-use std::ops::Range;
-use winapi::{um::d3d12, Interface};
 
 pub type Device = ComPtr<d3d12::ID3D12Device>;
+pub type Device1 = ComPtr<d3d12::ID3D12Device1>;
+pub type Device2 = ComPtr<d3d12::ID3D12Device2>;
+pub type Device3 = ComPtr<ffi::ID3D12Device3>;
+pub type Device4 = ComPtr<ffi::ID3D12Device4>;
+pub type Device5 = ComPtr<ffi::ID3D12Device5>;
+pub type Device6 = ComPtr<ffi::ID3D12Device6>;
+pub type Device7 = ComPtr<ffi::ID3D12Device7>;
+pub type Device8 = ComPtr<ffi::ID3D12Device8>;
+pub type Device9 = ComPtr<ffi::ID3D12Device9>;
+pub type Device10 = ComPtr<ffi::ID3D12Device10>;
+crate::com_inheritance_chain! {
+    #[derive(Debug, Clone, PartialEq, Hash)]
+    pub enum VersionedDevice {
+        Device(d3d12::ID3D12Device), from_device, as_device, device;
+        Device1(d3d12::ID3D12Device1), from_device1, as_device1, unwrap_device1;
+        Device2(d3d12::ID3D12Device2), from_device2, as_device2, unwrap_device2;
+        Device3(ffi::ID3D12Device3), from_device3, as_device3, unwrap_device3;
+        Device4(ffi::ID3D12Device4), from_device4, as_device4, unwrap_device4;
+        Device5(ffi::ID3D12Device5), from_device5, as_device5, unwrap_device5;
+        Device6(ffi::ID3D12Device6), from_device6, as_device6, unwrap_device6;
+        Device7(ffi::ID3D12Device7), from_device7, as_device7, unwrap_device7;
+        Device8(ffi::ID3D12Device8), from_device8, as_device8, unwrap_device8;
+        Device9(ffi::ID3D12Device9), from_device9, as_device9, unwrap_device9;
+        Device10(ffi::ID3D12Device10), from_device10, as_device10, unwrap_device10;
+    }
+}
+
+impl VersionedDevice {
+    /// Wraps `device` as the highest interface version it implements.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `device` is null.
+    pub fn upcast(device: Device) -> Self {
+        assert!(!device.is_null());
+
+        fn cast<I: Interface>(device: &Device) -> Option<ComPtr<I>> {
+            let (cast, hr) = unsafe { device.cast::<I>() };
+            if winerror::SUCCEEDED(hr) {
+                Some(cast)
+            } else {
+                None
+            }
+        }
+
+        // SAFETY: each pointer has been queried for the interface of its variant.
+        unsafe {
+            if let Some(device) = cast(&device) {
+                Self::from_device10(device)
+            } else if let Some(device) = cast(&device) {
+                Self::from_device9(device)
+            } else if let Some(device) = cast(&device) {
+                Self::from_device8(device)
+            } else if let Some(device) = cast(&device) {
+                Self::from_device7(device)
+            } else if let Some(device) = cast(&device) {
+                Self::from_device6(device)
+            } else if let Some(device) = cast(&device) {
+                Self::from_device5(device)
+            } else if let Some(device) = cast(&device) {
+                Self::from_device4(device)
+            } else if let Some(device) = cast(&device) {
+                Self::from_device3(device)
+            } else if let Some(device) = cast(&device) {
+                Self::from_device2(device)
+            } else if let Some(device) = cast(&device) {
+                Self::from_device1(device)
+            } else {
+                Self::from_device(device)
+            }
+        }
+    }
+}
 
 #[cfg(feature = "libloading")]
 impl crate::D3D12Lib {
@@ -41,8 +127,48 @@ impl crate::D3D12Lib {
 
         Ok((device, hr))
     }
+
+    /// Creates a device at `min_feature_level` and queries its highest supported levels.
+    pub fn create_device_with_levels<I: Interface>(
+        &self,
+        adapter: &ComPtr<I>,
+        min_feature_level: crate::FeatureLevel,
+    ) -> Result<Result<(Device, crate::DeviceLevels), HRESULT>, libloading::Error> {
+        let (device, hr) = self.create_device(adapter, min_feature_level)?;
+        if !winerror::SUCCEEDED(hr) {
+            return Ok(Err(hr));
+        }
+
+        Ok(crate::DeviceLevels::new(&device, min_feature_level).map(|levels| (device, levels)))
+    }
+
+    /// Creates a device on `adapter` at the first level of `DEVICE_CREATION_LEVELS`
+    /// it supports and queries its highest supported levels.
+    ///
+    /// If no level succeeds, returns the error of the first level, i.e. the reason
+    /// why no full device could be created.
+    pub fn create_best_device<I: Interface>(
+        &self,
+        adapter: &ComPtr<I>,
+    ) -> Result<Result<(Device, crate::DeviceLevels), HRESULT>, libloading::Error> {
+        let mut first_error = None;
+        for &level in DEVICE_CREATION_LEVELS.iter() {
+            match self.create_device_with_levels(adapter, level)? {
+                Ok(device) => return Ok(Ok(device)),
+                Err(hr) => {
+                    first_error.get_or_insert(hr);
+                }
+            }
+        }
+
+        Ok(Err(first_error.unwrap_or(winerror::E_FAIL)))
+    }
 }
 
+/// Minimum feature levels tried by `create_best_device`, in order: full devices
+/// first, then compute only devices.
+pub const DEVICE_CREATION_LEVELS: [FeatureLevel; 2] = [FeatureLevel::L11_0, FeatureLevel::L1_0Core];
+
 impl Device {
     #[cfg(feature = "implicit-link")]
     pub fn create<I: Interface>(
@@ -62,6 +188,132 @@ impl Device {
         (device, hr)
     }
 
+    /// Creates a device at `min_feature_level` and queries its highest supported levels.
+    #[cfg(feature = "implicit-link")]
+    pub fn create_with_levels<I: Interface>(
+        adapter: ComPtr<I>,
+        min_feature_level: FeatureLevel,
+    ) -> Result<(Self, crate::DeviceLevels), HRESULT> {
+        let (device, hr) = Self::create(adapter, min_feature_level);
+        if !winerror::SUCCEEDED(hr) {
+            return Err(hr);
+        }
+
+        crate::DeviceLevels::new(&device, min_feature_level).map(|levels| (device, levels))
+    }
+
+    /// Creates a device on `adapter` at the first level of `DEVICE_CREATION_LEVELS`
+    /// it supports and queries its highest supported levels.
+    ///
+    /// If no level succeeds, returns the error of the first level, i.e. the reason
+    /// why no full device could be created.
+    #[cfg(feature = "implicit-link")]
+    pub fn create_best_device<I: Interface>(
+        adapter: ComPtr<I>,
+    ) -> Result<(Self, crate::DeviceLevels), HRESULT> {
+        let mut first_error = None;
+        for &level in DEVICE_CREATION_LEVELS.iter() {
+            match Self::create_with_levels(adapter.clone(), level) {
+                Ok(device) => return Ok(device),
+                Err(hr) => {
+                    first_error.get_or_insert(hr);
+                }
+            }
+        }
+
+        Err(first_error.unwrap_or(winerror::E_FAIL))
+    }
+
+    /// Queries the support of `F` with the input of `F::query`.
+    pub fn check_feature<F: Feature>(&self) -> D3DResult<F> {
+        self.check_feature_with(F::query())
+    }
+
+    /// Queries the support of `F` with `data` as input, e.g. the format of
+    /// `D3D12_FEATURE_DATA_FORMAT_SUPPORT`.
+    pub fn check_feature_with<F: Feature>(&self, mut data: F) -> D3DResult<F> {
+        let hr = unsafe {
+            self.CheckFeatureSupport(
+                F::FEATURE,
+                &mut data as *mut F as *mut _,
+                mem::size_of::<F>() as _,
+            )
+        };
+
+        (data, hr)
+    }
+
+    pub fn format_support(
+        &self,
+        format: dxgiformat::DXGI_FORMAT,
+    ) -> D3DResult<d3d12::D3D12_FEATURE_DATA_FORMAT_SUPPORT> {
+        self.check_feature_with(d3d12::D3D12_FEATURE_DATA_FORMAT_SUPPORT {
+            Format: format,
+            Support1: 0,
+            Support2: 0,
+        })
+    }
+
+    /// Returns the number of quality levels for `sample_count` samples of `format`,
+    /// which is zero if the combination is not supported.
+    pub fn multisample_quality_levels(
+        &self,
+        format: dxgiformat::DXGI_FORMAT,
+        sample_count: u32,
+    ) -> D3DResult<u32> {
+        let (data, hr) =
+            self.check_feature_with(d3d12::D3D12_FEATURE_DATA_MULTISAMPLE_QUALITY_LEVELS {
+                Format: format,
+                SampleCount: sample_count,
+                Flags: d3d12::D3D12_MULTISAMPLE_QUALITY_LEVELS_FLAG_NONE,
+                NumQualityLevels: 0,
+            });
+
+        (data.NumQualityLevels, hr)
+    }
+
+    /// Returns the highest supported full feature level, leaving out levels which are
+    /// unknown to the installed runtime.
+    pub fn max_feature_level(&self) -> Result<FeatureLevel, HRESULT> {
+        let mut count = FEATURE_LEVELS.len();
+        loop {
+            let (data, hr) = self.check_feature_with(feature_levels_query(count));
+            if hr == winerror::E_INVALIDARG && count > 1 {
+                count -= 1;
+                continue;
+            }
+            if !winerror::SUCCEEDED(hr) {
+                return Err(hr);
+            }
+            return FeatureLevel::try_from(data.MaxSupportedFeatureLevel)
+                .map_err(|()| winerror::E_UNEXPECTED);
+        }
+    }
+
+    /// Returns the highest supported shader model, retrying with lower models
+    /// on runtimes which don't know about the requested one.
+    pub fn highest_shader_model(&self) -> Result<ShaderModel, HRESULT> {
+        for &model in ShaderModel::ALL.iter() {
+            let (data, hr) = self.check_feature_with(d3d12::D3D12_FEATURE_DATA_SHADER_MODEL {
+                HighestShaderModel: model as _,
+            });
+            if hr == winerror::E_INVALIDARG {
+                continue;
+            }
+            if !winerror::SUCCEEDED(hr) {
+                return Err(hr);
+            }
+            return ShaderModel::try_from(data.HighestShaderModel)
+                .map_err(|()| winerror::E_UNEXPECTED);
+        }
+
+        Err(winerror::E_INVALIDARG)
+    }
+
+    pub fn capabilities(&self) -> Result<DeviceCapabilities, HRESULT> {
+        DeviceCapabilities::new(self)
+    }
+
     pub fn create_heap(
         &self,
         size_in_bytes: u64,
@@ -83,6 +335,154 @@ impl Device {
         (heap, hr)
     }
 
+    pub fn create_committed_resource(
+        &self,
+        properties: HeapProperties,
+        heap_flags: HeapFlags,
+        desc: &ResourceDesc,
+        initial_state: d3d12::D3D12_RESOURCE_STATES,
+        clear_value: Option<&d3d12::D3D12_CLEAR_VALUE>,
+    ) -> D3DResult<Resource> {
+        let mut resource = Resource::null();
+        let clear_value = match clear_value {
+            Some(value) => value as *const _,
+            None => ptr::null(),
+        };
+
+        let hr = unsafe {
+            self.CreateCommittedResource(
+                &properties.0,
+                heap_flags.bits(),
+                &desc.0,
+                initial_state,
+                clear_value,
+                &d3d12::ID3D12Resource::uuidof(),
+                resource.mut_void(),
+            )
+        };
+
+        (resource, hr)
+    }
+
+    /// Creates a resource at `heap_offset` in `heap`, which must be aligned to the
+    /// alignment returned by `get_resource_allocation_info`.
+    pub fn create_placed_resource(
+        &self,
+        heap: &Heap,
+        heap_offset: u64,
+        desc: &ResourceDesc,
+        initial_state: d3d12::D3D12_RESOURCE_STATES,
+        clear_value: Option<&d3d12::D3D12_CLEAR_VALUE>,
+    ) -> D3DResult<Resource> {
+        let mut resource = Resource::null();
+        let clear_value = match clear_value {
+            Some(value) => value as *const _,
+            None => ptr::null(),
+        };
+
+        let hr = unsafe {
+            self.CreatePlacedResource(
+                heap.as_mut_ptr(),
+                heap_offset,
+                &desc.0,
+                initial_state,
+                clear_value,
+                &d3d12::ID3D12Resource::uuidof(),
+                resource.mut_void(),
+            )
+        };
+
+        (resource, hr)
+    }
+
+    /// Returns the size and alignment in bytes of a placed resource of `desc`.
+    pub fn get_resource_allocation_info(
+        &self,
+        visible_mask: NodeMask,
+        desc: &ResourceDesc,
+    ) -> d3d12::D3D12_RESOURCE_ALLOCATION_INFO {
+        unsafe { self.GetResourceAllocationInfo(visible_mask, 1, &desc.0) }
+    }
+
+    /// Creates a tiled resource without memory, whose tiles are mapped to heaps with
+    /// `CommandQueue::update_tile_mappings`. `desc` must use `D3D12_TEXTURE_LAYOUT_64KB_UNDEFINED_SWIZZLE`
+    /// for textures.
+    pub fn create_reserved_resource(
+        &self,
+        desc: &ResourceDesc,
+        initial_state: d3d12::D3D12_RESOURCE_STATES,
+        clear_value: Option<&d3d12::D3D12_CLEAR_VALUE>,
+    ) -> D3DResult<Resource> {
+        let mut resource = Resource::null();
+        let clear_value = match clear_value {
+            Some(value) => value as *const _,
+            None => ptr::null(),
+        };
+
+        let hr = unsafe {
+            self.CreateReservedResource(
+                &desc.0,
+                initial_state,
+                clear_value,
+                &d3d12::ID3D12Resource::uuidof(),
+                resource.mut_void(),
+            )
+        };
+
+        (resource, hr)
+    }
+
+    pub fn get_resource_tiling(&self, resource: &Resource) -> ResourceTiling {
+        let desc = resource.get_desc().0;
+        let array_size = match desc.Dimension {
+            d3d12::D3D12_RESOURCE_DIMENSION_TEXTURE3D => 1,
+            _ => desc.DepthOrArraySize as u32,
+        };
+        let mut num_subresource_tilings = desc.MipLevels as u32 * array_size;
+
+        let mut num_tiles = 0;
+        let mut packed_mip_info: d3d12::D3D12_PACKED_MIP_INFO = unsafe { mem::zeroed() };
+        let mut tile_shape: d3d12::D3D12_TILE_SHAPE = unsafe { mem::zeroed() };
+        let mut tilings: Vec<d3d12::D3D12_SUBRESOURCE_TILING> =
+            vec![unsafe { mem::zeroed() }; num_subresource_tilings as usize];
+        unsafe {
+            self.GetResourceTiling(
+                resource.as_mut_ptr(),
+                &mut num_tiles,
+                &mut packed_mip_info,
+                &mut tile_shape,
+                &mut num_subresource_tilings,
+                0,
+                tilings.as_mut_ptr(),
+            )
+        };
+        tilings.truncate(num_subresource_tilings as usize);
+
+        ResourceTiling {
+            num_tiles,
+            packed_mip_info: PackedMipInfo {
+                num_standard_mips: packed_mip_info.NumStandardMips,
+                num_packed_mips: packed_mip_info.NumPackedMips,
+                num_tiles: packed_mip_info.NumTilesForPackedMips,
+                start_tile: packed_mip_info.StartTileIndexInOverallResource,
+            },
+            tile_shape: TileShape {
+                width: tile_shape.WidthInTexels,
+                height: tile_shape.HeightInTexels,
+                depth: tile_shape.DepthInTexels,
+            },
+            subresource_tilings: tilings
+                .iter()
+                .map(|tiling| SubresourceTiling {
+                    width_in_tiles: tiling.WidthInTiles,
+                    height_in_tiles: tiling.HeightInTiles,
+                    depth_in_tiles: tiling.DepthInTiles,
+                    start_tile: tiling.StartTileIndexInOverallResource,
+                })
+                .collect(),
+        }
+    }
+
     pub fn create_command_allocator(&self, list_type: CmdListType) -> D3DResult<CommandAllocator> {
         let mut allocator = CommandAllocator::null();
         let hr = unsafe {
@@ -152,6 +552,95 @@ impl Device {
         unsafe { self.GetDescriptorHandleIncrementSize(heap_type as _) }
     }
 
+    /// Returns the layout of `subresources` when placed in a buffer at `base_offset`,
+    /// together with the total size in bytes.
+    pub fn get_copyable_footprints(
+        &self,
+        desc: &ResourceDesc,
+        subresources: Range<Subresource>,
+        base_offset: u64,
+    ) -> (Vec<CopyableFootprint>, u64) {
+        let count = (subresources.end - subresources.start) as usize;
+        let mut layouts = Vec::with_capacity(count);
+        let mut num_rows = Vec::with_capacity(count);
+        let mut row_sizes = Vec::with_capacity(count);
+        let mut total_bytes = 0;
+
+        unsafe {
+            self.GetCopyableFootprints(
+                &desc.0,
+                subresources.start,
+                count as _,
+                base_offset,
+                layouts.as_mut_ptr(),
+                num_rows.as_mut_ptr(),
+                row_sizes.as_mut_ptr(),
+                &mut total_bytes,
+            );
+            layouts.set_len(count);
+            num_rows.set_len(count);
+            row_sizes.set_len(count);
+        }
+
+        let footprints = layouts
+            .into_iter()
+            .zip(num_rows)
+            .zip(row_sizes)
+            .map(|((layout, num_rows), row_size)| CopyableFootprint {
+                layout,
+                num_rows,
+                row_size,
+            })
+            .collect();
+
+        (footprints, total_bytes)
+    }
+
+    /// Copies descriptors between arbitrary ranges.
+    ///
+    /// Empty `dst_sizes` or `src_sizes` denote ranges of a single descriptor each.
+    pub fn copy_descriptors(
+        &self,
+        dst_starts: &[CpuDescriptor],
+        dst_sizes: &[u32],
+        src_starts: &[CpuDescriptor],
+        src_sizes: &[u32],
+        heap_type: DescriptorHeapType,
+    ) {
+        debug_assert!(dst_sizes.is_empty() || dst_sizes.len() == dst_starts.len());
+        debug_assert!(src_sizes.is_empty() || src_sizes.len() == src_starts.len());
+        let sizes = |sizes: &[u32]| {
+            if sizes.is_empty() {
+                ptr::null()
+            } else {
+                sizes.as_ptr()
+            }
+        };
+        unsafe {
+            self.CopyDescriptors(
+                dst_starts.len() as _,
+                dst_starts.as_ptr(),
+                sizes(dst_sizes),
+                src_starts.len() as _,
+                src_starts.as_ptr(),
+                sizes(src_sizes),
+                heap_type as _,
+            );
+        }
+    }
+
+    pub fn copy_descriptors_simple(
+        &self,
+        count: u32,
+        dst_start: CpuDescriptor,
+        src_start: CpuDescriptor,
+        heap_type: DescriptorHeapType,
+    ) {
+        unsafe {
+            self.CopyDescriptorsSimple(count, dst_start, src_start, heap_type as _);
+        }
+    }
+
     pub fn create_graphics_command_list(
         &self,
         list_type: CmdListType,
@@ -241,32 +730,9 @@ impl Device {
         (pipeline, hr)
     }
 
-    pub fn create_sampler(
-        &self,
-        sampler: CpuDescriptor,
-        filter: d3d12::D3D12_FILTER,
-        address_mode: TextureAddressMode,
-        mip_lod_bias: f32,
-        max_anisotropy: u32,
-        comparison_op: d3d12::D3D12_COMPARISON_FUNC,
-        border_color: [f32; 4],
-        lod: Range<f32>,
-    ) {
-        let desc = d3d12::D3D12_SAMPLER_DESC {
-            Filter: filter,
-            AddressU: address_mode[0],
-            AddressV: address_mode[1],
-            AddressW: address_mode[2],
-            MipLODBias: mip_lod_bias,
-            MaxAnisotropy: max_anisotropy,
-            ComparisonFunc: comparison_op,
-            BorderColor: border_color,
-            MinLOD: lod.start,
-            MaxLOD: lod.end,
-        };
-
+    pub fn create_sampler(&self, sampler: CpuDescriptor, desc: &SamplerDesc) {
         unsafe {
-            self.CreateSampler(&desc, sampler);
+            self.CreateSampler(&desc.0, sampler);
         }
     }
 
@@ -327,6 +793,57 @@ impl Device {
         }
     }
 
+    pub fn create_depth_stencil_view(
+        &self,
+        resource: Resource,
+        desc: &DepthStencilViewDesc,
+        descriptor: CpuDescriptor,
+    ) {
+        unsafe {
+            self.CreateDepthStencilView(resource.as_mut_ptr(), &desc.0 as *const _, descriptor);
+        }
+    }
+
+    pub fn create_shader_resource_view(
+        &self,
+        resource: Resource,
+        desc: &ShaderResourceViewDesc,
+        descriptor: CpuDescriptor,
+    ) {
+        unsafe {
+            self.CreateShaderResourceView(resource.as_mut_ptr(), &desc.0 as *const _, descriptor);
+        }
+    }
+
+    /// `counter_resource` holds the hidden counter of append and consume structured buffers
+    /// and may be null.
+    pub fn create_unordered_access_view(
+        &self,
+        resource: Resource,
+        counter_resource: Resource,
+        desc: &UnorderedAccessViewDesc,
+        descriptor: CpuDescriptor,
+    ) {
+        unsafe {
+            self.CreateUnorderedAccessView(
+                resource.as_mut_ptr(),
+                counter_resource.as_mut_ptr(),
+                &desc.0 as *const _,
+                descriptor,
+            );
+        }
+    }
+
+    pub fn create_constant_buffer_view(
+        &self,
+        desc: &ConstantBufferViewDesc,
+        descriptor: CpuDescriptor,
+    ) {
+        unsafe {
+            self.CreateConstantBufferView(&desc.0 as *const _, descriptor);
+        }
+    }
+
     // TODO: interface not complete
     pub fn create_fence(&self, initial: u64) -> D3DResult<Fence> {
         let mut fence = Fence::null();
@@ -1,9 +1,13 @@
-use crate::{com::ComPtr, D3DResult, Resource, SampleDesc, HRESULT};
-use std::ptr;
+use crate::{
+    com::ComPtr, CommandQueue, D3DResult, Event, Format, NodeMask, Resource, SampleDesc, HRESULT,
+};
+use std::{fmt, mem, ptr};
 use winapi::{
     shared::{
-        dxgi, dxgi1_2, dxgi1_3, dxgi1_4, dxgi1_5, dxgi1_6, dxgiformat, dxgitype, minwindef::TRUE,
+        dxgi, dxgi1_2, dxgi1_3, dxgi1_4, dxgi1_5, dxgi1_6, dxgiformat, dxgitype,
+        minwindef::{BOOL, FALSE, TRUE},
         windef::HWND,
+        winerror,
     },
     um::{d3d12, dxgidebug, unknwnbase::IUnknown, winnt::HANDLE},
     Interface,
@@ -83,6 +87,7 @@ pub type SwapChain = ComPtr<dxgi::IDXGISwapChain>;
 pub type SwapChain1 = ComPtr<dxgi1_2::IDXGISwapChain1>;
 pub type SwapChain2 = ComPtr<dxgi1_3::IDXGISwapChain2>;
 pub type SwapChain3 = ComPtr<dxgi1_4::IDXGISwapChain3>;
+pub type SwapChain4 = ComPtr<dxgi1_5::IDXGISwapChain4>;
 crate::com_inheritance_chain! {
     #[derive(Debug, Clone, PartialEq, Hash)]
     pub enum DxgiSwapchain {
@@ -90,6 +95,7 @@ crate::com_inheritance_chain! {
         SwapChain1(dxgi1_2::IDXGISwapChain1), from_swap_chain1, as_swap_chain1, unwrap_swap_chain1;
         SwapChain2(dxgi1_3::IDXGISwapChain2), from_swap_chain2, as_swap_chain2, unwrap_swap_chain2;
         SwapChain3(dxgi1_4::IDXGISwapChain3), from_swap_chain3, as_swap_chain3, unwrap_swap_chain3;
+        SwapChain4(dxgi1_5::IDXGISwapChain4), from_swap_chain4, as_swap_chain4, unwrap_swap_chain4;
     }
 }
 
@@ -175,21 +181,184 @@ impl DxgiLib {
     }
 }
 
-// TODO: strong types
+bitflags::bitflags! {
+    #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    pub struct BufferUsage: u32 {
+        const SHADER_INPUT = dxgitype::DXGI_USAGE_SHADER_INPUT;
+        const RENDER_TARGET_OUTPUT = dxgitype::DXGI_USAGE_RENDER_TARGET_OUTPUT;
+        const BACK_BUFFER = dxgitype::DXGI_USAGE_BACK_BUFFER;
+        const SHARED = dxgitype::DXGI_USAGE_SHARED;
+        const READ_ONLY = dxgitype::DXGI_USAGE_READ_ONLY;
+        const DISCARD_ON_PRESENT = dxgitype::DXGI_USAGE_DISCARD_ON_PRESENT;
+        const UNORDERED_ACCESS = dxgitype::DXGI_USAGE_UNORDERED_ACCESS;
+    }
+}
+
+bitflags::bitflags! {
+    #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    pub struct SwapChainFlags: u32 {
+        const NONPREROTATED = dxgi::DXGI_SWAP_CHAIN_FLAG_NONPREROTATED;
+        /// Allows fullscreen transitions to change the display mode.
+        const ALLOW_MODE_SWITCH = dxgi::DXGI_SWAP_CHAIN_FLAG_ALLOW_MODE_SWITCH;
+        const GDI_COMPATIBLE = dxgi::DXGI_SWAP_CHAIN_FLAG_GDI_COMPATIBLE;
+        const RESTRICTED_CONTENT = dxgi::DXGI_SWAP_CHAIN_FLAG_RESTRICTED_CONTENT;
+        const RESTRICT_SHARED_RESOURCE_DRIVER = dxgi::DXGI_SWAP_CHAIN_FLAG_RESTRICT_SHARED_RESOURCE_DRIVER;
+        const DISPLAY_ONLY = dxgi::DXGI_SWAP_CHAIN_FLAG_DISPLAY_ONLY;
+        /// Enables `SwapChain2::get_frame_latency_waitable_object`.
+        const FRAME_LATENCY_WAITABLE_OBJECT = dxgi::DXGI_SWAP_CHAIN_FLAG_FRAME_LATENCY_WAITABLE_OBJECT;
+        const FOREGROUND_LAYER = dxgi::DXGI_SWAP_CHAIN_FLAG_FOREGROUND_LAYER;
+        const FULLSCREEN_VIDEO = dxgi::DXGI_SWAP_CHAIN_FLAG_FULLSCREEN_VIDEO;
+        const YUV_VIDEO = dxgi::DXGI_SWAP_CHAIN_FLAG_YUV_VIDEO;
+        const HW_PROTECTED = dxgi::DXGI_SWAP_CHAIN_FLAG_HW_PROTECTED;
+        /// Allows presenting with `SwapChainPresentFlags::DXGI_PRESENT_ALLOW_TEARING`
+        /// for variable refresh rate displays.
+        const ALLOW_TEARING = dxgi::DXGI_SWAP_CHAIN_FLAG_ALLOW_TEARING;
+    }
+}
+
+/// Maximum number of buffers of a swapchain.
+pub const MAX_SWAP_CHAIN_BUFFERS: u32 = dxgi::DXGI_MAX_SWAP_CHAIN_BUFFERS;
+
+/// Reason a `SwapchainDesc` is rejected by `SwapchainDesc::validate`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum SwapchainDescError {
+    /// The buffer count is outside of `1..=MAX_SWAP_CHAIN_BUFFERS`, or below 2 with
+    /// a flip model swap effect.
+    BufferCount(u32),
+    /// Flip model swap effects don't support multisampled buffers.
+    FlipModelMultisampling,
+    /// The format is not supported by flip model swap effects.
+    FlipModelFormat(Format),
+    /// `SwapChainFlags::ALLOW_TEARING` requires a flip model swap effect.
+    TearingWithoutFlipModel,
+}
+
+impl fmt::Display for SwapchainDescError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SwapchainDescError::BufferCount(count) => write!(f, "invalid buffer count {}", count),
+            SwapchainDescError::FlipModelMultisampling => {
+                write!(f, "flip model swapchains can't be multisampled")
+            }
+            SwapchainDescError::FlipModelFormat(format) => {
+                write!(
+                    f,
+                    "format {} is not supported by flip model swapchains",
+                    format
+                )
+            }
+            SwapchainDescError::TearingWithoutFlipModel => {
+                write!(f, "tearing requires a flip model swapchain")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SwapchainDescError {}
+
 pub struct SwapchainDesc {
     pub width: u32,
     pub height: u32,
-    pub format: dxgiformat::DXGI_FORMAT,
+    pub format: Format,
     pub stereo: bool,
     pub sample: SampleDesc,
-    pub buffer_usage: dxgitype::DXGI_USAGE,
+    pub buffer_usage: BufferUsage,
     pub buffer_count: u32,
     pub scaling: Scaling,
     pub swap_effect: SwapEffect,
     pub alpha_mode: AlphaMode,
-    pub flags: u32,
+    pub flags: SwapChainFlags,
 }
 impl SwapchainDesc {
+    /// Flip model swapchain of `buffer_count` render target buffers whose contents
+    /// are discarded on present, which is the recommended model.
+    ///
+    /// ```rust
+    /// # use winapi::shared::dxgiformat;
+    /// # use d3d12::{SwapChainFlags, SwapchainDesc, SwapchainDescError};
+    /// let desc = SwapchainDesc::flip_discard(1920, 1080, dxgiformat::DXGI_FORMAT_B8G8R8A8_UNORM, 3)
+    ///     .with_flags(SwapChainFlags::ALLOW_TEARING);
+    /// assert_eq!(desc.validate(), Ok(()));
+    ///
+    /// let desc = SwapchainDesc::flip_discard(1920, 1080, dxgiformat::DXGI_FORMAT_B8G8R8A8_UNORM, 17);
+    /// assert_eq!(desc.validate(), Err(SwapchainDescError::BufferCount(17)));
+    /// ```
+    pub fn flip_discard(width: u32, height: u32, format: Format, buffer_count: u32) -> Self {
+        SwapchainDesc {
+            width,
+            height,
+            format,
+            stereo: false,
+            sample: SampleDesc {
+                count: 1,
+                quality: 0,
+            },
+            buffer_usage: BufferUsage::RENDER_TARGET_OUTPUT,
+            buffer_count,
+            scaling: Scaling::Stretch,
+            swap_effect: SwapEffect::FlipDiscard,
+            alpha_mode: AlphaMode::Ignore,
+            flags: SwapChainFlags::empty(),
+        }
+    }
+
+    /// Flip discard swapchain with three buffers.
+    pub fn flip_discard_triple_buffered(width: u32, height: u32, format: Format) -> Self {
+        Self::flip_discard(width, height, format, 3)
+    }
+
+    /// Flip model swapchain of `buffer_count` buffers whose contents are kept
+    /// on present.
+    pub fn flip_sequential(width: u32, height: u32, format: Format, buffer_count: u32) -> Self {
+        SwapchainDesc {
+            swap_effect: SwapEffect::FlipSequential,
+            ..Self::flip_discard(width, height, format, buffer_count)
+        }
+    }
+
+    pub fn with_flags(mut self, flags: SwapChainFlags) -> Self {
+        self.flags = flags;
+        self
+    }
+
+    pub fn with_buffer_usage(mut self, buffer_usage: BufferUsage) -> Self {
+        self.buffer_usage = buffer_usage;
+        self
+    }
+
+    pub fn is_flip_model(&self) -> bool {
+        matches!(
+            self.swap_effect,
+            SwapEffect::FlipDiscard | SwapEffect::FlipSequential
+        )
+    }
+
+    /// Checks the combinations of swap effect, sampling, buffer count, format
+    /// and flags which DXGI rejects.
+    pub fn validate(&self) -> Result<(), SwapchainDescError> {
+        let min_buffers = if self.is_flip_model() { 2 } else { 1 };
+        if self.buffer_count < min_buffers || self.buffer_count > MAX_SWAP_CHAIN_BUFFERS {
+            return Err(SwapchainDescError::BufferCount(self.buffer_count));
+        }
+
+        if self.is_flip_model() {
+            if self.sample.count > 1 {
+                return Err(SwapchainDescError::FlipModelMultisampling);
+            }
+            match self.format {
+                dxgiformat::DXGI_FORMAT_R16G16B16A16_FLOAT
+                | dxgiformat::DXGI_FORMAT_B8G8R8A8_UNORM
+                | dxgiformat::DXGI_FORMAT_R8G8B8A8_UNORM
+                | dxgiformat::DXGI_FORMAT_R10G10B10A2_UNORM => {}
+                format => return Err(SwapchainDescError::FlipModelFormat(format)),
+            }
+        } else if self.flags.contains(SwapChainFlags::ALLOW_TEARING) {
+            return Err(SwapchainDescError::TearingWithoutFlipModel);
+        }
+
+        Ok(())
+    }
+
     pub fn to_desc1(&self) -> dxgi1_2::DXGI_SWAP_CHAIN_DESC1 {
         dxgi1_2::DXGI_SWAP_CHAIN_DESC1 {
             AlphaMode: self.alpha_mode as _,
@@ -197,8 +366,8 @@ impl SwapchainDesc {
             Width: self.width,
             Height: self.height,
             Format: self.format,
-            Flags: self.flags,
-            BufferUsage: self.buffer_usage,
+            Flags: self.flags.bits(),
+            BufferUsage: self.buffer_usage.bits(),
             SampleDesc: dxgitype::DXGI_SAMPLE_DESC {
                 Count: self.sample.count,
                 Quality: self.sample.quality,
@@ -208,6 +377,44 @@ impl SwapchainDesc {
             SwapEffect: self.swap_effect as _,
         }
     }
+
+    /// Returns `None` if the description holds a scaling, swap effect or alpha
+    /// mode unknown to this crate.
+    pub fn from_desc1(desc: &dxgi1_2::DXGI_SWAP_CHAIN_DESC1) -> Option<Self> {
+        Some(SwapchainDesc {
+            width: desc.Width,
+            height: desc.Height,
+            format: desc.Format,
+            stereo: desc.Stereo != FALSE,
+            sample: SampleDesc {
+                count: desc.SampleDesc.Count,
+                quality: desc.SampleDesc.Quality,
+            },
+            buffer_usage: BufferUsage::from_bits_retain(desc.BufferUsage),
+            buffer_count: desc.BufferCount,
+            scaling: match desc.Scaling {
+                dxgi1_2::DXGI_SCALING_STRETCH => Scaling::Stretch,
+                dxgi1_2::DXGI_SCALING_NONE => Scaling::Identity,
+                dxgi1_2::DXGI_SCALING_ASPECT_RATIO_STRETCH => Scaling::Aspect,
+                _ => return None,
+            },
+            swap_effect: match desc.SwapEffect {
+                dxgi::DXGI_SWAP_EFFECT_DISCARD => SwapEffect::Discard,
+                dxgi::DXGI_SWAP_EFFECT_SEQUENTIAL => SwapEffect::Sequential,
+                dxgi::DXGI_SWAP_EFFECT_FLIP_DISCARD => SwapEffect::FlipDiscard,
+                dxgi::DXGI_SWAP_EFFECT_FLIP_SEQUENTIAL => SwapEffect::FlipSequential,
+                _ => return None,
+            },
+            alpha_mode: match desc.AlphaMode {
+                dxgi1_2::DXGI_ALPHA_MODE_UNSPECIFIED => AlphaMode::Unspecified,
+                dxgi1_2::DXGI_ALPHA_MODE_PREMULTIPLIED => AlphaMode::Premultiplied,
+                dxgi1_2::DXGI_ALPHA_MODE_STRAIGHT => AlphaMode::Straight,
+                dxgi1_2::DXGI_ALPHA_MODE_IGNORE => AlphaMode::Ignore,
+                _ => return None,
+            },
+            flags: SwapChainFlags::from_bits_retain(desc.Flags),
+        })
+    }
 }
 
 impl Factory1 {
@@ -223,7 +430,7 @@ impl Factory1 {
         let mut desc = dxgi::DXGI_SWAP_CHAIN_DESC {
             BufferDesc: dxgitype::DXGI_MODE_DESC {
                 Width: desc.width,
-                Height: desc.width,
+                Height: desc.height,
                 RefreshRate: dxgitype::DXGI_RATIONAL {
                     Numerator: 1,
                     Denominator: 60,
@@ -236,12 +443,12 @@ impl Factory1 {
                 Count: desc.sample.count,
                 Quality: desc.sample.quality,
             },
-            BufferUsage: desc.buffer_usage,
+            BufferUsage: desc.buffer_usage.bits(),
             BufferCount: desc.buffer_count,
             OutputWindow: hwnd,
             Windowed: TRUE,
             SwapEffect: desc.swap_effect as _,
-            Flags: desc.flags,
+            Flags: desc.flags.bits(),
         };
 
         let mut swapchain = SwapChain::null();
@@ -379,10 +586,231 @@ impl SwapChain {
     pub fn present_flags(&self, interval: u32, flags: SwapChainPresentFlags) -> HRESULT {
         unsafe { self.Present(interval, flags.bits()) }
     }
+
+    /// Resizes the buffers, keeping the buffer count if `buffer_count` is zero and
+    /// the format if `format` is `DXGI_FORMAT_UNKNOWN`.
+    ///
+    /// All references to the buffers must have been released.
+    pub fn resize_buffers(
+        &self,
+        buffer_count: u32,
+        width: u32,
+        height: u32,
+        format: Format,
+        flags: SwapChainFlags,
+    ) -> HRESULT {
+        unsafe { self.ResizeBuffers(buffer_count, width, height, format, flags.bits()) }
+    }
+
+    /// Enters or leaves fullscreen on the output containing most of the window.
+    pub fn set_fullscreen_state(&self, fullscreen: bool) -> HRESULT {
+        unsafe { self.SetFullscreenState(fullscreen as BOOL, ptr::null_mut()) }
+    }
+
+    pub fn get_fullscreen_state(&self) -> D3DResult<bool> {
+        let mut fullscreen = FALSE;
+        let hr = unsafe { self.GetFullscreenState(&mut fullscreen, ptr::null_mut()) };
+
+        (fullscreen != FALSE, hr)
+    }
+}
+
+impl SwapChain1 {
+    /// Fails with `E_UNEXPECTED` if the description can't be represented by a
+    /// `SwapchainDesc`.
+    pub fn get_desc1(&self) -> Result<SwapchainDesc, HRESULT> {
+        let mut desc: dxgi1_2::DXGI_SWAP_CHAIN_DESC1 = unsafe { mem::zeroed() };
+        let hr = unsafe { self.GetDesc1(&mut desc) };
+        if !winerror::SUCCEEDED(hr) {
+            return Err(hr);
+        }
+
+        SwapchainDesc::from_desc1(&desc).ok_or(winerror::E_UNEXPECTED)
+    }
+}
+
+impl SwapChain2 {
+    /// Sets the number of frames which can be queued for presentation.
+    pub fn set_maximum_frame_latency(&self, max_latency: u32) -> HRESULT {
+        unsafe { self.SetMaximumFrameLatency(max_latency) }
+    }
+
+    pub fn get_maximum_frame_latency(&self) -> D3DResult<u32> {
+        let mut max_latency = 0;
+        let hr = unsafe { self.GetMaximumFrameLatency(&mut max_latency) };
+
+        (max_latency, hr)
+    }
+
+    /// Returns the event signaled when a frame can be queued, waited on before
+    /// rendering each frame. Requires `SwapChainFlags::FRAME_LATENCY_WAITABLE_OBJECT`.
+    ///
+    /// The handle must be closed by the caller.
+    pub fn get_frame_latency_waitable_object(&self) -> Event {
+        Event(unsafe { self.GetFrameLatencyWaitableObject() })
+    }
+}
+
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ColorSpace {
+    /// sRGB, `DXGI_COLOR_SPACE_RGB_FULL_G22_NONE_P709`.
+    Srgb = dxgitype::DXGI_COLOR_SPACE_RGB_FULL_G22_NONE_P709,
+    /// Linear scRGB, `DXGI_COLOR_SPACE_RGB_FULL_G10_NONE_P709`.
+    ScRgb = dxgitype::DXGI_COLOR_SPACE_RGB_FULL_G10_NONE_P709,
+    /// HDR10 with the ST.2084 curve and BT.2020 primaries,
+    /// `DXGI_COLOR_SPACE_RGB_FULL_G2084_NONE_P2020`.
+    Hdr10 = dxgitype::DXGI_COLOR_SPACE_RGB_FULL_G2084_NONE_P2020,
+    /// Gamma 2.2 with BT.2020 primaries, `DXGI_COLOR_SPACE_RGB_FULL_G22_NONE_P2020`.
+    Bt2020 = dxgitype::DXGI_COLOR_SPACE_RGB_FULL_G22_NONE_P2020,
+}
+
+bitflags::bitflags! {
+    #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    pub struct ColorSpaceSupport: u32 {
+        const PRESENT = dxgi1_4::DXGI_SWAP_CHAIN_COLOR_SPACE_SUPPORT_FLAG_PRESENT;
+        const OVERLAY_PRESENT = dxgi1_4::DXGI_SWAP_CHAIN_COLOR_SPACE_SUPPORT_FLAG_OVERLAY_PRESENT;
+    }
 }
 
 impl SwapChain3 {
     pub fn get_current_back_buffer_index(&self) -> u32 {
         unsafe { self.GetCurrentBackBufferIndex() }
     }
+
+    /// Resizes the buffers like `SwapChain::resize_buffers`, creating buffer `i` on
+    /// the node of `node_masks[i]` and presenting it from `queues[i]`.
+    ///
+    /// Fails with `E_INVALIDARG` unless there is one node mask and one queue per buffer.
+    pub fn resize_buffers1(
+        &self,
+        buffer_count: u32,
+        width: u32,
+        height: u32,
+        format: Format,
+        flags: SwapChainFlags,
+        node_masks: &[NodeMask],
+        queues: &[CommandQueue],
+    ) -> HRESULT {
+        if node_masks.len() != queues.len()
+            || (buffer_count != 0 && queues.len() != buffer_count as usize)
+        {
+            return winerror::E_INVALIDARG;
+        }
+        let mut queues = queues
+            .iter()
+            .map(|queue| queue.as_mut_ptr() as *mut IUnknown)
+            .collect::<Vec<_>>();
+        unsafe {
+            self.ResizeBuffers1(
+                buffer_count,
+                width,
+                height,
+                format,
+                flags.bits(),
+                node_masks.as_ptr(),
+                queues.as_mut_ptr(),
+            )
+        }
+    }
+
+    pub fn check_color_space_support(
+        &self,
+        color_space: ColorSpace,
+    ) -> D3DResult<ColorSpaceSupport> {
+        let mut support = 0;
+        let hr = unsafe { self.CheckColorSpaceSupport(color_space as _, &mut support) };
+
+        (ColorSpaceSupport::from_bits_retain(support), hr)
+    }
+
+    pub fn set_color_space1(&self, color_space: ColorSpace) -> HRESULT {
+        unsafe { self.SetColorSpace1(color_space as _) }
+    }
+}
+
+/// HDR10 mastering display and content light levels, sent to the display with
+/// `SwapChain4::set_hdr_metadata`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Hdr10Metadata {
+    /// CIE 1931 xy chromaticity of the red primary of the mastering display.
+    pub red_primary: [f32; 2],
+    pub green_primary: [f32; 2],
+    pub blue_primary: [f32; 2],
+    pub white_point: [f32; 2],
+    /// In nits.
+    pub max_mastering_luminance: f32,
+    /// In nits.
+    pub min_mastering_luminance: f32,
+    /// Brightest pixel of the content, in nits.
+    pub max_content_light_level: u16,
+    /// Brightest frame average of the content, in nits.
+    pub max_frame_average_light_level: u16,
+}
+
+impl Hdr10Metadata {
+    /// Metadata of a mastering display with BT.2020 primaries and a D65 white point.
+    ///
+    /// ```rust
+    /// # use d3d12::Hdr10Metadata;
+    /// let raw = Hdr10Metadata::bt2020(1000.0, 0.001, 1000, 400).to_raw();
+    /// assert_eq!(raw.RedPrimary, [35400, 14600]);
+    /// assert_eq!(raw.WhitePoint, [15635, 16450]);
+    /// assert_eq!((raw.MaxMasteringLuminance, raw.MinMasteringLuminance), (1000, 10));
+    /// ```
+    pub fn bt2020(
+        max_mastering_luminance: f32,
+        min_mastering_luminance: f32,
+        max_content_light_level: u16,
+        max_frame_average_light_level: u16,
+    ) -> Self {
+        Hdr10Metadata {
+            red_primary: [0.708, 0.292],
+            green_primary: [0.170, 0.797],
+            blue_primary: [0.131, 0.046],
+            white_point: [0.3127, 0.3290],
+            max_mastering_luminance,
+            min_mastering_luminance,
+            max_content_light_level,
+            max_frame_average_light_level,
+        }
+    }
+
+    /// Converts to the DXGI units, which are 0.00002 for chromaticities, nits for
+    /// the maximum mastering luminance and 0.0001 nits for the minimum.
+    pub fn to_raw(&self) -> dxgi1_5::DXGI_HDR_METADATA_HDR10 {
+        let chromaticity =
+            |[x, y]: [f32; 2]| [(x * 50000.0).round() as u16, (y * 50000.0).round() as u16];
+        dxgi1_5::DXGI_HDR_METADATA_HDR10 {
+            RedPrimary: chromaticity(self.red_primary),
+            GreenPrimary: chromaticity(self.green_primary),
+            BluePrimary: chromaticity(self.blue_primary),
+            WhitePoint: chromaticity(self.white_point),
+            MaxMasteringLuminance: self.max_mastering_luminance.round() as u32,
+            MinMasteringLuminance: (self.min_mastering_luminance * 10000.0).round() as u32,
+            MaxContentLightLevel: self.max_content_light_level,
+            MaxFrameAverageLightLevel: self.max_frame_average_light_level,
+        }
+    }
+}
+
+impl SwapChain4 {
+    /// Sets the HDR10 metadata of the presented content, or clears it with `None`.
+    pub fn set_hdr_metadata(&self, metadata: Option<&Hdr10Metadata>) -> HRESULT {
+        match metadata {
+            Some(metadata) => {
+                let mut raw = metadata.to_raw();
+                unsafe {
+                    self.SetHDRMetaData(
+                        dxgi1_5::DXGI_HDR_METADATA_TYPE_HDR10,
+                        mem::size_of_val(&raw) as _,
+                        &mut raw as *mut _ as *mut _,
+                    )
+                }
+            }
+            None => unsafe {
+                self.SetHDRMetaData(dxgi1_5::DXGI_HDR_METADATA_TYPE_NONE, 0, ptr::null_mut())
+            },
+        }
+    }
 }
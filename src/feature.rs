@@ -0,0 +1,383 @@
+//! Device feature support
+
+use crate::{ffi, Device, FeatureLevel, RootSignatureVersion, HRESULT};
+use std::{convert::TryFrom, mem};
+use winapi::{
+    shared::{minwindef::FALSE, winerror},
+    um::{d3d12, d3dcommon},
+};
+
+/// Data structure of a `D3D12_FEATURE`, queried with `Device::check_feature`.
+///
+/// # Safety
+///
+/// `Self` must be the data structure `CheckFeatureSupport` expects for `FEATURE`.
+pub unsafe trait Feature: Copy {
+    const FEATURE: d3d12::D3D12_FEATURE;
+
+    /// Input passed by `Device::check_feature`, zeroed by default.
+    ///
+    /// Features which require a format or sample count as input need to be
+    /// queried with `Device::check_feature_with` instead.
+    fn query() -> Self {
+        unsafe { mem::zeroed() }
+    }
+}
+
+macro_rules! impl_feature {
+    ($($data:ty => $feature:expr,)*) => {
+        $(
+            unsafe impl Feature for $data {
+                const FEATURE: d3d12::D3D12_FEATURE = $feature;
+            }
+        )*
+    };
+}
+
+impl_feature! {
+    d3d12::D3D12_FEATURE_DATA_D3D12_OPTIONS => d3d12::D3D12_FEATURE_D3D12_OPTIONS,
+    d3d12::D3D12_FEATURE_DATA_D3D12_OPTIONS1 => d3d12::D3D12_FEATURE_D3D12_OPTIONS1,
+    d3d12::D3D12_FEATURE_DATA_D3D12_OPTIONS2 => d3d12::D3D12_FEATURE_D3D12_OPTIONS2,
+    ffi::D3D12_FEATURE_DATA_D3D12_OPTIONS3 => ffi::D3D12_FEATURE_D3D12_OPTIONS3,
+    ffi::D3D12_FEATURE_DATA_D3D12_OPTIONS4 => ffi::D3D12_FEATURE_D3D12_OPTIONS4,
+    ffi::D3D12_FEATURE_DATA_D3D12_OPTIONS5 => ffi::D3D12_FEATURE_D3D12_OPTIONS5,
+    ffi::D3D12_FEATURE_DATA_D3D12_OPTIONS6 => ffi::D3D12_FEATURE_D3D12_OPTIONS6,
+    ffi::D3D12_FEATURE_DATA_D3D12_OPTIONS7 => ffi::D3D12_FEATURE_D3D12_OPTIONS7,
+    ffi::D3D12_FEATURE_DATA_D3D12_OPTIONS8 => ffi::D3D12_FEATURE_D3D12_OPTIONS8,
+    ffi::D3D12_FEATURE_DATA_D3D12_OPTIONS9 => ffi::D3D12_FEATURE_D3D12_OPTIONS9,
+    ffi::D3D12_FEATURE_DATA_D3D12_OPTIONS10 => ffi::D3D12_FEATURE_D3D12_OPTIONS10,
+    ffi::D3D12_FEATURE_DATA_D3D12_OPTIONS11 => ffi::D3D12_FEATURE_D3D12_OPTIONS11,
+    ffi::D3D12_FEATURE_DATA_D3D12_OPTIONS12 => ffi::D3D12_FEATURE_D3D12_OPTIONS12,
+    ffi::D3D12_FEATURE_DATA_D3D12_OPTIONS13 => ffi::D3D12_FEATURE_D3D12_OPTIONS13,
+    d3d12::D3D12_FEATURE_DATA_ARCHITECTURE => d3d12::D3D12_FEATURE_ARCHITECTURE,
+    d3d12::D3D12_FEATURE_DATA_ARCHITECTURE1 => d3d12::D3D12_FEATURE_ARCHITECTURE1,
+    d3d12::D3D12_FEATURE_DATA_FORMAT_SUPPORT => d3d12::D3D12_FEATURE_FORMAT_SUPPORT,
+    d3d12::D3D12_FEATURE_DATA_MULTISAMPLE_QUALITY_LEVELS => d3d12::D3D12_FEATURE_MULTISAMPLE_QUALITY_LEVELS,
+    d3d12::D3D12_FEATURE_DATA_FORMAT_INFO => d3d12::D3D12_FEATURE_FORMAT_INFO,
+    d3d12::D3D12_FEATURE_DATA_GPU_VIRTUAL_ADDRESS_SUPPORT => d3d12::D3D12_FEATURE_GPU_VIRTUAL_ADDRESS_SUPPORT,
+    d3d12::D3D12_FEATURE_DATA_SHADER_CACHE => d3d12::D3D12_FEATURE_SHADER_CACHE,
+    d3d12::D3D12_FEATURE_DATA_COMMAND_QUEUE_PRIORITY => d3d12::D3D12_FEATURE_COMMAND_QUEUE_PRIORITY,
+}
+
+/// Full feature levels, from the lowest to the highest.
+pub(crate) static FEATURE_LEVELS: [d3dcommon::D3D_FEATURE_LEVEL; 10] = [
+    d3dcommon::D3D_FEATURE_LEVEL_9_1,
+    d3dcommon::D3D_FEATURE_LEVEL_9_2,
+    d3dcommon::D3D_FEATURE_LEVEL_9_3,
+    d3dcommon::D3D_FEATURE_LEVEL_10_0,
+    d3dcommon::D3D_FEATURE_LEVEL_10_1,
+    d3dcommon::D3D_FEATURE_LEVEL_11_0,
+    d3dcommon::D3D_FEATURE_LEVEL_11_1,
+    d3dcommon::D3D_FEATURE_LEVEL_12_0,
+    d3dcommon::D3D_FEATURE_LEVEL_12_1,
+    ffi::D3D_FEATURE_LEVEL_12_2,
+];
+
+/// Returns the query of `D3D12_FEATURE_FEATURE_LEVELS` for all levels up to
+/// `FEATURE_LEVELS[count - 1]`.
+pub(crate) fn feature_levels_query(count: usize) -> d3d12::D3D12_FEATURE_DATA_FEATURE_LEVELS {
+    let levels = &FEATURE_LEVELS[..count];
+    d3d12::D3D12_FEATURE_DATA_FEATURE_LEVELS {
+        NumFeatureLevels: levels.len() as _,
+        pFeatureLevelsRequested: levels.as_ptr(),
+        MaxSupportedFeatureLevel: 0,
+    }
+}
+
+unsafe impl Feature for d3d12::D3D12_FEATURE_DATA_FEATURE_LEVELS {
+    const FEATURE: d3d12::D3D12_FEATURE = d3d12::D3D12_FEATURE_FEATURE_LEVELS;
+
+    /// Requests all full feature levels, `L1_0Core` is not requested. Runtimes reject
+    /// levels they don't know, such as `L12_2` on older runtimes, with `E_INVALIDARG`,
+    /// which is why `Device::max_feature_level` retries with fewer levels.
+    fn query() -> Self {
+        feature_levels_query(FEATURE_LEVELS.len())
+    }
+}
+
+unsafe impl Feature for d3d12::D3D12_FEATURE_DATA_SHADER_MODEL {
+    const FEATURE: d3d12::D3D12_FEATURE = d3d12::D3D12_FEATURE_SHADER_MODEL;
+
+    /// Requests the highest shader model known to the runtime of the crate, which
+    /// older runtimes reject with `E_INVALIDARG`, see `Device::highest_shader_model`.
+    fn query() -> Self {
+        d3d12::D3D12_FEATURE_DATA_SHADER_MODEL {
+            HighestShaderModel: ShaderModel::HIGHEST as _,
+        }
+    }
+}
+
+unsafe impl Feature for d3d12::D3D12_FEATURE_DATA_ROOT_SIGNATURE {
+    const FEATURE: d3d12::D3D12_FEATURE = d3d12::D3D12_FEATURE_ROOT_SIGNATURE;
+
+    fn query() -> Self {
+        d3d12::D3D12_FEATURE_DATA_ROOT_SIGNATURE {
+            HighestVersion: d3d12::D3D_ROOT_SIGNATURE_VERSION_1_1,
+        }
+    }
+}
+
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum ShaderModel {
+    V5_1 = d3d12::D3D_SHADER_MODEL_5_1,
+    V6_0 = d3d12::D3D_SHADER_MODEL_6_0,
+    V6_1 = ffi::D3D_SHADER_MODEL_6_1,
+    V6_2 = ffi::D3D_SHADER_MODEL_6_2,
+    V6_3 = ffi::D3D_SHADER_MODEL_6_3,
+    V6_4 = ffi::D3D_SHADER_MODEL_6_4,
+    V6_5 = ffi::D3D_SHADER_MODEL_6_5,
+    V6_6 = ffi::D3D_SHADER_MODEL_6_6,
+    V6_7 = ffi::D3D_SHADER_MODEL_6_7,
+    V6_8 = ffi::D3D_SHADER_MODEL_6_8,
+}
+
+impl ShaderModel {
+    pub const HIGHEST: Self = ShaderModel::V6_8;
+
+    /// All shader models, from the highest to the lowest.
+    pub const ALL: [Self; 10] = [
+        ShaderModel::V6_8,
+        ShaderModel::V6_7,
+        ShaderModel::V6_6,
+        ShaderModel::V6_5,
+        ShaderModel::V6_4,
+        ShaderModel::V6_3,
+        ShaderModel::V6_2,
+        ShaderModel::V6_1,
+        ShaderModel::V6_0,
+        ShaderModel::V5_1,
+    ];
+}
+
+impl TryFrom<u32> for ShaderModel {
+    type Error = ();
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|&model| model as u32 == value)
+            .ok_or(())
+    }
+}
+
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum RaytracingTier {
+    NotSupported = ffi::D3D12_RAYTRACING_TIER_NOT_SUPPORTED,
+    Tier1_0 = ffi::D3D12_RAYTRACING_TIER_1_0,
+    Tier1_1 = ffi::D3D12_RAYTRACING_TIER_1_1,
+}
+
+impl From<ffi::D3D12_RAYTRACING_TIER> for RaytracingTier {
+    /// Unknown tiers map to the highest known tier below them.
+    fn from(tier: ffi::D3D12_RAYTRACING_TIER) -> Self {
+        if tier >= ffi::D3D12_RAYTRACING_TIER_1_1 {
+            RaytracingTier::Tier1_1
+        } else if tier >= ffi::D3D12_RAYTRACING_TIER_1_0 {
+            RaytracingTier::Tier1_0
+        } else {
+            RaytracingTier::NotSupported
+        }
+    }
+}
+
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum MeshShaderTier {
+    NotSupported = ffi::D3D12_MESH_SHADER_TIER_NOT_SUPPORTED,
+    Tier1 = ffi::D3D12_MESH_SHADER_TIER_1,
+}
+
+impl From<ffi::D3D12_MESH_SHADER_TIER> for MeshShaderTier {
+    /// Unknown tiers map to the highest known tier below them.
+    fn from(tier: ffi::D3D12_MESH_SHADER_TIER) -> Self {
+        if tier >= ffi::D3D12_MESH_SHADER_TIER_1 {
+            MeshShaderTier::Tier1
+        } else {
+            MeshShaderTier::NotSupported
+        }
+    }
+}
+
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum ResourceHeapTier {
+    /// Heaps hold a single category of buffers, render target and depth stencil
+    /// textures or other textures.
+    Tier1 = d3d12::D3D12_RESOURCE_HEAP_TIER_1,
+    /// Heaps hold any mix of resources.
+    Tier2 = d3d12::D3D12_RESOURCE_HEAP_TIER_2,
+}
+
+impl From<d3d12::D3D12_RESOURCE_HEAP_TIER> for ResourceHeapTier {
+    /// Unknown tiers map to the highest known tier below them.
+    fn from(tier: d3d12::D3D12_RESOURCE_HEAP_TIER) -> Self {
+        if tier >= d3d12::D3D12_RESOURCE_HEAP_TIER_2 {
+            ResourceHeapTier::Tier2
+        } else {
+            ResourceHeapTier::Tier1
+        }
+    }
+}
+
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum SamplerFeedbackTier {
+    NotSupported = ffi::D3D12_SAMPLER_FEEDBACK_TIER_NOT_SUPPORTED,
+    Tier0_9 = ffi::D3D12_SAMPLER_FEEDBACK_TIER_0_9,
+    Tier1_0 = ffi::D3D12_SAMPLER_FEEDBACK_TIER_1_0,
+}
+
+impl From<ffi::D3D12_SAMPLER_FEEDBACK_TIER> for SamplerFeedbackTier {
+    /// Unknown tiers map to the highest known tier below them.
+    fn from(tier: ffi::D3D12_SAMPLER_FEEDBACK_TIER) -> Self {
+        if tier >= ffi::D3D12_SAMPLER_FEEDBACK_TIER_1_0 {
+            SamplerFeedbackTier::Tier1_0
+        } else if tier >= ffi::D3D12_SAMPLER_FEEDBACK_TIER_0_9 {
+            SamplerFeedbackTier::Tier0_9
+        } else {
+            SamplerFeedbackTier::NotSupported
+        }
+    }
+}
+
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum VariableShadingRateTier {
+    NotSupported = ffi::D3D12_VARIABLE_SHADING_RATE_TIER_NOT_SUPPORTED,
+    /// Per draw shading rates.
+    Tier1 = ffi::D3D12_VARIABLE_SHADING_RATE_TIER_1,
+    /// Per primitive and image based shading rates, as well as combiners.
+    Tier2 = ffi::D3D12_VARIABLE_SHADING_RATE_TIER_2,
+}
+
+impl From<ffi::D3D12_VARIABLE_SHADING_RATE_TIER> for VariableShadingRateTier {
+    /// Unknown tiers map to the highest known tier below them.
+    fn from(tier: ffi::D3D12_VARIABLE_SHADING_RATE_TIER) -> Self {
+        if tier >= ffi::D3D12_VARIABLE_SHADING_RATE_TIER_2 {
+            VariableShadingRateTier::Tier2
+        } else if tier >= ffi::D3D12_VARIABLE_SHADING_RATE_TIER_1 {
+            VariableShadingRateTier::Tier1
+        } else {
+            VariableShadingRateTier::NotSupported
+        }
+    }
+}
+
+/// Highest feature level and shader model supported by a device.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct DeviceLevels {
+    pub feature_level: FeatureLevel,
+    pub shader_model: ShaderModel,
+}
+
+impl DeviceLevels {
+    /// Queries the levels of `device`, which has been created at `created_level`.
+    ///
+    /// Devices created at `FeatureLevel::L1_0Core` report that level without querying.
+    pub fn new(device: &Device, created_level: FeatureLevel) -> Result<Self, HRESULT> {
+        let feature_level = match created_level {
+            FeatureLevel::L1_0Core => FeatureLevel::L1_0Core,
+            _ => device.max_feature_level()?,
+        };
+
+        Ok(DeviceLevels {
+            feature_level,
+            shader_model: device.highest_shader_model()?,
+        })
+    }
+}
+
+/// Summary of the features supported by a device.
+///
+/// Options which are unknown to the installed runtime are `None`.
+#[derive(Clone, Copy)]
+pub struct DeviceCapabilities {
+    pub max_feature_level: FeatureLevel,
+    pub shader_model: ShaderModel,
+    pub root_signature_version: RootSignatureVersion,
+    pub tile_based_renderer: bool,
+    pub uma: bool,
+    pub cache_coherent_uma: bool,
+    pub gpu_virtual_address: d3d12::D3D12_FEATURE_DATA_GPU_VIRTUAL_ADDRESS_SUPPORT,
+    pub raytracing_tier: RaytracingTier,
+    pub mesh_shader_tier: MeshShaderTier,
+    pub sampler_feedback_tier: SamplerFeedbackTier,
+    pub options: d3d12::D3D12_FEATURE_DATA_D3D12_OPTIONS,
+    pub options1: Option<d3d12::D3D12_FEATURE_DATA_D3D12_OPTIONS1>,
+    pub options2: Option<d3d12::D3D12_FEATURE_DATA_D3D12_OPTIONS2>,
+    pub options3: Option<ffi::D3D12_FEATURE_DATA_D3D12_OPTIONS3>,
+    pub options4: Option<ffi::D3D12_FEATURE_DATA_D3D12_OPTIONS4>,
+    pub options5: Option<ffi::D3D12_FEATURE_DATA_D3D12_OPTIONS5>,
+    pub options6: Option<ffi::D3D12_FEATURE_DATA_D3D12_OPTIONS6>,
+    pub options7: Option<ffi::D3D12_FEATURE_DATA_D3D12_OPTIONS7>,
+    pub options8: Option<ffi::D3D12_FEATURE_DATA_D3D12_OPTIONS8>,
+    pub options9: Option<ffi::D3D12_FEATURE_DATA_D3D12_OPTIONS9>,
+    pub options10: Option<ffi::D3D12_FEATURE_DATA_D3D12_OPTIONS10>,
+    pub options11: Option<ffi::D3D12_FEATURE_DATA_D3D12_OPTIONS11>,
+    pub options12: Option<ffi::D3D12_FEATURE_DATA_D3D12_OPTIONS12>,
+    pub options13: Option<ffi::D3D12_FEATURE_DATA_D3D12_OPTIONS13>,
+}
+
+impl DeviceCapabilities {
+    pub fn new(device: &Device) -> Result<Self, HRESULT> {
+        fn required<F: Feature>(device: &Device) -> Result<F, HRESULT> {
+            let (data, hr) = device.check_feature::<F>();
+            if winerror::SUCCEEDED(hr) {
+                Ok(data)
+            } else {
+                Err(hr)
+            }
+        }
+        fn optional<F: Feature>(device: &Device) -> Option<F> {
+            required(device).ok()
+        }
+
+        let root_signature_version =
+            match optional::<d3d12::D3D12_FEATURE_DATA_ROOT_SIGNATURE>(device) {
+                Some(data) if data.HighestVersion == d3d12::D3D_ROOT_SIGNATURE_VERSION_1_1 => {
+                    RootSignatureVersion::V1_1
+                }
+                _ => RootSignatureVersion::V1_0,
+            };
+
+        let architecture = required::<d3d12::D3D12_FEATURE_DATA_ARCHITECTURE>(device)?;
+        let options5 = optional::<ffi::D3D12_FEATURE_DATA_D3D12_OPTIONS5>(device);
+        let options7 = optional::<ffi::D3D12_FEATURE_DATA_D3D12_OPTIONS7>(device);
+
+        Ok(DeviceCapabilities {
+            max_feature_level: device.max_feature_level()?,
+            shader_model: device.highest_shader_model()?,
+            root_signature_version,
+            tile_based_renderer: architecture.TileBasedRenderer != FALSE,
+            uma: architecture.UMA != FALSE,
+            cache_coherent_uma: architecture.CacheCoherentUMA != FALSE,
+            gpu_virtual_address: required(device)?,
+            raytracing_tier: options5.map_or(RaytracingTier::NotSupported, |options| {
+                options.RaytracingTier.into()
+            }),
+            mesh_shader_tier: options7.map_or(MeshShaderTier::NotSupported, |options| {
+                options.MeshShaderTier.into()
+            }),
+            sampler_feedback_tier: options7.map_or(SamplerFeedbackTier::NotSupported, |options| {
+                options.SamplerFeedbackTier.into()
+            }),
+            options: required(device)?,
+            options1: optional(device),
+            options2: optional(device),
+            options3: optional(device),
+            options4: optional(device),
+            options5,
+            options6: optional(device),
+            options7,
+            options8: optional(device),
+            options9: optional(device),
+            options10: optional(device),
+            options11: optional(device),
+            options12: optional(device),
+            options13: optional(device),
+        })
+    }
+}
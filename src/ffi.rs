@@ -0,0 +1,1313 @@
+//! Raw definitions which are missing from `winapi`.
+#![allow(non_camel_case_types, non_snake_case)]
+
+use winapi::{
+    ctypes::c_void,
+    shared::{
+        basetsd::{SIZE_T, UINT16, UINT32, UINT64},
+        dxgiformat::DXGI_FORMAT,
+        dxgitype::DXGI_SAMPLE_DESC,
+        guiddef::{GUID, REFGUID, REFIID},
+        minwindef::{BOOL, BYTE, FLOAT, UINT},
+        ntdef::{HANDLE, LPCWSTR},
+        winerror::HRESULT,
+    },
+    um::{
+        d3d12::*,
+        d3dcommon::D3D_FEATURE_LEVEL,
+        unknwnbase::{IUnknown, IUnknownVtbl},
+    },
+    ENUM, RIDL,
+};
+
+pub const D3D12_ROOT_SIGNATURE_FLAG_LOCAL_ROOT_SIGNATURE: D3D12_ROOT_SIGNATURE_FLAGS = 0x80;
+pub const D3D12_ROOT_SIGNATURE_FLAG_DENY_AMPLIFICATION_SHADER_ROOT_ACCESS:
+    D3D12_ROOT_SIGNATURE_FLAGS = 0x100;
+pub const D3D12_ROOT_SIGNATURE_FLAG_DENY_MESH_SHADER_ROOT_ACCESS: D3D12_ROOT_SIGNATURE_FLAGS =
+    0x200;
+pub const D3D12_ROOT_SIGNATURE_FLAG_CBV_SRV_UAV_HEAP_DIRECTLY_INDEXED: D3D12_ROOT_SIGNATURE_FLAGS =
+    0x400;
+pub const D3D12_ROOT_SIGNATURE_FLAG_SAMPLER_HEAP_DIRECTLY_INDEXED: D3D12_ROOT_SIGNATURE_FLAGS =
+    0x800;
+
+pub const D3D_FEATURE_LEVEL_1_0_CORE: D3D_FEATURE_LEVEL = 0x1000;
+pub const D3D_FEATURE_LEVEL_12_2: D3D_FEATURE_LEVEL = 0xc200;
+
+// Feature support queries added after `D3D12_FEATURE_COMMAND_QUEUE_PRIORITY`.
+
+pub const D3D12_FEATURE_D3D12_OPTIONS3: D3D12_FEATURE = 21;
+pub const D3D12_FEATURE_D3D12_OPTIONS4: D3D12_FEATURE = 23;
+pub const D3D12_FEATURE_D3D12_OPTIONS5: D3D12_FEATURE = 27;
+pub const D3D12_FEATURE_D3D12_OPTIONS6: D3D12_FEATURE = 30;
+pub const D3D12_FEATURE_D3D12_OPTIONS7: D3D12_FEATURE = 32;
+pub const D3D12_FEATURE_D3D12_OPTIONS8: D3D12_FEATURE = 36;
+pub const D3D12_FEATURE_D3D12_OPTIONS9: D3D12_FEATURE = 37;
+pub const D3D12_FEATURE_D3D12_OPTIONS10: D3D12_FEATURE = 39;
+pub const D3D12_FEATURE_D3D12_OPTIONS11: D3D12_FEATURE = 40;
+pub const D3D12_FEATURE_D3D12_OPTIONS12: D3D12_FEATURE = 41;
+pub const D3D12_FEATURE_D3D12_OPTIONS13: D3D12_FEATURE = 42;
+
+pub const D3D_SHADER_MODEL_6_1: D3D_SHADER_MODEL = 0x61;
+pub const D3D_SHADER_MODEL_6_2: D3D_SHADER_MODEL = 0x62;
+pub const D3D_SHADER_MODEL_6_3: D3D_SHADER_MODEL = 0x63;
+pub const D3D_SHADER_MODEL_6_4: D3D_SHADER_MODEL = 0x64;
+pub const D3D_SHADER_MODEL_6_5: D3D_SHADER_MODEL = 0x65;
+pub const D3D_SHADER_MODEL_6_6: D3D_SHADER_MODEL = 0x66;
+pub const D3D_SHADER_MODEL_6_7: D3D_SHADER_MODEL = 0x67;
+pub const D3D_SHADER_MODEL_6_8: D3D_SHADER_MODEL = 0x68;
+
+ENUM! {enum D3D12_COMMAND_LIST_SUPPORT_FLAGS {
+    D3D12_COMMAND_LIST_SUPPORT_FLAG_NONE = 0,
+    D3D12_COMMAND_LIST_SUPPORT_FLAG_DIRECT = 1 << D3D12_COMMAND_LIST_TYPE_DIRECT,
+    D3D12_COMMAND_LIST_SUPPORT_FLAG_BUNDLE = 1 << D3D12_COMMAND_LIST_TYPE_BUNDLE,
+    D3D12_COMMAND_LIST_SUPPORT_FLAG_COMPUTE = 1 << D3D12_COMMAND_LIST_TYPE_COMPUTE,
+    D3D12_COMMAND_LIST_SUPPORT_FLAG_COPY = 1 << D3D12_COMMAND_LIST_TYPE_COPY,
+    D3D12_COMMAND_LIST_SUPPORT_FLAG_VIDEO_DECODE = 1 << 4,
+    D3D12_COMMAND_LIST_SUPPORT_FLAG_VIDEO_PROCESS = 1 << 5,
+    D3D12_COMMAND_LIST_SUPPORT_FLAG_VIDEO_ENCODE = 1 << 6,
+}}
+
+ENUM! {enum D3D12_VIEW_INSTANCING_TIER {
+    D3D12_VIEW_INSTANCING_TIER_NOT_SUPPORTED = 0,
+    D3D12_VIEW_INSTANCING_TIER_1 = 1,
+    D3D12_VIEW_INSTANCING_TIER_2 = 2,
+    D3D12_VIEW_INSTANCING_TIER_3 = 3,
+}}
+
+ENUM! {enum D3D12_SHARED_RESOURCE_COMPATIBILITY_TIER {
+    D3D12_SHARED_RESOURCE_COMPATIBILITY_TIER_0 = 0,
+    D3D12_SHARED_RESOURCE_COMPATIBILITY_TIER_1 = 1,
+    D3D12_SHARED_RESOURCE_COMPATIBILITY_TIER_2 = 2,
+}}
+
+ENUM! {enum D3D12_RENDER_PASS_TIER {
+    D3D12_RENDER_PASS_TIER_0 = 0,
+    D3D12_RENDER_PASS_TIER_1 = 1,
+    D3D12_RENDER_PASS_TIER_2 = 2,
+}}
+
+ENUM! {enum D3D12_RAYTRACING_TIER {
+    D3D12_RAYTRACING_TIER_NOT_SUPPORTED = 0,
+    D3D12_RAYTRACING_TIER_1_0 = 10,
+    D3D12_RAYTRACING_TIER_1_1 = 11,
+}}
+
+ENUM! {enum D3D12_VARIABLE_SHADING_RATE_TIER {
+    D3D12_VARIABLE_SHADING_RATE_TIER_NOT_SUPPORTED = 0,
+    D3D12_VARIABLE_SHADING_RATE_TIER_1 = 1,
+    D3D12_VARIABLE_SHADING_RATE_TIER_2 = 2,
+}}
+
+ENUM! {enum D3D12_MESH_SHADER_TIER {
+    D3D12_MESH_SHADER_TIER_NOT_SUPPORTED = 0,
+    D3D12_MESH_SHADER_TIER_1 = 10,
+}}
+
+ENUM! {enum D3D12_SAMPLER_FEEDBACK_TIER {
+    D3D12_SAMPLER_FEEDBACK_TIER_NOT_SUPPORTED = 0,
+    D3D12_SAMPLER_FEEDBACK_TIER_0_9 = 90,
+    D3D12_SAMPLER_FEEDBACK_TIER_1_0 = 100,
+}}
+
+pub const DXGI_FORMAT_SAMPLER_FEEDBACK_MIN_MIP_OPAQUE: DXGI_FORMAT = 189;
+pub const DXGI_FORMAT_SAMPLER_FEEDBACK_MIP_REGION_USED_OPAQUE: DXGI_FORMAT = 190;
+
+pub const D3D12_RESOLVE_MODE_ENCODE_SAMPLER_FEEDBACK: D3D12_RESOLVE_MODE = 4;
+pub const D3D12_RESOLVE_MODE_DECODE_SAMPLER_FEEDBACK: D3D12_RESOLVE_MODE = 5;
+
+ENUM! {enum D3D12_WAVE_MMA_TIER {
+    D3D12_WAVE_MMA_TIER_NOT_SUPPORTED = 0,
+    D3D12_WAVE_MMA_TIER_1_0 = 10,
+}}
+
+pub type D3D12_TRI_STATE = i32;
+pub const D3D12_TRI_STATE_UNKNOWN: D3D12_TRI_STATE = -1;
+pub const D3D12_TRI_STATE_FALSE: D3D12_TRI_STATE = 0;
+pub const D3D12_TRI_STATE_TRUE: D3D12_TRI_STATE = 1;
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct D3D12_FEATURE_DATA_D3D12_OPTIONS3 {
+    pub CopyQueueTimestampQueriesSupported: BOOL,
+    pub CastingFullyTypedFormatSupported: BOOL,
+    pub WriteBufferImmediateSupportFlags: D3D12_COMMAND_LIST_SUPPORT_FLAGS,
+    pub ViewInstancingTier: D3D12_VIEW_INSTANCING_TIER,
+    pub BarycentricsSupported: BOOL,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct D3D12_FEATURE_DATA_D3D12_OPTIONS4 {
+    pub MSAA64KBAlignedTextureSupported: BOOL,
+    pub SharedResourceCompatibilityTier: D3D12_SHARED_RESOURCE_COMPATIBILITY_TIER,
+    pub Native16BitShaderOpsSupported: BOOL,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct D3D12_FEATURE_DATA_D3D12_OPTIONS5 {
+    pub SRVOnlyTiledResourceTier3: BOOL,
+    pub RenderPassesTier: D3D12_RENDER_PASS_TIER,
+    pub RaytracingTier: D3D12_RAYTRACING_TIER,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct D3D12_FEATURE_DATA_D3D12_OPTIONS6 {
+    pub AdditionalShadingRatesSupported: BOOL,
+    pub PerPrimitiveShadingRateSupportedWithViewportIndexing: BOOL,
+    pub VariableShadingRateTier: D3D12_VARIABLE_SHADING_RATE_TIER,
+    pub ShadingRateImageTileSize: UINT,
+    pub BackgroundProcessingSupported: BOOL,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct D3D12_FEATURE_DATA_D3D12_OPTIONS7 {
+    pub MeshShaderTier: D3D12_MESH_SHADER_TIER,
+    pub SamplerFeedbackTier: D3D12_SAMPLER_FEEDBACK_TIER,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct D3D12_FEATURE_DATA_D3D12_OPTIONS8 {
+    pub UnalignedBlockTexturesSupported: BOOL,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct D3D12_FEATURE_DATA_D3D12_OPTIONS9 {
+    pub MeshShaderPipelineStatsSupported: BOOL,
+    pub MeshShaderSupportsFullRangeRenderTargetArrayIndex: BOOL,
+    pub AtomicInt64OnTypedResourceSupported: BOOL,
+    pub AtomicInt64OnGroupSharedSupported: BOOL,
+    pub DerivativesInMeshAndAmplificationShadersSupported: BOOL,
+    pub WaveMMATier: D3D12_WAVE_MMA_TIER,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct D3D12_FEATURE_DATA_D3D12_OPTIONS10 {
+    pub VariableRateShadingSumCombinerSupported: BOOL,
+    pub MeshShaderPerPrimitiveShadingRateSupported: BOOL,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct D3D12_FEATURE_DATA_D3D12_OPTIONS11 {
+    pub AtomicInt64OnDescriptorHeapResourceSupported: BOOL,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct D3D12_FEATURE_DATA_D3D12_OPTIONS12 {
+    pub MSPrimitivesPipelineStatisticIncludesCulledPrimitives: D3D12_TRI_STATE,
+    pub EnhancedBarriersSupported: BOOL,
+    pub RelaxedFormatCastingSupported: BOOL,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct D3D12_FEATURE_DATA_D3D12_OPTIONS13 {
+    pub UnrestrictedBufferTextureCopyPitchSupported: BOOL,
+    pub UnrestrictedVertexElementAlignmentSupported: BOOL,
+    pub InvertedViewportHeightFlipsYSupported: BOOL,
+    pub InvertedViewportDepthFlipsZSupported: BOOL,
+    pub TextureCopyBetweenDimensionsSupported: BOOL,
+    pub AlphaBlendFactorSupported: BOOL,
+}
+
+// Device interfaces added after `ID3D12Device2`.
+
+pub type D3D12_RESIDENCY_FLAGS = u32;
+pub const D3D12_RESIDENCY_FLAG_NONE: D3D12_RESIDENCY_FLAGS = 0;
+pub const D3D12_RESIDENCY_FLAG_DENY_OVERBUDGET: D3D12_RESIDENCY_FLAGS = 0x1;
+
+pub type D3D12_COMMAND_LIST_FLAGS = u32;
+pub const D3D12_COMMAND_LIST_FLAG_NONE: D3D12_COMMAND_LIST_FLAGS = 0;
+
+pub type D3D12_PROTECTED_RESOURCE_SESSION_FLAGS = u32;
+pub type D3D12_PROTECTED_SESSION_STATUS = u32;
+pub type D3D12_LIFETIME_STATE = u32;
+pub type D3D12_META_COMMAND_PARAMETER_TYPE = u32;
+pub type D3D12_META_COMMAND_PARAMETER_FLAGS = u32;
+pub type D3D12_META_COMMAND_PARAMETER_STAGE = u32;
+pub type D3D12_GRAPHICS_STATES = u32;
+pub type D3D12_SERIALIZED_DATA_TYPE = u32;
+pub type D3D12_DRIVER_MATCHING_IDENTIFIER_STATUS = u32;
+pub type D3D12_BACKGROUND_PROCESSING_MODE = u32;
+pub type D3D12_MEASUREMENTS_ACTION = u32;
+pub type D3D12_SHADER_CACHE_MODE = u32;
+pub type D3D12_SHADER_CACHE_FLAGS = u32;
+pub type D3D12_SHADER_CACHE_KIND_FLAGS = u32;
+pub type D3D12_SHADER_CACHE_CONTROL_FLAGS = u32;
+pub type D3D12_BARRIER_LAYOUT = u32;
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct D3D12_PROTECTED_RESOURCE_SESSION_DESC {
+    pub NodeMask: UINT,
+    pub Flags: D3D12_PROTECTED_RESOURCE_SESSION_FLAGS,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct D3D12_PROTECTED_RESOURCE_SESSION_DESC1 {
+    pub NodeMask: UINT,
+    pub Flags: D3D12_PROTECTED_RESOURCE_SESSION_FLAGS,
+    pub ProtectionType: GUID,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct D3D12_RESOURCE_ALLOCATION_INFO1 {
+    pub Offset: UINT64,
+    pub Alignment: UINT64,
+    pub SizeInBytes: UINT64,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct D3D12_MIP_REGION {
+    pub Width: UINT,
+    pub Height: UINT,
+    pub Depth: UINT,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct D3D12_RESOURCE_DESC1 {
+    pub Dimension: D3D12_RESOURCE_DIMENSION,
+    pub Alignment: UINT64,
+    pub Width: UINT64,
+    pub Height: UINT,
+    pub DepthOrArraySize: UINT16,
+    pub MipLevels: UINT16,
+    pub Format: DXGI_FORMAT,
+    pub SampleDesc: DXGI_SAMPLE_DESC,
+    pub Layout: D3D12_TEXTURE_LAYOUT,
+    pub Flags: D3D12_RESOURCE_FLAGS,
+    pub SamplerFeedbackMipRegion: D3D12_MIP_REGION,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct D3D12_META_COMMAND_DESC {
+    pub Id: GUID,
+    pub Name: LPCWSTR,
+    pub InitializationDirtyState: D3D12_GRAPHICS_STATES,
+    pub ExecutionDirtyState: D3D12_GRAPHICS_STATES,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct D3D12_META_COMMAND_PARAMETER_DESC {
+    pub Name: LPCWSTR,
+    pub Type: D3D12_META_COMMAND_PARAMETER_TYPE,
+    pub Flags: D3D12_META_COMMAND_PARAMETER_FLAGS,
+    pub RequiredResourceState: D3D12_RESOURCE_STATES,
+    pub StructureOffset: UINT,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct D3D12_SERIALIZED_DATA_DRIVER_MATCHING_IDENTIFIER {
+    pub DriverOpaqueGUID: GUID,
+    pub DriverOpaqueVersioningData: [BYTE; 16],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct D3D12_SHADER_CACHE_SESSION_DESC {
+    pub Identifier: GUID,
+    pub Mode: D3D12_SHADER_CACHE_MODE,
+    pub Flags: D3D12_SHADER_CACHE_FLAGS,
+    pub MaximumInMemoryCacheSizeBytes: UINT,
+    pub MaximumInMemoryCacheEntries: UINT,
+    pub MaximumValueFileSizeBytes: UINT,
+    pub Version: UINT64,
+}
+
+pub const D3D12_SHADER_IDENTIFIER_SIZE_IN_BYTES: usize = 32;
+pub const D3D12_RAYTRACING_SHADER_RECORD_BYTE_ALIGNMENT: u64 = 32;
+pub const D3D12_RAYTRACING_SHADER_TABLE_BYTE_ALIGNMENT: u64 = 64;
+
+pub type D3D12_STATE_OBJECT_TYPE = u32;
+pub const D3D12_STATE_OBJECT_TYPE_COLLECTION: D3D12_STATE_OBJECT_TYPE = 0;
+pub const D3D12_STATE_OBJECT_TYPE_RAYTRACING_PIPELINE: D3D12_STATE_OBJECT_TYPE = 3;
+
+pub type D3D12_STATE_SUBOBJECT_TYPE = u32;
+pub const D3D12_STATE_SUBOBJECT_TYPE_STATE_OBJECT_CONFIG: D3D12_STATE_SUBOBJECT_TYPE = 0;
+pub const D3D12_STATE_SUBOBJECT_TYPE_GLOBAL_ROOT_SIGNATURE: D3D12_STATE_SUBOBJECT_TYPE = 1;
+pub const D3D12_STATE_SUBOBJECT_TYPE_LOCAL_ROOT_SIGNATURE: D3D12_STATE_SUBOBJECT_TYPE = 2;
+pub const D3D12_STATE_SUBOBJECT_TYPE_NODE_MASK: D3D12_STATE_SUBOBJECT_TYPE = 3;
+pub const D3D12_STATE_SUBOBJECT_TYPE_DXIL_LIBRARY: D3D12_STATE_SUBOBJECT_TYPE = 5;
+pub const D3D12_STATE_SUBOBJECT_TYPE_EXISTING_COLLECTION: D3D12_STATE_SUBOBJECT_TYPE = 6;
+pub const D3D12_STATE_SUBOBJECT_TYPE_SUBOBJECT_TO_EXPORTS_ASSOCIATION: D3D12_STATE_SUBOBJECT_TYPE =
+    7;
+pub const D3D12_STATE_SUBOBJECT_TYPE_DXIL_SUBOBJECT_TO_EXPORTS_ASSOCIATION:
+    D3D12_STATE_SUBOBJECT_TYPE = 8;
+pub const D3D12_STATE_SUBOBJECT_TYPE_RAYTRACING_SHADER_CONFIG: D3D12_STATE_SUBOBJECT_TYPE = 9;
+pub const D3D12_STATE_SUBOBJECT_TYPE_RAYTRACING_PIPELINE_CONFIG: D3D12_STATE_SUBOBJECT_TYPE = 10;
+pub const D3D12_STATE_SUBOBJECT_TYPE_HIT_GROUP: D3D12_STATE_SUBOBJECT_TYPE = 11;
+pub const D3D12_STATE_SUBOBJECT_TYPE_RAYTRACING_PIPELINE_CONFIG1: D3D12_STATE_SUBOBJECT_TYPE = 12;
+
+pub type D3D12_STATE_OBJECT_FLAGS = u32;
+pub const D3D12_STATE_OBJECT_FLAG_NONE: D3D12_STATE_OBJECT_FLAGS = 0;
+pub const D3D12_STATE_OBJECT_FLAG_ALLOW_LOCAL_DEPENDENCIES_ON_EXTERNAL_DEFINITIONS:
+    D3D12_STATE_OBJECT_FLAGS = 0x1;
+pub const D3D12_STATE_OBJECT_FLAG_ALLOW_EXTERNAL_DEPENDENCIES_ON_LOCAL_DEFINITIONS:
+    D3D12_STATE_OBJECT_FLAGS = 0x2;
+pub const D3D12_STATE_OBJECT_FLAG_ALLOW_STATE_OBJECT_ADDITIONS: D3D12_STATE_OBJECT_FLAGS = 0x4;
+
+pub type D3D12_EXPORT_FLAGS = u32;
+pub const D3D12_EXPORT_FLAG_NONE: D3D12_EXPORT_FLAGS = 0;
+
+pub type D3D12_HIT_GROUP_TYPE = u32;
+pub const D3D12_HIT_GROUP_TYPE_TRIANGLES: D3D12_HIT_GROUP_TYPE = 0;
+pub const D3D12_HIT_GROUP_TYPE_PROCEDURAL_PRIMITIVE: D3D12_HIT_GROUP_TYPE = 0x1;
+
+pub type D3D12_RAYTRACING_PIPELINE_FLAGS = u32;
+pub const D3D12_RAYTRACING_PIPELINE_FLAG_NONE: D3D12_RAYTRACING_PIPELINE_FLAGS = 0;
+pub const D3D12_RAYTRACING_PIPELINE_FLAG_SKIP_TRIANGLES: D3D12_RAYTRACING_PIPELINE_FLAGS = 0x100;
+pub const D3D12_RAYTRACING_PIPELINE_FLAG_SKIP_PROCEDURAL_PRIMITIVES:
+    D3D12_RAYTRACING_PIPELINE_FLAGS = 0x200;
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct D3D12_STATE_OBJECT_CONFIG {
+    pub Flags: D3D12_STATE_OBJECT_FLAGS,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct D3D12_GLOBAL_ROOT_SIGNATURE {
+    pub pGlobalRootSignature: *mut ID3D12RootSignature,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct D3D12_LOCAL_ROOT_SIGNATURE {
+    pub pLocalRootSignature: *mut ID3D12RootSignature,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct D3D12_NODE_MASK {
+    pub NodeMask: UINT,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct D3D12_EXPORT_DESC {
+    pub Name: LPCWSTR,
+    pub ExportToRename: LPCWSTR,
+    pub Flags: D3D12_EXPORT_FLAGS,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct D3D12_DXIL_LIBRARY_DESC {
+    pub DXILLibrary: D3D12_SHADER_BYTECODE,
+    pub NumExports: UINT,
+    pub pExports: *mut D3D12_EXPORT_DESC,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct D3D12_EXISTING_COLLECTION_DESC {
+    pub pExistingCollection: *mut ID3D12StateObject,
+    pub NumExports: UINT,
+    pub pExports: *mut D3D12_EXPORT_DESC,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct D3D12_SUBOBJECT_TO_EXPORTS_ASSOCIATION {
+    pub pSubobjectToAssociate: *const D3D12_STATE_SUBOBJECT,
+    pub NumExports: UINT,
+    pub pExports: *mut LPCWSTR,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct D3D12_DXIL_SUBOBJECT_TO_EXPORTS_ASSOCIATION {
+    pub SubobjectToAssociate: LPCWSTR,
+    pub NumExports: UINT,
+    pub pExports: *mut LPCWSTR,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct D3D12_HIT_GROUP_DESC {
+    pub HitGroupExport: LPCWSTR,
+    pub Type: D3D12_HIT_GROUP_TYPE,
+    pub AnyHitShaderImport: LPCWSTR,
+    pub ClosestHitShaderImport: LPCWSTR,
+    pub IntersectionShaderImport: LPCWSTR,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct D3D12_RAYTRACING_SHADER_CONFIG {
+    pub MaxPayloadSizeInBytes: UINT,
+    pub MaxAttributeSizeInBytes: UINT,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct D3D12_RAYTRACING_PIPELINE_CONFIG {
+    pub MaxTraceRecursionDepth: UINT,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct D3D12_RAYTRACING_PIPELINE_CONFIG1 {
+    pub MaxTraceRecursionDepth: UINT,
+    pub Flags: D3D12_RAYTRACING_PIPELINE_FLAGS,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct D3D12_STATE_SUBOBJECT {
+    pub Type: D3D12_STATE_SUBOBJECT_TYPE,
+    pub pDesc: *const c_void,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct D3D12_STATE_OBJECT_DESC {
+    pub Type: D3D12_STATE_OBJECT_TYPE,
+    pub NumSubobjects: UINT,
+    pub pSubobjects: *const D3D12_STATE_SUBOBJECT,
+}
+
+pub const D3D12_RAYTRACING_ACCELERATION_STRUCTURE_BYTE_ALIGNMENT: u64 = 256;
+pub const D3D12_RAYTRACING_INSTANCE_DESCS_BYTE_ALIGNMENT: u64 = 16;
+pub const D3D12_RAYTRACING_AABB_BYTE_ALIGNMENT: u64 = 8;
+pub const D3D12_RAYTRACING_TRANSFORM3X4_BYTE_ALIGNMENT: u64 = 16;
+
+pub type D3D12_RAYTRACING_ACCELERATION_STRUCTURE_TYPE = u32;
+pub const D3D12_RAYTRACING_ACCELERATION_STRUCTURE_TYPE_TOP_LEVEL:
+    D3D12_RAYTRACING_ACCELERATION_STRUCTURE_TYPE = 0;
+pub const D3D12_RAYTRACING_ACCELERATION_STRUCTURE_TYPE_BOTTOM_LEVEL:
+    D3D12_RAYTRACING_ACCELERATION_STRUCTURE_TYPE = 0x1;
+
+pub type D3D12_RAYTRACING_ACCELERATION_STRUCTURE_BUILD_FLAGS = u32;
+pub const D3D12_RAYTRACING_ACCELERATION_STRUCTURE_BUILD_FLAG_NONE:
+    D3D12_RAYTRACING_ACCELERATION_STRUCTURE_BUILD_FLAGS = 0;
+pub const D3D12_RAYTRACING_ACCELERATION_STRUCTURE_BUILD_FLAG_ALLOW_UPDATE:
+    D3D12_RAYTRACING_ACCELERATION_STRUCTURE_BUILD_FLAGS = 0x1;
+pub const D3D12_RAYTRACING_ACCELERATION_STRUCTURE_BUILD_FLAG_ALLOW_COMPACTION:
+    D3D12_RAYTRACING_ACCELERATION_STRUCTURE_BUILD_FLAGS = 0x2;
+pub const D3D12_RAYTRACING_ACCELERATION_STRUCTURE_BUILD_FLAG_PREFER_FAST_TRACE:
+    D3D12_RAYTRACING_ACCELERATION_STRUCTURE_BUILD_FLAGS = 0x4;
+pub const D3D12_RAYTRACING_ACCELERATION_STRUCTURE_BUILD_FLAG_PREFER_FAST_BUILD:
+    D3D12_RAYTRACING_ACCELERATION_STRUCTURE_BUILD_FLAGS = 0x8;
+pub const D3D12_RAYTRACING_ACCELERATION_STRUCTURE_BUILD_FLAG_MINIMIZE_MEMORY:
+    D3D12_RAYTRACING_ACCELERATION_STRUCTURE_BUILD_FLAGS = 0x10;
+pub const D3D12_RAYTRACING_ACCELERATION_STRUCTURE_BUILD_FLAG_PERFORM_UPDATE:
+    D3D12_RAYTRACING_ACCELERATION_STRUCTURE_BUILD_FLAGS = 0x20;
+
+pub type D3D12_RAYTRACING_GEOMETRY_TYPE = u32;
+pub const D3D12_RAYTRACING_GEOMETRY_TYPE_TRIANGLES: D3D12_RAYTRACING_GEOMETRY_TYPE = 0;
+pub const D3D12_RAYTRACING_GEOMETRY_TYPE_PROCEDURAL_PRIMITIVE_AABBS:
+    D3D12_RAYTRACING_GEOMETRY_TYPE = 1;
+
+pub type D3D12_RAYTRACING_GEOMETRY_FLAGS = u32;
+pub const D3D12_RAYTRACING_GEOMETRY_FLAG_NONE: D3D12_RAYTRACING_GEOMETRY_FLAGS = 0;
+pub const D3D12_RAYTRACING_GEOMETRY_FLAG_OPAQUE: D3D12_RAYTRACING_GEOMETRY_FLAGS = 0x1;
+pub const D3D12_RAYTRACING_GEOMETRY_FLAG_NO_DUPLICATE_ANYHIT_INVOCATION:
+    D3D12_RAYTRACING_GEOMETRY_FLAGS = 0x2;
+
+pub type D3D12_ELEMENTS_LAYOUT = u32;
+pub const D3D12_ELEMENTS_LAYOUT_ARRAY: D3D12_ELEMENTS_LAYOUT = 0;
+pub const D3D12_ELEMENTS_LAYOUT_ARRAY_OF_POINTERS: D3D12_ELEMENTS_LAYOUT = 0x1;
+
+pub type D3D12_RAYTRACING_INSTANCE_FLAGS = u32;
+pub const D3D12_RAYTRACING_INSTANCE_FLAG_NONE: D3D12_RAYTRACING_INSTANCE_FLAGS = 0;
+pub const D3D12_RAYTRACING_INSTANCE_FLAG_TRIANGLE_CULL_DISABLE: D3D12_RAYTRACING_INSTANCE_FLAGS =
+    0x1;
+pub const D3D12_RAYTRACING_INSTANCE_FLAG_TRIANGLE_FRONT_COUNTERCLOCKWISE:
+    D3D12_RAYTRACING_INSTANCE_FLAGS = 0x2;
+pub const D3D12_RAYTRACING_INSTANCE_FLAG_FORCE_OPAQUE: D3D12_RAYTRACING_INSTANCE_FLAGS = 0x4;
+pub const D3D12_RAYTRACING_INSTANCE_FLAG_FORCE_NON_OPAQUE: D3D12_RAYTRACING_INSTANCE_FLAGS = 0x8;
+
+/// The C bitfields are packed from the least significant bit: `InstanceID : 24`
+/// and `InstanceMask : 8`, then `InstanceContributionToHitGroupIndex : 24` and
+/// `Flags : 8`.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct D3D12_RAYTRACING_INSTANCE_DESC {
+    pub Transform: [[FLOAT; 4]; 3],
+    pub InstanceID_InstanceMask: UINT,
+    pub InstanceContributionToHitGroupIndex_Flags: UINT,
+    pub AccelerationStructure: D3D12_GPU_VIRTUAL_ADDRESS,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct D3D12_GPU_VIRTUAL_ADDRESS_AND_STRIDE {
+    pub StartAddress: D3D12_GPU_VIRTUAL_ADDRESS,
+    pub StrideInBytes: UINT64,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct D3D12_GPU_VIRTUAL_ADDRESS_RANGE {
+    pub StartAddress: D3D12_GPU_VIRTUAL_ADDRESS,
+    pub SizeInBytes: UINT64,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct D3D12_GPU_VIRTUAL_ADDRESS_RANGE_AND_STRIDE {
+    pub StartAddress: D3D12_GPU_VIRTUAL_ADDRESS,
+    pub SizeInBytes: UINT64,
+    pub StrideInBytes: UINT64,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct D3D12_RAYTRACING_GEOMETRY_TRIANGLES_DESC {
+    pub Transform3x4: D3D12_GPU_VIRTUAL_ADDRESS,
+    pub IndexFormat: DXGI_FORMAT,
+    pub VertexFormat: DXGI_FORMAT,
+    pub IndexCount: UINT,
+    pub VertexCount: UINT,
+    pub IndexBuffer: D3D12_GPU_VIRTUAL_ADDRESS,
+    pub VertexBuffer: D3D12_GPU_VIRTUAL_ADDRESS_AND_STRIDE,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct D3D12_RAYTRACING_AABB {
+    pub MinX: FLOAT,
+    pub MinY: FLOAT,
+    pub MinZ: FLOAT,
+    pub MaxX: FLOAT,
+    pub MaxY: FLOAT,
+    pub MaxZ: FLOAT,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct D3D12_RAYTRACING_GEOMETRY_AABBS_DESC {
+    pub AABBCount: UINT64,
+    pub AABBs: D3D12_GPU_VIRTUAL_ADDRESS_AND_STRIDE,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub union D3D12_RAYTRACING_GEOMETRY_DESC_u {
+    pub Triangles: D3D12_RAYTRACING_GEOMETRY_TRIANGLES_DESC,
+    pub AABBs: D3D12_RAYTRACING_GEOMETRY_AABBS_DESC,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct D3D12_RAYTRACING_GEOMETRY_DESC {
+    pub Type: D3D12_RAYTRACING_GEOMETRY_TYPE,
+    pub Flags: D3D12_RAYTRACING_GEOMETRY_FLAGS,
+    pub u: D3D12_RAYTRACING_GEOMETRY_DESC_u,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub union D3D12_BUILD_RAYTRACING_ACCELERATION_STRUCTURE_INPUTS_u {
+    pub InstanceDescs: D3D12_GPU_VIRTUAL_ADDRESS,
+    pub pGeometryDescs: *const D3D12_RAYTRACING_GEOMETRY_DESC,
+    pub ppGeometryDescs: *const *const D3D12_RAYTRACING_GEOMETRY_DESC,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct D3D12_BUILD_RAYTRACING_ACCELERATION_STRUCTURE_INPUTS {
+    pub Type: D3D12_RAYTRACING_ACCELERATION_STRUCTURE_TYPE,
+    pub Flags: D3D12_RAYTRACING_ACCELERATION_STRUCTURE_BUILD_FLAGS,
+    pub NumDescs: UINT,
+    pub DescsLayout: D3D12_ELEMENTS_LAYOUT,
+    pub u: D3D12_BUILD_RAYTRACING_ACCELERATION_STRUCTURE_INPUTS_u,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct D3D12_RAYTRACING_ACCELERATION_STRUCTURE_PREBUILD_INFO {
+    pub ResultDataMaxSizeInBytes: UINT64,
+    pub ScratchDataSizeInBytes: UINT64,
+    pub UpdateScratchDataSizeInBytes: UINT64,
+}
+
+RIDL! {#[uuid(0xde5fa827, 0x9bf9, 0x4f26, 0x89, 0xff, 0xd7, 0xf5, 0x6f, 0xde, 0x38, 0x60)]
+interface ID3D12StateObjectProperties(ID3D12StateObjectPropertiesVtbl): IUnknown(IUnknownVtbl) {
+    fn GetShaderIdentifier(
+        pExportName: LPCWSTR,
+    ) -> *mut c_void,
+    fn GetShaderStackSize(
+        pExportName: LPCWSTR,
+    ) -> UINT64,
+    fn GetPipelineStackSize() -> UINT64,
+    fn SetPipelineStackSize(
+        PipelineStackSizeInBytes: UINT64,
+    ) -> (),
+}}
+
+RIDL! {#[uuid(0xa1533d18, 0x0ac1, 0x4084, 0x85, 0xb9, 0x89, 0xa9, 0x61, 0x16, 0x80, 0x6b)]
+interface ID3D12ProtectedSession(ID3D12ProtectedSessionVtbl):
+    ID3D12DeviceChild(ID3D12DeviceChildVtbl) {
+    fn GetStatusFence(
+        riid: REFIID,
+        ppFence: *mut *mut c_void,
+    ) -> HRESULT,
+    fn GetSessionStatus() -> D3D12_PROTECTED_SESSION_STATUS,
+}}
+
+RIDL! {#[uuid(0x6cd696f4, 0xf289, 0x40cc, 0x80, 0x91, 0x5a, 0x6c, 0x0a, 0x09, 0x9c, 0x3d)]
+interface ID3D12ProtectedResourceSession(ID3D12ProtectedResourceSessionVtbl):
+    ID3D12ProtectedSession(ID3D12ProtectedSessionVtbl) {
+    fn GetDesc(
+        ret: *mut D3D12_PROTECTED_RESOURCE_SESSION_DESC,
+    ) -> *mut D3D12_PROTECTED_RESOURCE_SESSION_DESC,
+}}
+
+RIDL! {#[uuid(0xe667af9f, 0xcd56, 0x4f46, 0x83, 0xce, 0x03, 0x2e, 0x59, 0x5d, 0x70, 0xa8)]
+interface ID3D12LifetimeOwner(ID3D12LifetimeOwnerVtbl): IUnknown(IUnknownVtbl) {
+    fn LifetimeStateUpdated(
+        NewState: D3D12_LIFETIME_STATE,
+    ) -> (),
+}}
+
+RIDL! {#[uuid(0x47016943, 0xfca8, 0x4594, 0x93, 0xea, 0xaf, 0x25, 0x8b, 0x55, 0x34, 0x6d)]
+interface ID3D12StateObject(ID3D12StateObjectVtbl): ID3D12Pageable(ID3D12PageableVtbl) {}}
+
+RIDL! {#[uuid(0x81dadc15, 0x2bad, 0x4392, 0x93, 0xc5, 0x10, 0x13, 0x45, 0xc4, 0xaa, 0x98)]
+interface ID3D12Device3(ID3D12Device3Vtbl): ID3D12Device2(ID3D12Device2Vtbl) {
+    fn OpenExistingHeapFromAddress(
+        pAddress: *const c_void,
+        riid: REFIID,
+        ppvHeap: *mut *mut c_void,
+    ) -> HRESULT,
+    fn OpenExistingHeapFromFileMapping(
+        hFileMapping: HANDLE,
+        riid: REFIID,
+        ppvHeap: *mut *mut c_void,
+    ) -> HRESULT,
+    fn EnqueueMakeResident(
+        Flags: D3D12_RESIDENCY_FLAGS,
+        NumObjects: UINT,
+        ppObjects: *const *mut ID3D12Pageable,
+        pFenceToSignal: *mut ID3D12Fence,
+        FenceValueToSignal: UINT64,
+    ) -> HRESULT,
+}}
+
+RIDL! {#[uuid(0xe865df17, 0xa9ee, 0x46f9, 0xa4, 0x63, 0x30, 0x98, 0x31, 0x5a, 0xa2, 0xe5)]
+interface ID3D12Device4(ID3D12Device4Vtbl): ID3D12Device3(ID3D12Device3Vtbl) {
+    fn CreateCommandList1(
+        nodeMask: UINT,
+        Type: D3D12_COMMAND_LIST_TYPE,
+        flags: D3D12_COMMAND_LIST_FLAGS,
+        riid: REFIID,
+        ppCommandList: *mut *mut c_void,
+    ) -> HRESULT,
+    fn CreateProtectedResourceSession(
+        pDesc: *const D3D12_PROTECTED_RESOURCE_SESSION_DESC,
+        riid: REFIID,
+        ppSession: *mut *mut c_void,
+    ) -> HRESULT,
+    fn CreateCommittedResource1(
+        pHeapProperties: *const D3D12_HEAP_PROPERTIES,
+        HeapFlags: D3D12_HEAP_FLAGS,
+        pDesc: *const D3D12_RESOURCE_DESC,
+        InitialResourceState: D3D12_RESOURCE_STATES,
+        pOptimizedClearValue: *const D3D12_CLEAR_VALUE,
+        pProtectedSession: *mut ID3D12ProtectedResourceSession,
+        riidResource: REFIID,
+        ppvResource: *mut *mut c_void,
+    ) -> HRESULT,
+    fn CreateHeap1(
+        pDesc: *const D3D12_HEAP_DESC,
+        pProtectedSession: *mut ID3D12ProtectedResourceSession,
+        riid: REFIID,
+        ppvHeap: *mut *mut c_void,
+    ) -> HRESULT,
+    fn CreateReservedResource1(
+        pDesc: *const D3D12_RESOURCE_DESC,
+        InitialState: D3D12_RESOURCE_STATES,
+        pOptimizedClearValue: *const D3D12_CLEAR_VALUE,
+        pProtectedSession: *mut ID3D12ProtectedResourceSession,
+        riid: REFIID,
+        ppvResource: *mut *mut c_void,
+    ) -> HRESULT,
+    fn GetResourceAllocationInfo1(
+        ret: *mut D3D12_RESOURCE_ALLOCATION_INFO,
+        visibleMask: UINT,
+        numResourceDescs: UINT,
+        pResourceDescs: *const D3D12_RESOURCE_DESC,
+        pResourceAllocationInfo1: *mut D3D12_RESOURCE_ALLOCATION_INFO1,
+    ) -> *mut D3D12_RESOURCE_ALLOCATION_INFO,
+}}
+
+RIDL! {#[uuid(0x8b4f173b, 0x2fea, 0x4b80, 0x8f, 0x58, 0x43, 0x07, 0x19, 0x1a, 0xb9, 0x5d)]
+interface ID3D12Device5(ID3D12Device5Vtbl): ID3D12Device4(ID3D12Device4Vtbl) {
+    fn CreateLifetimeTracker(
+        pOwner: *mut ID3D12LifetimeOwner,
+        riid: REFIID,
+        ppvTracker: *mut *mut c_void,
+    ) -> HRESULT,
+    fn RemoveDevice() -> (),
+    fn EnumerateMetaCommands(
+        pNumMetaCommands: *mut UINT,
+        pDescs: *mut D3D12_META_COMMAND_DESC,
+    ) -> HRESULT,
+    fn EnumerateMetaCommandParameters(
+        CommandId: REFGUID,
+        Stage: D3D12_META_COMMAND_PARAMETER_STAGE,
+        pTotalStructureSizeInBytes: *mut UINT,
+        pParameterCount: *mut UINT,
+        pParameterDescs: *mut D3D12_META_COMMAND_PARAMETER_DESC,
+    ) -> HRESULT,
+    fn CreateMetaCommand(
+        CommandId: REFGUID,
+        NodeMask: UINT,
+        pCreationParametersData: *const c_void,
+        CreationParametersDataSizeInBytes: SIZE_T,
+        riid: REFIID,
+        ppMetaCommand: *mut *mut c_void,
+    ) -> HRESULT,
+    fn CreateStateObject(
+        pDesc: *const D3D12_STATE_OBJECT_DESC,
+        riid: REFIID,
+        ppStateObject: *mut *mut c_void,
+    ) -> HRESULT,
+    fn GetRaytracingAccelerationStructurePrebuildInfo(
+        pDesc: *const D3D12_BUILD_RAYTRACING_ACCELERATION_STRUCTURE_INPUTS,
+        pInfo: *mut D3D12_RAYTRACING_ACCELERATION_STRUCTURE_PREBUILD_INFO,
+    ) -> (),
+    fn CheckDriverMatchingIdentifier(
+        SerializedDataType: D3D12_SERIALIZED_DATA_TYPE,
+        pIdentifierToCheck: *const D3D12_SERIALIZED_DATA_DRIVER_MATCHING_IDENTIFIER,
+    ) -> D3D12_DRIVER_MATCHING_IDENTIFIER_STATUS,
+}}
+
+RIDL! {#[uuid(0xc70b221b, 0x40e4, 0x4a17, 0x89, 0xaf, 0x02, 0x5a, 0x07, 0x27, 0xa6, 0xdc)]
+interface ID3D12Device6(ID3D12Device6Vtbl): ID3D12Device5(ID3D12Device5Vtbl) {
+    fn SetBackgroundProcessingMode(
+        Mode: D3D12_BACKGROUND_PROCESSING_MODE,
+        MeasurementsAction: D3D12_MEASUREMENTS_ACTION,
+        hEventToSignalUponCompletion: HANDLE,
+        pbFurtherMeasurementsDesired: *mut BOOL,
+    ) -> HRESULT,
+}}
+
+RIDL! {#[uuid(0x5c014b53, 0x68a1, 0x4b9b, 0x8b, 0xd1, 0xdd, 0x60, 0x46, 0xb9, 0x35, 0x8b)]
+interface ID3D12Device7(ID3D12Device7Vtbl): ID3D12Device6(ID3D12Device6Vtbl) {
+    fn AddToStateObject(
+        pAddition: *const D3D12_STATE_OBJECT_DESC,
+        pStateObjectToGrowFrom: *mut ID3D12StateObject,
+        riid: REFIID,
+        ppNewStateObject: *mut *mut c_void,
+    ) -> HRESULT,
+    fn CreateProtectedResourceSession1(
+        pDesc: *const D3D12_PROTECTED_RESOURCE_SESSION_DESC1,
+        riid: REFIID,
+        ppSession: *mut *mut c_void,
+    ) -> HRESULT,
+}}
+
+RIDL! {#[uuid(0x9218e6bb, 0xf944, 0x4f7e, 0xa7, 0x5c, 0xb1, 0xb2, 0xc7, 0xb7, 0x01, 0xf3)]
+interface ID3D12Device8(ID3D12Device8Vtbl): ID3D12Device7(ID3D12Device7Vtbl) {
+    fn GetResourceAllocationInfo2(
+        ret: *mut D3D12_RESOURCE_ALLOCATION_INFO,
+        visibleMask: UINT,
+        numResourceDescs: UINT,
+        pResourceDescs: *const D3D12_RESOURCE_DESC1,
+        pResourceAllocationInfo1: *mut D3D12_RESOURCE_ALLOCATION_INFO1,
+    ) -> *mut D3D12_RESOURCE_ALLOCATION_INFO,
+    fn CreateCommittedResource2(
+        pHeapProperties: *const D3D12_HEAP_PROPERTIES,
+        HeapFlags: D3D12_HEAP_FLAGS,
+        pDesc: *const D3D12_RESOURCE_DESC1,
+        InitialResourceState: D3D12_RESOURCE_STATES,
+        pOptimizedClearValue: *const D3D12_CLEAR_VALUE,
+        pProtectedSession: *mut ID3D12ProtectedResourceSession,
+        riidResource: REFIID,
+        ppvResource: *mut *mut c_void,
+    ) -> HRESULT,
+    fn CreatePlacedResource1(
+        pHeap: *mut ID3D12Heap,
+        HeapOffset: UINT64,
+        pDesc: *const D3D12_RESOURCE_DESC1,
+        InitialState: D3D12_RESOURCE_STATES,
+        pOptimizedClearValue: *const D3D12_CLEAR_VALUE,
+        riid: REFIID,
+        ppvResource: *mut *mut c_void,
+    ) -> HRESULT,
+    fn CreateSamplerFeedbackUnorderedAccessView(
+        pTargetedResource: *mut ID3D12Resource,
+        pFeedbackResource: *mut ID3D12Resource,
+        DestDescriptor: D3D12_CPU_DESCRIPTOR_HANDLE,
+    ) -> (),
+    fn GetCopyableFootprints1(
+        pResourceDesc: *const D3D12_RESOURCE_DESC1,
+        FirstSubresource: UINT,
+        NumSubresources: UINT,
+        BaseOffset: UINT64,
+        pLayouts: *mut D3D12_PLACED_SUBRESOURCE_FOOTPRINT,
+        pNumRows: *mut UINT,
+        pRowSizeInBytes: *mut UINT64,
+        pTotalBytes: *mut UINT64,
+    ) -> (),
+}}
+
+RIDL! {#[uuid(0x4c80e962, 0xf032, 0x4f60, 0xbc, 0x9e, 0xeb, 0xc2, 0xcf, 0xa1, 0xd8, 0x3c)]
+interface ID3D12Device9(ID3D12Device9Vtbl): ID3D12Device8(ID3D12Device8Vtbl) {
+    fn CreateShaderCacheSession(
+        pDesc: *const D3D12_SHADER_CACHE_SESSION_DESC,
+        riid: REFIID,
+        ppvSession: *mut *mut c_void,
+    ) -> HRESULT,
+    fn ShaderCacheControl(
+        Kinds: D3D12_SHADER_CACHE_KIND_FLAGS,
+        Control: D3D12_SHADER_CACHE_CONTROL_FLAGS,
+    ) -> HRESULT,
+    fn CreateCommandQueue1(
+        pDesc: *const D3D12_COMMAND_QUEUE_DESC,
+        CreatorID: REFIID,
+        riid: REFIID,
+        ppCommandQueue: *mut *mut c_void,
+    ) -> HRESULT,
+}}
+
+RIDL! {#[uuid(0x517f8718, 0xaa66, 0x49f9, 0xb0, 0x2b, 0xa7, 0xab, 0x89, 0xc0, 0x60, 0x31)]
+interface ID3D12Device10(ID3D12Device10Vtbl): ID3D12Device9(ID3D12Device9Vtbl) {
+    fn CreateCommittedResource3(
+        pHeapProperties: *const D3D12_HEAP_PROPERTIES,
+        HeapFlags: D3D12_HEAP_FLAGS,
+        pDesc: *const D3D12_RESOURCE_DESC1,
+        InitialLayout: D3D12_BARRIER_LAYOUT,
+        pOptimizedClearValue: *const D3D12_CLEAR_VALUE,
+        pProtectedSession: *mut ID3D12ProtectedResourceSession,
+        NumCastableFormats: UINT32,
+        pCastableFormats: *const DXGI_FORMAT,
+        riidResource: REFIID,
+        ppvResource: *mut *mut c_void,
+    ) -> HRESULT,
+    fn CreatePlacedResource2(
+        pHeap: *mut ID3D12Heap,
+        HeapOffset: UINT64,
+        pDesc: *const D3D12_RESOURCE_DESC1,
+        InitialLayout: D3D12_BARRIER_LAYOUT,
+        pOptimizedClearValue: *const D3D12_CLEAR_VALUE,
+        NumCastableFormats: UINT32,
+        pCastableFormats: *const DXGI_FORMAT,
+        riid: REFIID,
+        ppvResource: *mut *mut c_void,
+    ) -> HRESULT,
+    fn CreateReservedResource2(
+        pDesc: *const D3D12_RESOURCE_DESC,
+        InitialLayout: D3D12_BARRIER_LAYOUT,
+        pOptimizedClearValue: *const D3D12_CLEAR_VALUE,
+        pProtectedSession: *mut ID3D12ProtectedResourceSession,
+        NumCastableFormats: UINT32,
+        pCastableFormats: *const DXGI_FORMAT,
+        riid: REFIID,
+        ppvResource: *mut *mut c_void,
+    ) -> HRESULT,
+}}
+
+// Graphics command list interfaces.
+
+pub type D3D12_WRITEBUFFERIMMEDIATE_MODE = u32;
+pub const D3D12_WRITEBUFFERIMMEDIATE_MODE_DEFAULT: D3D12_WRITEBUFFERIMMEDIATE_MODE = 0;
+pub const D3D12_WRITEBUFFERIMMEDIATE_MODE_MARKER_IN: D3D12_WRITEBUFFERIMMEDIATE_MODE = 0x1;
+pub const D3D12_WRITEBUFFERIMMEDIATE_MODE_MARKER_OUT: D3D12_WRITEBUFFERIMMEDIATE_MODE = 0x2;
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct D3D12_WRITEBUFFERIMMEDIATE_PARAMETER {
+    pub Dest: D3D12_GPU_VIRTUAL_ADDRESS,
+    pub Value: UINT32,
+}
+
+pub type D3D12_RENDER_PASS_BEGINNING_ACCESS_TYPE = u32;
+pub const D3D12_RENDER_PASS_BEGINNING_ACCESS_TYPE_DISCARD: D3D12_RENDER_PASS_BEGINNING_ACCESS_TYPE =
+    0;
+pub const D3D12_RENDER_PASS_BEGINNING_ACCESS_TYPE_PRESERVE:
+    D3D12_RENDER_PASS_BEGINNING_ACCESS_TYPE = 1;
+pub const D3D12_RENDER_PASS_BEGINNING_ACCESS_TYPE_CLEAR: D3D12_RENDER_PASS_BEGINNING_ACCESS_TYPE =
+    2;
+pub const D3D12_RENDER_PASS_BEGINNING_ACCESS_TYPE_NO_ACCESS:
+    D3D12_RENDER_PASS_BEGINNING_ACCESS_TYPE = 3;
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct D3D12_RENDER_PASS_BEGINNING_ACCESS_CLEAR_PARAMETERS {
+    pub ClearValue: D3D12_CLEAR_VALUE,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub union D3D12_RENDER_PASS_BEGINNING_ACCESS_u {
+    pub Clear: D3D12_RENDER_PASS_BEGINNING_ACCESS_CLEAR_PARAMETERS,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct D3D12_RENDER_PASS_BEGINNING_ACCESS {
+    pub Type: D3D12_RENDER_PASS_BEGINNING_ACCESS_TYPE,
+    pub u: D3D12_RENDER_PASS_BEGINNING_ACCESS_u,
+}
+
+pub type D3D12_RENDER_PASS_ENDING_ACCESS_TYPE = u32;
+pub const D3D12_RENDER_PASS_ENDING_ACCESS_TYPE_DISCARD: D3D12_RENDER_PASS_ENDING_ACCESS_TYPE = 0;
+pub const D3D12_RENDER_PASS_ENDING_ACCESS_TYPE_PRESERVE: D3D12_RENDER_PASS_ENDING_ACCESS_TYPE = 1;
+pub const D3D12_RENDER_PASS_ENDING_ACCESS_TYPE_RESOLVE: D3D12_RENDER_PASS_ENDING_ACCESS_TYPE = 2;
+pub const D3D12_RENDER_PASS_ENDING_ACCESS_TYPE_NO_ACCESS: D3D12_RENDER_PASS_ENDING_ACCESS_TYPE = 3;
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct D3D12_RENDER_PASS_ENDING_ACCESS_RESOLVE_SUBRESOURCE_PARAMETERS {
+    pub SrcSubresource: UINT,
+    pub DstSubresource: UINT,
+    pub DstX: UINT,
+    pub DstY: UINT,
+    pub SrcRect: D3D12_RECT,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct D3D12_RENDER_PASS_ENDING_ACCESS_RESOLVE_PARAMETERS {
+    pub pSrcResource: *mut ID3D12Resource,
+    pub pDstResource: *mut ID3D12Resource,
+    pub SubresourceCount: UINT,
+    pub pSubresourceParameters:
+        *const D3D12_RENDER_PASS_ENDING_ACCESS_RESOLVE_SUBRESOURCE_PARAMETERS,
+    pub Format: DXGI_FORMAT,
+    pub ResolveMode: D3D12_RESOLVE_MODE,
+    pub PreserveResolveSource: BOOL,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub union D3D12_RENDER_PASS_ENDING_ACCESS_u {
+    pub Resolve: D3D12_RENDER_PASS_ENDING_ACCESS_RESOLVE_PARAMETERS,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct D3D12_RENDER_PASS_ENDING_ACCESS {
+    pub Type: D3D12_RENDER_PASS_ENDING_ACCESS_TYPE,
+    pub u: D3D12_RENDER_PASS_ENDING_ACCESS_u,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct D3D12_RENDER_PASS_RENDER_TARGET_DESC {
+    pub cpuDescriptor: D3D12_CPU_DESCRIPTOR_HANDLE,
+    pub BeginningAccess: D3D12_RENDER_PASS_BEGINNING_ACCESS,
+    pub EndingAccess: D3D12_RENDER_PASS_ENDING_ACCESS,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct D3D12_RENDER_PASS_DEPTH_STENCIL_DESC {
+    pub cpuDescriptor: D3D12_CPU_DESCRIPTOR_HANDLE,
+    pub DepthBeginningAccess: D3D12_RENDER_PASS_BEGINNING_ACCESS,
+    pub StencilBeginningAccess: D3D12_RENDER_PASS_BEGINNING_ACCESS,
+    pub DepthEndingAccess: D3D12_RENDER_PASS_ENDING_ACCESS,
+    pub StencilEndingAccess: D3D12_RENDER_PASS_ENDING_ACCESS,
+}
+
+pub type D3D12_RENDER_PASS_FLAGS = u32;
+pub const D3D12_RENDER_PASS_FLAG_NONE: D3D12_RENDER_PASS_FLAGS = 0;
+pub const D3D12_RENDER_PASS_FLAG_ALLOW_UAV_WRITES: D3D12_RENDER_PASS_FLAGS = 0x1;
+pub const D3D12_RENDER_PASS_FLAG_SUSPENDING_PASS: D3D12_RENDER_PASS_FLAGS = 0x2;
+pub const D3D12_RENDER_PASS_FLAG_RESUMING_PASS: D3D12_RENDER_PASS_FLAGS = 0x4;
+
+pub type D3D12_RAYTRACING_ACCELERATION_STRUCTURE_POSTBUILD_INFO_TYPE = u32;
+pub const D3D12_RAYTRACING_ACCELERATION_STRUCTURE_POSTBUILD_INFO_COMPACTED_SIZE:
+    D3D12_RAYTRACING_ACCELERATION_STRUCTURE_POSTBUILD_INFO_TYPE = 0;
+pub const D3D12_RAYTRACING_ACCELERATION_STRUCTURE_POSTBUILD_INFO_TOOLS_VISUALIZATION:
+    D3D12_RAYTRACING_ACCELERATION_STRUCTURE_POSTBUILD_INFO_TYPE = 0x1;
+pub const D3D12_RAYTRACING_ACCELERATION_STRUCTURE_POSTBUILD_INFO_SERIALIZATION:
+    D3D12_RAYTRACING_ACCELERATION_STRUCTURE_POSTBUILD_INFO_TYPE = 0x2;
+pub const D3D12_RAYTRACING_ACCELERATION_STRUCTURE_POSTBUILD_INFO_CURRENT_SIZE:
+    D3D12_RAYTRACING_ACCELERATION_STRUCTURE_POSTBUILD_INFO_TYPE = 0x3;
+
+pub type D3D12_RAYTRACING_ACCELERATION_STRUCTURE_COPY_MODE = u32;
+pub const D3D12_RAYTRACING_ACCELERATION_STRUCTURE_COPY_MODE_CLONE:
+    D3D12_RAYTRACING_ACCELERATION_STRUCTURE_COPY_MODE = 0;
+pub const D3D12_RAYTRACING_ACCELERATION_STRUCTURE_COPY_MODE_COMPACT:
+    D3D12_RAYTRACING_ACCELERATION_STRUCTURE_COPY_MODE = 0x1;
+pub const D3D12_RAYTRACING_ACCELERATION_STRUCTURE_COPY_MODE_VISUALIZATION_DECODE_FOR_TOOLS:
+    D3D12_RAYTRACING_ACCELERATION_STRUCTURE_COPY_MODE = 0x2;
+pub const D3D12_RAYTRACING_ACCELERATION_STRUCTURE_COPY_MODE_SERIALIZE:
+    D3D12_RAYTRACING_ACCELERATION_STRUCTURE_COPY_MODE = 0x3;
+pub const D3D12_RAYTRACING_ACCELERATION_STRUCTURE_COPY_MODE_DESERIALIZE:
+    D3D12_RAYTRACING_ACCELERATION_STRUCTURE_COPY_MODE = 0x4;
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct D3D12_BUILD_RAYTRACING_ACCELERATION_STRUCTURE_DESC {
+    pub DestAccelerationStructureData: D3D12_GPU_VIRTUAL_ADDRESS,
+    pub Inputs: D3D12_BUILD_RAYTRACING_ACCELERATION_STRUCTURE_INPUTS,
+    pub SourceAccelerationStructureData: D3D12_GPU_VIRTUAL_ADDRESS,
+    pub ScratchAccelerationStructureData: D3D12_GPU_VIRTUAL_ADDRESS,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct D3D12_RAYTRACING_ACCELERATION_STRUCTURE_POSTBUILD_INFO_DESC {
+    pub DestBuffer: D3D12_GPU_VIRTUAL_ADDRESS,
+    pub InfoType: D3D12_RAYTRACING_ACCELERATION_STRUCTURE_POSTBUILD_INFO_TYPE,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct D3D12_DISPATCH_RAYS_DESC {
+    pub RayGenerationShaderRecord: D3D12_GPU_VIRTUAL_ADDRESS_RANGE,
+    pub MissShaderTable: D3D12_GPU_VIRTUAL_ADDRESS_RANGE_AND_STRIDE,
+    pub HitGroupTable: D3D12_GPU_VIRTUAL_ADDRESS_RANGE_AND_STRIDE,
+    pub CallableShaderTable: D3D12_GPU_VIRTUAL_ADDRESS_RANGE_AND_STRIDE,
+    pub Width: UINT,
+    pub Height: UINT,
+    pub Depth: UINT,
+}
+
+pub type D3D12_SHADING_RATE = u32;
+pub const D3D12_SHADING_RATE_1X1: D3D12_SHADING_RATE = 0;
+pub const D3D12_SHADING_RATE_1X2: D3D12_SHADING_RATE = 0x1;
+pub const D3D12_SHADING_RATE_2X1: D3D12_SHADING_RATE = 0x4;
+pub const D3D12_SHADING_RATE_2X2: D3D12_SHADING_RATE = 0x5;
+pub const D3D12_SHADING_RATE_2X4: D3D12_SHADING_RATE = 0x6;
+pub const D3D12_SHADING_RATE_4X2: D3D12_SHADING_RATE = 0x9;
+pub const D3D12_SHADING_RATE_4X4: D3D12_SHADING_RATE = 0xa;
+
+pub type D3D12_SHADING_RATE_COMBINER = u32;
+pub const D3D12_SHADING_RATE_COMBINER_PASSTHROUGH: D3D12_SHADING_RATE_COMBINER = 0;
+pub const D3D12_SHADING_RATE_COMBINER_OVERRIDE: D3D12_SHADING_RATE_COMBINER = 1;
+pub const D3D12_SHADING_RATE_COMBINER_MIN: D3D12_SHADING_RATE_COMBINER = 2;
+pub const D3D12_SHADING_RATE_COMBINER_MAX: D3D12_SHADING_RATE_COMBINER = 3;
+pub const D3D12_SHADING_RATE_COMBINER_SUM: D3D12_SHADING_RATE_COMBINER = 4;
+
+pub const D3D12_RESOURCE_STATE_SHADING_RATE_SOURCE: D3D12_RESOURCE_STATES = 0x1000000;
+
+pub type D3D12_BARRIER_TYPE = u32;
+pub const D3D12_BARRIER_TYPE_GLOBAL: D3D12_BARRIER_TYPE = 0;
+pub const D3D12_BARRIER_TYPE_TEXTURE: D3D12_BARRIER_TYPE = 1;
+pub const D3D12_BARRIER_TYPE_BUFFER: D3D12_BARRIER_TYPE = 2;
+
+pub type D3D12_BARRIER_SYNC = u32;
+pub type D3D12_BARRIER_ACCESS = u32;
+pub type D3D12_TEXTURE_BARRIER_FLAGS = u32;
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct D3D12_GLOBAL_BARRIER {
+    pub SyncBefore: D3D12_BARRIER_SYNC,
+    pub SyncAfter: D3D12_BARRIER_SYNC,
+    pub AccessBefore: D3D12_BARRIER_ACCESS,
+    pub AccessAfter: D3D12_BARRIER_ACCESS,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct D3D12_BARRIER_SUBRESOURCE_RANGE {
+    pub IndexOrFirstMipLevel: UINT,
+    pub NumMipLevels: UINT,
+    pub FirstArraySlice: UINT,
+    pub NumArraySlices: UINT,
+    pub FirstPlane: UINT,
+    pub NumPlanes: UINT,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct D3D12_TEXTURE_BARRIER {
+    pub SyncBefore: D3D12_BARRIER_SYNC,
+    pub SyncAfter: D3D12_BARRIER_SYNC,
+    pub AccessBefore: D3D12_BARRIER_ACCESS,
+    pub AccessAfter: D3D12_BARRIER_ACCESS,
+    pub LayoutBefore: D3D12_BARRIER_LAYOUT,
+    pub LayoutAfter: D3D12_BARRIER_LAYOUT,
+    pub pResource: *mut ID3D12Resource,
+    pub Subresources: D3D12_BARRIER_SUBRESOURCE_RANGE,
+    pub Flags: D3D12_TEXTURE_BARRIER_FLAGS,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct D3D12_BUFFER_BARRIER {
+    pub SyncBefore: D3D12_BARRIER_SYNC,
+    pub SyncAfter: D3D12_BARRIER_SYNC,
+    pub AccessBefore: D3D12_BARRIER_ACCESS,
+    pub AccessAfter: D3D12_BARRIER_ACCESS,
+    pub pResource: *mut ID3D12Resource,
+    pub Offset: UINT64,
+    pub Size: UINT64,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub union D3D12_BARRIER_GROUP_u {
+    pub pGlobalBarriers: *const D3D12_GLOBAL_BARRIER,
+    pub pTextureBarriers: *const D3D12_TEXTURE_BARRIER,
+    pub pBufferBarriers: *const D3D12_BUFFER_BARRIER,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct D3D12_BARRIER_GROUP {
+    pub Type: D3D12_BARRIER_TYPE,
+    pub NumBarriers: UINT32,
+    pub u: D3D12_BARRIER_GROUP_u,
+}
+
+RIDL! {#[uuid(0x283e78d8, 0x90c9, 0x4e9d, 0x9e, 0xf3, 0x0c, 0x56, 0x47, 0x11, 0xc3, 0x45)]
+interface ID3D12MetaCommand(ID3D12MetaCommandVtbl): ID3D12Pageable(ID3D12PageableVtbl) {
+    fn GetRequiredParameterResourceSize(
+        Stage: D3D12_META_COMMAND_PARAMETER_STAGE,
+        ParameterIndex: UINT,
+    ) -> UINT64,
+}}
+
+// `winapi` leaves out `SetViewInstanceMask`, which `ID3D12GraphicsCommandList2`
+// and later rely on for the layout of their vtables.
+RIDL! {#[uuid(0x553103fb, 0x1fe7, 0x4557, 0xbb, 0x38, 0x94, 0x6d, 0x7d, 0x0e, 0x7c, 0xa7)]
+interface ID3D12GraphicsCommandList1(ID3D12GraphicsCommandList1Vtbl):
+    ID3D12GraphicsCommandList(ID3D12GraphicsCommandListVtbl) {
+    fn AtomicCopyBufferUINT(
+        pDstBuffer: *mut ID3D12Resource,
+        DstOffset: UINT64,
+        pSrcBuffer: *mut ID3D12Resource,
+        SrcOffset: UINT64,
+        Dependencies: UINT,
+        ppDependentResources: *const *mut ID3D12Resource,
+        pDependentSubresourceRanges: *const D3D12_SUBRESOURCE_RANGE_UINT64,
+    ) -> (),
+    fn AtomicCopyBufferUINT64(
+        pDstBuffer: *mut ID3D12Resource,
+        DstOffset: UINT64,
+        pSrcBuffer: *mut ID3D12Resource,
+        SrcOffset: UINT64,
+        Dependencies: UINT,
+        ppDependentResources: *const *mut ID3D12Resource,
+        pDependentSubresourceRanges: *const D3D12_SUBRESOURCE_RANGE_UINT64,
+    ) -> (),
+    fn OMSetDepthBounds(
+        Min: FLOAT,
+        Max: FLOAT,
+    ) -> (),
+    fn SetSamplePositions(
+        NumSamplesPerPixel: UINT,
+        NumPixels: UINT,
+        pSamplePositions: *mut D3D12_SAMPLE_POSITION,
+    ) -> (),
+    fn ResolveSubresourceRegion(
+        pDstResource: *mut ID3D12Resource,
+        DstSubresource: UINT,
+        DstX: UINT,
+        DstY: UINT,
+        pSrcResource: *mut ID3D12Resource,
+        SrcSubresource: UINT,
+        pSrcRect: *mut D3D12_RECT,
+        Format: DXGI_FORMAT,
+        ResolveMode: D3D12_RESOLVE_MODE,
+    ) -> (),
+    fn SetViewInstanceMask(
+        Mask: UINT,
+    ) -> (),
+}}
+
+RIDL! {#[uuid(0x38c3e585, 0xff17, 0x412c, 0x91, 0x50, 0x4f, 0xc6, 0xf9, 0xd7, 0x2a, 0x28)]
+interface ID3D12GraphicsCommandList2(ID3D12GraphicsCommandList2Vtbl):
+    ID3D12GraphicsCommandList1(ID3D12GraphicsCommandList1Vtbl) {
+    fn WriteBufferImmediate(
+        Count: UINT,
+        pParams: *const D3D12_WRITEBUFFERIMMEDIATE_PARAMETER,
+        pModes: *const D3D12_WRITEBUFFERIMMEDIATE_MODE,
+    ) -> (),
+}}
+
+RIDL! {#[uuid(0x6fda83a7, 0xb84c, 0x4e38, 0x9a, 0xc8, 0xc7, 0xbd, 0x22, 0x01, 0x6b, 0x3d)]
+interface ID3D12GraphicsCommandList3(ID3D12GraphicsCommandList3Vtbl):
+    ID3D12GraphicsCommandList2(ID3D12GraphicsCommandList2Vtbl) {
+    fn SetProtectedResourceSession(
+        pProtectedResourceSession: *mut ID3D12ProtectedResourceSession,
+    ) -> (),
+}}
+
+RIDL! {#[uuid(0x8754318e, 0xd3a9, 0x4541, 0x98, 0xcf, 0x64, 0x5b, 0x50, 0xdc, 0x48, 0x74)]
+interface ID3D12GraphicsCommandList4(ID3D12GraphicsCommandList4Vtbl):
+    ID3D12GraphicsCommandList3(ID3D12GraphicsCommandList3Vtbl) {
+    fn BeginRenderPass(
+        NumRenderTargets: UINT,
+        pRenderTargets: *const D3D12_RENDER_PASS_RENDER_TARGET_DESC,
+        pDepthStencil: *const D3D12_RENDER_PASS_DEPTH_STENCIL_DESC,
+        Flags: D3D12_RENDER_PASS_FLAGS,
+    ) -> (),
+    fn EndRenderPass() -> (),
+    fn InitializeMetaCommand(
+        pMetaCommand: *mut ID3D12MetaCommand,
+        pInitializationParametersData: *const c_void,
+        InitializationParametersDataSizeInBytes: SIZE_T,
+    ) -> (),
+    fn ExecuteMetaCommand(
+        pMetaCommand: *mut ID3D12MetaCommand,
+        pExecutionParametersData: *const c_void,
+        ExecutionParametersDataSizeInBytes: SIZE_T,
+    ) -> (),
+    fn BuildRaytracingAccelerationStructure(
+        pDesc: *const D3D12_BUILD_RAYTRACING_ACCELERATION_STRUCTURE_DESC,
+        NumPostbuildInfoDescs: UINT,
+        pPostbuildInfoDescs: *const D3D12_RAYTRACING_ACCELERATION_STRUCTURE_POSTBUILD_INFO_DESC,
+    ) -> (),
+    fn EmitRaytracingAccelerationStructurePostbuildInfo(
+        pDesc: *const D3D12_RAYTRACING_ACCELERATION_STRUCTURE_POSTBUILD_INFO_DESC,
+        NumSourceAccelerationStructures: UINT,
+        pSourceAccelerationStructureData: *const D3D12_GPU_VIRTUAL_ADDRESS,
+    ) -> (),
+    fn CopyRaytracingAccelerationStructure(
+        DestAccelerationStructureData: D3D12_GPU_VIRTUAL_ADDRESS,
+        SourceAccelerationStructureData: D3D12_GPU_VIRTUAL_ADDRESS,
+        Mode: D3D12_RAYTRACING_ACCELERATION_STRUCTURE_COPY_MODE,
+    ) -> (),
+    fn SetPipelineState1(
+        pStateObject: *mut ID3D12StateObject,
+    ) -> (),
+    fn DispatchRays(
+        pDesc: *const D3D12_DISPATCH_RAYS_DESC,
+    ) -> (),
+}}
+
+RIDL! {#[uuid(0x55050859, 0x4024, 0x474c, 0x87, 0xf5, 0x64, 0x72, 0xea, 0xee, 0x44, 0xea)]
+interface ID3D12GraphicsCommandList5(ID3D12GraphicsCommandList5Vtbl):
+    ID3D12GraphicsCommandList4(ID3D12GraphicsCommandList4Vtbl) {
+    fn RSSetShadingRate(
+        baseShadingRate: D3D12_SHADING_RATE,
+        combiners: *const D3D12_SHADING_RATE_COMBINER,
+    ) -> (),
+    fn RSSetShadingRateImage(
+        shadingRateImage: *mut ID3D12Resource,
+    ) -> (),
+}}
+
+RIDL! {#[uuid(0xc3827890, 0xe548, 0x4cfa, 0x96, 0xcf, 0x56, 0x89, 0xa9, 0x37, 0x0f, 0x80)]
+interface ID3D12GraphicsCommandList6(ID3D12GraphicsCommandList6Vtbl):
+    ID3D12GraphicsCommandList5(ID3D12GraphicsCommandList5Vtbl) {
+    fn DispatchMesh(
+        ThreadGroupCountX: UINT,
+        ThreadGroupCountY: UINT,
+        ThreadGroupCountZ: UINT,
+    ) -> (),
+}}
+
+RIDL! {#[uuid(0xdd171223, 0x8b61, 0x4769, 0x90, 0xe3, 0x16, 0x0c, 0xcd, 0xe4, 0xe2, 0xc1)]
+interface ID3D12GraphicsCommandList7(ID3D12GraphicsCommandList7Vtbl):
+    ID3D12GraphicsCommandList6(ID3D12GraphicsCommandList6Vtbl) {
+    fn Barrier(
+        NumBarrierGroups: UINT32,
+        pBarrierGroups: *const D3D12_BARRIER_GROUP,
+    ) -> (),
+}}
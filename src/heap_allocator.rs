@@ -0,0 +1,489 @@
+//! Placed resource allocators
+
+use crate::{
+    Device, Heap, HeapFlags, HeapProperties, HeapType, Resource, ResourceDesc, ResourceFlags,
+    ResourceHeapTier, HRESULT,
+};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use winapi::{shared::winerror, um::d3d12};
+
+/// Placement alignment of buffers and single sampled textures.
+pub const RESOURCE_PLACEMENT_ALIGNMENT: u64 =
+    d3d12::D3D12_DEFAULT_RESOURCE_PLACEMENT_ALIGNMENT as _;
+/// Placement alignment of multisampled textures.
+pub const MSAA_RESOURCE_PLACEMENT_ALIGNMENT: u64 =
+    d3d12::D3D12_DEFAULT_MSAA_RESOURCE_PLACEMENT_ALIGNMENT as _;
+
+/// Usage statistics of one or more block allocators.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct BlockStats {
+    pub size: u64,
+    /// Bytes of the allocated blocks, including the padding to their power of two size.
+    pub allocated: u64,
+    pub allocation_count: usize,
+    pub free_block_count: usize,
+    pub largest_free_block: u64,
+}
+
+impl BlockStats {
+    pub fn free(&self) -> u64 {
+        self.size - self.allocated
+    }
+
+    /// Share of the free bytes outside of the largest free block, from 0 when
+    /// all free memory is contiguous to close to 1.
+    pub fn fragmentation(&self) -> f32 {
+        match self.free() {
+            0 => 0.0,
+            free => 1.0 - self.largest_free_block as f32 / free as f32,
+        }
+    }
+
+    fn merge(self, other: BlockStats) -> Self {
+        BlockStats {
+            size: self.size + other.size,
+            allocated: self.allocated + other.allocated,
+            allocation_count: self.allocation_count + other.allocation_count,
+            free_block_count: self.free_block_count + other.free_block_count,
+            largest_free_block: self.largest_free_block.max(other.largest_free_block),
+        }
+    }
+}
+
+/// Buddy allocator of offsets in `[0, size)`, independent of any heap.
+///
+/// Allocations are rounded up to power of two blocks of at least `min_block`
+/// bytes, which are aligned to their size.
+///
+/// ```rust
+/// # use d3d12::BuddyAllocator;
+/// let mut allocator = BuddyAllocator::new(1 << 20, 1 << 16);
+/// let a = allocator.allocate(100_000, 1 << 16).unwrap();
+/// let b = allocator.allocate(1 << 16, 1 << 16).unwrap();
+/// assert_eq!((a, b), (0, 1 << 17));
+/// assert_eq!(allocator.stats().allocated, 3 << 16);
+/// assert_eq!(allocator.allocate(1 << 20, 1 << 16), None);
+///
+/// allocator.free(a);
+/// allocator.free(b);
+/// assert!(allocator.is_empty());
+/// assert_eq!(allocator.stats().largest_free_block, 1 << 20);
+/// ```
+#[derive(Debug)]
+pub struct BuddyAllocator {
+    size: u64,
+    min_block: u64,
+    // Offsets of the free blocks of `min_block << order`, indexed by order.
+    free: Vec<BTreeSet<u64>>,
+    // Order of each allocated block, by offset.
+    allocated: BTreeMap<u64, usize>,
+}
+
+impl BuddyAllocator {
+    /// # Panics
+    ///
+    /// Panics if `size` or `min_block` is not a power of two, or if `size` is
+    /// smaller than `min_block`.
+    pub fn new(size: u64, min_block: u64) -> Self {
+        assert!(size.is_power_of_two() && min_block.is_power_of_two());
+        assert!(size >= min_block);
+
+        let orders = (size / min_block).trailing_zeros() as usize + 1;
+        let mut free = vec![BTreeSet::new(); orders];
+        free[orders - 1].insert(0);
+
+        BuddyAllocator {
+            size,
+            min_block,
+            free,
+            allocated: BTreeMap::new(),
+        }
+    }
+
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.allocated.is_empty()
+    }
+
+    fn block_size(&self, order: usize) -> u64 {
+        self.min_block << order
+    }
+
+    /// Allocates `size` bytes aligned to `alignment`, which must be a power of
+    /// two, and returns their offset.
+    pub fn allocate(&mut self, size: u64, alignment: u64) -> Option<u64> {
+        debug_assert!(alignment.is_power_of_two());
+        if size == 0 || size > self.size || alignment > self.size {
+            return None;
+        }
+        let block = size.next_power_of_two().max(alignment).max(self.min_block);
+        let order = (block / self.min_block).trailing_zeros() as usize;
+
+        let mut current = (order..self.free.len()).find(|&order| !self.free[order].is_empty())?;
+        let offset = *self.free[current].iter().next().unwrap();
+        self.free[current].remove(&offset);
+        while current > order {
+            current -= 1;
+            let buddy = offset + self.block_size(current);
+            self.free[current].insert(buddy);
+        }
+
+        self.allocated.insert(offset, order);
+        Some(offset)
+    }
+
+    /// Frees the block allocated at `offset`, merging it with its free buddies.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no block is allocated at `offset`.
+    pub fn free(&mut self, offset: u64) {
+        let mut order = self
+            .allocated
+            .remove(&offset)
+            .unwrap_or_else(|| panic!("no block allocated at {}", offset));
+
+        let mut offset = offset;
+        while order + 1 < self.free.len() {
+            let buddy = offset ^ self.block_size(order);
+            if !self.free[order].remove(&buddy) {
+                break;
+            }
+            offset = offset.min(buddy);
+            order += 1;
+        }
+        self.free[order].insert(offset);
+    }
+
+    pub fn stats(&self) -> BlockStats {
+        BlockStats {
+            size: self.size,
+            allocated: self
+                .allocated
+                .values()
+                .map(|&order| self.block_size(order))
+                .sum(),
+            allocation_count: self.allocated.len(),
+            free_block_count: self.free.iter().map(|blocks| blocks.len()).sum(),
+            largest_free_block: (0..self.free.len())
+                .rev()
+                .find(|&order| !self.free[order].is_empty())
+                .map_or(0, |order| self.block_size(order)),
+        }
+    }
+}
+
+/// Category of resources which share heaps on `ResourceHeapTier::Tier1` devices.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ResourceCategory {
+    Buffer,
+    /// Textures allowing render target or depth stencil usage.
+    RtDsTexture,
+    NonRtDsTexture,
+}
+
+impl ResourceCategory {
+    pub fn from_desc(desc: &ResourceDesc) -> Self {
+        let flags = ResourceFlags::from_bits_truncate(desc.0.Flags);
+        if desc.0.Dimension == d3d12::D3D12_RESOURCE_DIMENSION_BUFFER {
+            ResourceCategory::Buffer
+        } else if flags
+            .intersects(ResourceFlags::ALLOW_RENDER_TARGET | ResourceFlags::ALLOW_DEPTH_STENCIL)
+        {
+            ResourceCategory::RtDsTexture
+        } else {
+            ResourceCategory::NonRtDsTexture
+        }
+    }
+
+    /// Flags of heaps which only hold resources of this category.
+    pub fn heap_flags(self) -> HeapFlags {
+        match self {
+            ResourceCategory::Buffer => HeapFlags::ALLOW_ONLY_BUFFERS,
+            ResourceCategory::RtDsTexture => HeapFlags::ALLOW_ONLY_RT_DS_TEXTURES,
+            ResourceCategory::NonRtDsTexture => HeapFlags::ALLOW_ONLY_NON_RT_DS_TEXTURES,
+        }
+    }
+}
+
+type PoolKey = (d3d12::D3D12_HEAP_TYPE, HeapFlags);
+
+/// Resource placed in a heap of a `PlacedResourceAllocator`.
+#[derive(Debug)]
+pub struct PlacedAllocation {
+    resource: Resource,
+    pool: PoolKey,
+    heap: usize,
+    offset: u64,
+}
+
+impl PlacedAllocation {
+    pub fn resource(&self) -> &Resource {
+        &self.resource
+    }
+
+    pub fn heap_offset(&self) -> u64 {
+        self.offset
+    }
+}
+
+/// Suballocator of placed resources in heaps of `heap_size` bytes, with one pool
+/// of heaps per heap type and heap flags.
+///
+/// On `ResourceHeapTier::Tier1` devices, each resource category gets its own pools.
+/// Resources larger than the heap size should be committed instead.
+#[derive(Debug)]
+pub struct PlacedResourceAllocator {
+    device: Device,
+    heap_size: u64,
+    tier: ResourceHeapTier,
+    pools: HashMap<PoolKey, Vec<Option<(Heap, BuddyAllocator)>>>,
+}
+
+impl PlacedResourceAllocator {
+    /// `heap_size` is rounded up to a power of two of at least
+    /// `MSAA_RESOURCE_PLACEMENT_ALIGNMENT`.
+    pub fn new(device: Device, heap_size: u64, tier: ResourceHeapTier) -> Self {
+        PlacedResourceAllocator {
+            device,
+            heap_size: heap_size
+                .next_power_of_two()
+                .max(MSAA_RESOURCE_PLACEMENT_ALIGNMENT),
+            tier,
+            pools: HashMap::new(),
+        }
+    }
+
+    pub fn heap_size(&self) -> u64 {
+        self.heap_size
+    }
+
+    /// Places a resource of `desc` in a heap of `heap_type`, creating a new heap
+    /// when the existing ones are full.
+    ///
+    /// Fails with `E_INVALIDARG` for custom heaps and for resources larger than the heap size.
+    pub fn allocate(
+        &mut self,
+        heap_type: HeapType,
+        heap_flags: HeapFlags,
+        desc: &ResourceDesc,
+        initial_state: d3d12::D3D12_RESOURCE_STATES,
+        clear_value: Option<&d3d12::D3D12_CLEAR_VALUE>,
+    ) -> Result<PlacedAllocation, HRESULT> {
+        if let HeapType::Custom = heap_type {
+            return Err(winerror::E_INVALIDARG);
+        }
+        let info = self.device.get_resource_allocation_info(0, desc);
+        if info.SizeInBytes > self.heap_size {
+            return Err(winerror::E_INVALIDARG);
+        }
+
+        let heap_flags = match self.tier {
+            ResourceHeapTier::Tier1 => heap_flags | ResourceCategory::from_desc(desc).heap_flags(),
+            ResourceHeapTier::Tier2 => heap_flags,
+        };
+        let pool_key = (heap_type as d3d12::D3D12_HEAP_TYPE, heap_flags);
+        let pool = self.pools.entry(pool_key).or_default();
+
+        let existing = pool.iter_mut().enumerate().find_map(|(index, heap)| {
+            let (_, blocks) = heap.as_mut()?;
+            let offset = blocks.allocate(info.SizeInBytes, info.Alignment)?;
+            Some((index, offset))
+        });
+        let (index, offset) = match existing {
+            Some(placement) => placement,
+            None => {
+                let properties = HeapProperties(d3d12::D3D12_HEAP_PROPERTIES {
+                    Type: pool_key.0,
+                    CPUPageProperty: d3d12::D3D12_CPU_PAGE_PROPERTY_UNKNOWN,
+                    MemoryPoolPreference: d3d12::D3D12_MEMORY_POOL_UNKNOWN,
+                    CreationNodeMask: 0,
+                    VisibleNodeMask: 0,
+                });
+                let (heap, hr) = self.device.create_heap(
+                    self.heap_size,
+                    properties,
+                    MSAA_RESOURCE_PLACEMENT_ALIGNMENT,
+                    heap_flags,
+                );
+                if !winerror::SUCCEEDED(hr) {
+                    return Err(hr);
+                }
+
+                let mut blocks = BuddyAllocator::new(self.heap_size, RESOURCE_PLACEMENT_ALIGNMENT);
+                let offset = blocks.allocate(info.SizeInBytes, info.Alignment).unwrap();
+                let index = match pool.iter().position(Option::is_none) {
+                    Some(index) => {
+                        pool[index] = Some((heap, blocks));
+                        index
+                    }
+                    None => {
+                        pool.push(Some((heap, blocks)));
+                        pool.len() - 1
+                    }
+                };
+                (index, offset)
+            }
+        };
+
+        let (heap, blocks) = pool[index].as_mut().unwrap();
+        let (resource, hr) =
+            self.device
+                .create_placed_resource(heap, offset, desc, initial_state, clear_value);
+        if !winerror::SUCCEEDED(hr) {
+            blocks.free(offset);
+            return Err(hr);
+        }
+
+        Ok(PlacedAllocation {
+            resource,
+            pool: pool_key,
+            heap: index,
+            offset,
+        })
+    }
+
+    /// Releases the resource of `allocation` and returns its memory to the pool.
+    /// Heaps left empty are released, except for the last one of each pool.
+    ///
+    /// The resource must not be in use by the GPU.
+    pub fn free(&mut self, allocation: PlacedAllocation) {
+        let pool = self.pools.get_mut(&allocation.pool).unwrap();
+        let (_, blocks) = pool[allocation.heap].as_mut().unwrap();
+        blocks.free(allocation.offset);
+
+        let is_empty = blocks.is_empty();
+        if is_empty && pool.iter().flatten().count() > 1 {
+            pool[allocation.heap] = None;
+        }
+    }
+
+    pub fn heap_count(&self) -> usize {
+        self.pools.values().flatten().flatten().count()
+    }
+
+    /// Statistics over the heaps of all pools.
+    pub fn stats(&self) -> BlockStats {
+        self.pools
+            .values()
+            .flatten()
+            .flatten()
+            .map(|(_, blocks)| blocks.stats())
+            .fold(BlockStats::default(), BlockStats::merge)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_fully_merged(allocator: &BuddyAllocator) {
+        let stats = allocator.stats();
+        assert!(allocator.is_empty());
+        assert_eq!(stats.free_block_count, 1);
+        assert_eq!(stats.largest_free_block, allocator.size());
+    }
+
+    #[test]
+    fn buddies_merge_into_a_single_block() {
+        let mut allocator = BuddyAllocator::new(1024, 64);
+        let offsets: Vec<_> = (0..16)
+            .map(|_| allocator.allocate(64, 64).unwrap())
+            .collect();
+        assert_eq!(allocator.stats().largest_free_block, 0);
+
+        // Free every other block first so that no buddies can merge until the end.
+        for &offset in offsets.iter().step_by(2) {
+            allocator.free(offset);
+        }
+        assert_eq!(allocator.stats().free_block_count, 8);
+        for &offset in offsets.iter().skip(1).step_by(2) {
+            allocator.free(offset);
+        }
+
+        assert_fully_merged(&allocator);
+        assert_eq!(allocator.allocate(1024, 1), Some(0));
+    }
+
+    #[test]
+    fn alignment_larger_than_size() {
+        let mut allocator = BuddyAllocator::new(1024, 64);
+        assert_eq!(allocator.allocate(64, 1), Some(0));
+        assert_eq!(allocator.allocate(64, 256), Some(256));
+        assert_eq!(allocator.stats().allocated, 64 + 256);
+        assert_eq!(allocator.allocate(64, 2048), None);
+    }
+
+    #[test]
+    fn sizes_round_up_to_min_block() {
+        let mut allocator = BuddyAllocator::new(1024, 64);
+        assert_eq!(allocator.allocate(1, 1), Some(0));
+        assert_eq!(allocator.allocate(65, 1), Some(128));
+        assert_eq!(allocator.allocate(1, 1), Some(64));
+        assert_eq!(allocator.stats().allocated, 64 + 128 + 64);
+    }
+
+    #[test]
+    fn out_of_space() {
+        let mut allocator = BuddyAllocator::new(256, 64);
+        assert_eq!(allocator.allocate(0, 1), None);
+        assert_eq!(allocator.allocate(257, 1), None);
+        assert_eq!(allocator.allocate(256, 1), Some(0));
+        assert_eq!(allocator.allocate(1, 1), None);
+
+        allocator.free(0);
+        assert_eq!(allocator.allocate(128, 1), Some(0));
+        assert_eq!(allocator.allocate(128, 1), Some(128));
+        assert_eq!(allocator.allocate(64, 1), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "no block allocated")]
+    fn double_free_panics() {
+        let mut allocator = BuddyAllocator::new(256, 64);
+        let offset = allocator.allocate(64, 1).unwrap();
+        allocator.free(offset);
+        allocator.free(offset);
+    }
+
+    #[test]
+    fn random_allocations_do_not_overlap() {
+        // xorshift64, to stay deterministic without extra dependencies.
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        let mut next = move |bound: u64| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state % bound
+        };
+
+        let mut allocator = BuddyAllocator::new(1 << 16, 64);
+        let mut live: Vec<(u64, u64)> = Vec::new();
+        for _ in 0..10_000 {
+            if live.is_empty() || next(3) != 0 {
+                let size = 1 + next(4096);
+                let alignment = 1 << next(10);
+                if let Some(offset) = allocator.allocate(size, alignment) {
+                    assert_eq!(offset % alignment, 0);
+                    assert!(offset + size <= allocator.size());
+                    assert!(live
+                        .iter()
+                        .all(|&(start, len)| offset + size <= start || start + len <= offset));
+                    live.push((offset, size));
+                }
+            } else {
+                let (offset, _) = live.swap_remove(next(live.len() as u64) as usize);
+                allocator.free(offset);
+            }
+            assert_eq!(allocator.stats().allocation_count, live.len());
+        }
+
+        for (offset, _) in live.drain(..) {
+            allocator.free(offset);
+        }
+        assert_fully_merged(&allocator);
+    }
+}
@@ -6,33 +6,64 @@ use winapi::{
     um::{d3d12, d3dcommon},
 };
 
+mod bindless;
 mod com;
 mod command_allocator;
 mod command_list;
 mod debug;
 mod descriptor;
+mod descriptor_allocator;
 mod device;
 mod dxgi;
+mod feature;
+pub mod ffi;
 mod heap;
+mod heap_allocator;
+mod memory_budget;
 mod pso;
 mod query;
 mod queue;
+mod raytracing;
+mod readback;
+mod render_pass;
+mod residency;
 mod resource;
+mod sampler_feedback;
+mod shader_table;
+mod shading_rate;
+mod state_object;
 mod sync;
+mod tiled;
+mod upload;
 
+pub use crate::bindless::*;
 pub use crate::com::*;
 pub use crate::command_allocator::*;
 pub use crate::command_list::*;
 pub use crate::debug::*;
 pub use crate::descriptor::*;
+pub use crate::descriptor_allocator::*;
 pub use crate::device::*;
 pub use crate::dxgi::*;
+pub use crate::feature::*;
 pub use crate::heap::*;
+pub use crate::heap_allocator::*;
+pub use crate::memory_budget::*;
 pub use crate::pso::*;
 pub use crate::query::*;
 pub use crate::queue::*;
+pub use crate::raytracing::*;
+pub use crate::readback::*;
+pub use crate::render_pass::*;
+pub use crate::residency::*;
 pub use crate::resource::*;
+pub use crate::sampler_feedback::*;
+pub use crate::shader_table::*;
+pub use crate::shading_rate::*;
+pub use crate::state_object::*;
 pub use crate::sync::*;
+pub use crate::tiled::*;
+pub use crate::upload::*;
 
 pub use winapi::shared::winerror::HRESULT;
 
@@ -57,6 +88,12 @@ pub type WorkGroupCount = [u32; 3];
 
 pub type TextureAddressMode = [d3d12::D3D12_TEXTURE_ADDRESS_MODE; 3];
 
+/// Rounds `value` up to a multiple of `alignment`, which must be a power of two.
+pub(crate) fn align_up(value: u64, alignment: u64) -> u64 {
+    debug_assert!(alignment.is_power_of_two());
+    (value + alignment - 1) & !(alignment - 1)
+}
+
 pub struct SampleDesc {
     pub count: u32,
     pub quality: u32,
@@ -64,7 +101,10 @@ pub struct SampleDesc {
 
 #[repr(u32)]
 #[non_exhaustive]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub enum FeatureLevel {
+    /// Compute only device, created on adapters without graphics support.
+    L1_0Core = ffi::D3D_FEATURE_LEVEL_1_0_CORE,
     L9_1 = d3dcommon::D3D_FEATURE_LEVEL_9_1,
     L9_2 = d3dcommon::D3D_FEATURE_LEVEL_9_2,
     L9_3 = d3dcommon::D3D_FEATURE_LEVEL_9_3,
@@ -74,6 +114,7 @@ pub enum FeatureLevel {
     L11_1 = d3dcommon::D3D_FEATURE_LEVEL_11_1,
     L12_0 = d3dcommon::D3D_FEATURE_LEVEL_12_0,
     L12_1 = d3dcommon::D3D_FEATURE_LEVEL_12_1,
+    L12_2 = ffi::D3D_FEATURE_LEVEL_12_2,
 }
 
 impl TryFrom<u32> for FeatureLevel {
@@ -81,6 +122,7 @@ impl TryFrom<u32> for FeatureLevel {
 
     fn try_from(value: u32) -> Result<Self, Self::Error> {
         Ok(match value {
+            ffi::D3D_FEATURE_LEVEL_1_0_CORE => Self::L1_0Core,
             d3dcommon::D3D_FEATURE_LEVEL_9_1 => Self::L9_1,
             d3dcommon::D3D_FEATURE_LEVEL_9_2 => Self::L9_2,
             d3dcommon::D3D_FEATURE_LEVEL_9_3 => Self::L9_3,
@@ -90,6 +132,7 @@ impl TryFrom<u32> for FeatureLevel {
             d3dcommon::D3D_FEATURE_LEVEL_11_1 => Self::L11_1,
             d3dcommon::D3D_FEATURE_LEVEL_12_0 => Self::L12_0,
             d3dcommon::D3D_FEATURE_LEVEL_12_1 => Self::L12_1,
+            ffi::D3D_FEATURE_LEVEL_12_2 => Self::L12_2,
             _ => return Err(()),
         })
     }
@@ -0,0 +1,153 @@
+//! Video memory budgets
+
+use crate::{Adapter3, D3DResult, Event, HRESULT};
+use std::mem;
+use winapi::shared::{dxgi1_4, winerror};
+
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum MemorySegmentGroup {
+    /// Memory local to the GPU, which is all memory on UMA devices.
+    Local = dxgi1_4::DXGI_MEMORY_SEGMENT_GROUP_LOCAL,
+    /// System memory visible to discrete GPUs.
+    NonLocal = dxgi1_4::DXGI_MEMORY_SEGMENT_GROUP_NON_LOCAL,
+}
+
+/// Usage and budget in bytes of a memory segment group.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct VideoMemoryInfo {
+    /// Memory the application may use before the OS starts paging its resources out.
+    pub budget: u64,
+    pub current_usage: u64,
+    pub available_for_reservation: u64,
+    pub current_reservation: u64,
+}
+
+impl VideoMemoryInfo {
+    /// Memory left in the budget, which is zero when over the budget.
+    pub fn available(&self) -> u64 {
+        self.budget.saturating_sub(self.current_usage)
+    }
+
+    pub fn is_over_budget(&self) -> bool {
+        self.current_usage > self.budget
+    }
+}
+
+impl From<dxgi1_4::DXGI_QUERY_VIDEO_MEMORY_INFO> for VideoMemoryInfo {
+    fn from(info: dxgi1_4::DXGI_QUERY_VIDEO_MEMORY_INFO) -> Self {
+        VideoMemoryInfo {
+            budget: info.Budget,
+            current_usage: info.CurrentUsage,
+            available_for_reservation: info.AvailableForReservation,
+            current_reservation: info.CurrentReservation,
+        }
+    }
+}
+
+/// Memory usage and budgets of a node of an adapter.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct MemoryBudget {
+    pub local: VideoMemoryInfo,
+    pub non_local: VideoMemoryInfo,
+}
+
+impl MemoryBudget {
+    pub fn get(&self, group: MemorySegmentGroup) -> &VideoMemoryInfo {
+        match group {
+            MemorySegmentGroup::Local => &self.local,
+            MemorySegmentGroup::NonLocal => &self.non_local,
+        }
+    }
+}
+
+/// Registration of an `Event` signaled when the memory budget of an adapter
+/// changes, which is unregistered on drop.
+#[derive(Debug)]
+pub struct MemoryBudgetSubscription {
+    adapter: Adapter3,
+    event: Event,
+    cookie: u32,
+}
+
+impl MemoryBudgetSubscription {
+    pub fn event(&self) -> Event {
+        self.event
+    }
+
+    /// Waits for a budget change for up to `timeout_ms`, returning the result
+    /// of `WaitForSingleObject`.
+    pub fn wait(&self, timeout_ms: u32) -> u32 {
+        self.event.wait(timeout_ms)
+    }
+}
+
+impl Drop for MemoryBudgetSubscription {
+    fn drop(&mut self) {
+        unsafe {
+            self.adapter
+                .UnregisterVideoMemoryBudgetChangeNotification(self.cookie)
+        }
+    }
+}
+
+impl Adapter3 {
+    pub fn query_video_memory_info(
+        &self,
+        node_index: u32,
+        group: MemorySegmentGroup,
+    ) -> D3DResult<VideoMemoryInfo> {
+        let mut info: dxgi1_4::DXGI_QUERY_VIDEO_MEMORY_INFO = unsafe { mem::zeroed() };
+        let hr = unsafe { self.QueryVideoMemoryInfo(node_index, group as _, &mut info) };
+
+        (info.into(), hr)
+    }
+
+    /// Queries the local and non local memory of `node_index`.
+    pub fn memory_budget(&self, node_index: u32) -> Result<MemoryBudget, HRESULT> {
+        let query = |group| {
+            let (info, hr) = self.query_video_memory_info(node_index, group);
+            if winerror::SUCCEEDED(hr) {
+                Ok(info)
+            } else {
+                Err(hr)
+            }
+        };
+
+        Ok(MemoryBudget {
+            local: query(MemorySegmentGroup::Local)?,
+            non_local: query(MemorySegmentGroup::NonLocal)?,
+        })
+    }
+
+    /// Reserves `reservation` bytes of `group`, which must not exceed
+    /// `VideoMemoryInfo::available_for_reservation`.
+    pub fn set_video_memory_reservation(
+        &self,
+        node_index: u32,
+        group: MemorySegmentGroup,
+        reservation: u64,
+    ) -> HRESULT {
+        unsafe { self.SetVideoMemoryReservation(node_index, group as _, reservation) }
+    }
+
+    /// Signals `event` whenever the memory budget changes, until the returned
+    /// subscription is dropped.
+    pub fn subscribe_memory_budget_changes(
+        &self,
+        event: Event,
+    ) -> Result<MemoryBudgetSubscription, HRESULT> {
+        let mut cookie = 0;
+        let hr =
+            unsafe { self.RegisterVideoMemoryBudgetChangeNotificationEvent(event.0, &mut cookie) };
+        if !winerror::SUCCEEDED(hr) {
+            return Err(hr);
+        }
+
+        Ok(MemoryBudgetSubscription {
+            adapter: self.clone(),
+            event,
+            cookie,
+        })
+    }
+}
@@ -1,4 +1,10 @@
-use crate::{com::ComPtr, sync::Fence, CommandList, HRESULT};
+use crate::{
+    com::ComPtr,
+    sync::Fence,
+    tiled::{TileMappingFlags, TileRange, TileRegionSize, TiledResourceCoordinate},
+    CommandList, Heap, Resource, HRESULT,
+};
+use std::ptr;
 use winapi::um::d3d12;
 
 #[repr(u32)]
@@ -29,4 +35,73 @@ impl CommandQueue {
     pub fn signal(&self, fence: &Fence, value: u64) -> HRESULT {
         unsafe { self.Signal(fence.as_mut_ptr(), value) }
     }
+
+    /// Makes the queue wait on the GPU until `fence` reaches `value`.
+    pub fn wait(&self, fence: &Fence, value: u64) -> HRESULT {
+        unsafe { self.Wait(fence.as_mut_ptr(), value) }
+    }
+
+    /// Maps the tiles of `regions` of `resource` to `ranges` of tiles in `heap`.
+    ///
+    /// The tiles of all regions are taken in order and mapped to the tiles of all
+    /// ranges in order. `heap` may only be `None` if all ranges are `NULL` or `SKIP`.
+    pub fn update_tile_mappings(
+        &self,
+        resource: &Resource,
+        regions: &[(TiledResourceCoordinate, TileRegionSize)],
+        heap: Option<&Heap>,
+        ranges: &[TileRange],
+        flags: TileMappingFlags,
+    ) {
+        let (coordinates, sizes): (Vec<_>, Vec<_>) = regions
+            .iter()
+            .map(|&(coordinate, size)| (coordinate.0, size.0))
+            .unzip();
+        let range_flags = ranges
+            .iter()
+            .map(|range| range.flags.bits())
+            .collect::<Box<[_]>>();
+        let heap_starts = ranges
+            .iter()
+            .map(|range| range.heap_start)
+            .collect::<Box<[_]>>();
+        let counts = ranges.iter().map(|range| range.count).collect::<Box<[_]>>();
+
+        unsafe {
+            self.UpdateTileMappings(
+                resource.as_mut_ptr(),
+                regions.len() as _,
+                coordinates.as_ptr(),
+                sizes.as_ptr(),
+                heap.map_or(ptr::null_mut(), |heap| heap.as_mut_ptr()),
+                ranges.len() as _,
+                range_flags.as_ptr(),
+                heap_starts.as_ptr(),
+                counts.as_ptr(),
+                flags.bits(),
+            )
+        }
+    }
+
+    /// Copies the tile mappings of a region of `src` to `dst`.
+    pub fn copy_tile_mappings(
+        &self,
+        dst: &Resource,
+        dst_start: TiledResourceCoordinate,
+        src: &Resource,
+        src_start: TiledResourceCoordinate,
+        size: TileRegionSize,
+        flags: TileMappingFlags,
+    ) {
+        unsafe {
+            self.CopyTileMappings(
+                dst.as_mut_ptr(),
+                &dst_start.0,
+                src.as_mut_ptr(),
+                &src_start.0,
+                &size.0,
+                flags.bits(),
+            )
+        }
+    }
 }
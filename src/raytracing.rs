@@ -0,0 +1,471 @@
+//! Raytracing acceleration structures
+
+use crate::{ffi, Device5, Format, GpuAddress, GraphicsCommandList4};
+use std::{marker::PhantomData, mem, ptr};
+use winapi::shared::dxgiformat;
+
+/// Alignment of acceleration structures and their scratch memory.
+pub const ACCELERATION_STRUCTURE_ALIGNMENT: u64 =
+    ffi::D3D12_RAYTRACING_ACCELERATION_STRUCTURE_BYTE_ALIGNMENT;
+/// Alignment of the `RaytracingInstanceDesc` array of a top level build.
+pub const INSTANCE_DESCS_ALIGNMENT: u64 = ffi::D3D12_RAYTRACING_INSTANCE_DESCS_BYTE_ALIGNMENT;
+
+bitflags::bitflags! {
+    #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    pub struct RaytracingGeometryFlags: u32 {
+        const OPAQUE = ffi::D3D12_RAYTRACING_GEOMETRY_FLAG_OPAQUE;
+        const NO_DUPLICATE_ANYHIT_INVOCATION =
+            ffi::D3D12_RAYTRACING_GEOMETRY_FLAG_NO_DUPLICATE_ANYHIT_INVOCATION;
+    }
+}
+
+bitflags::bitflags! {
+    #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    pub struct AccelerationStructureBuildFlags: u32 {
+        const ALLOW_UPDATE = ffi::D3D12_RAYTRACING_ACCELERATION_STRUCTURE_BUILD_FLAG_ALLOW_UPDATE;
+        const ALLOW_COMPACTION =
+            ffi::D3D12_RAYTRACING_ACCELERATION_STRUCTURE_BUILD_FLAG_ALLOW_COMPACTION;
+        const PREFER_FAST_TRACE =
+            ffi::D3D12_RAYTRACING_ACCELERATION_STRUCTURE_BUILD_FLAG_PREFER_FAST_TRACE;
+        const PREFER_FAST_BUILD =
+            ffi::D3D12_RAYTRACING_ACCELERATION_STRUCTURE_BUILD_FLAG_PREFER_FAST_BUILD;
+        const MINIMIZE_MEMORY =
+            ffi::D3D12_RAYTRACING_ACCELERATION_STRUCTURE_BUILD_FLAG_MINIMIZE_MEMORY;
+        /// Updates the source acceleration structure, which must have been built
+        /// with `ALLOW_UPDATE`.
+        const PERFORM_UPDATE =
+            ffi::D3D12_RAYTRACING_ACCELERATION_STRUCTURE_BUILD_FLAG_PERFORM_UPDATE;
+    }
+}
+
+bitflags::bitflags! {
+    #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    pub struct RaytracingInstanceFlags: u8 {
+        const TRIANGLE_CULL_DISABLE =
+            ffi::D3D12_RAYTRACING_INSTANCE_FLAG_TRIANGLE_CULL_DISABLE as u8;
+        const TRIANGLE_FRONT_COUNTERCLOCKWISE =
+            ffi::D3D12_RAYTRACING_INSTANCE_FLAG_TRIANGLE_FRONT_COUNTERCLOCKWISE as u8;
+        const FORCE_OPAQUE = ffi::D3D12_RAYTRACING_INSTANCE_FLAG_FORCE_OPAQUE as u8;
+        const FORCE_NON_OPAQUE = ffi::D3D12_RAYTRACING_INSTANCE_FLAG_FORCE_NON_OPAQUE as u8;
+    }
+}
+
+/// Geometry of a bottom level acceleration structure.
+#[repr(transparent)]
+#[derive(Clone, Copy)]
+pub struct RaytracingGeometryDesc(pub(crate) ffi::D3D12_RAYTRACING_GEOMETRY_DESC);
+
+impl RaytracingGeometryDesc {
+    /// Non-indexed triangles with `vertex_count` vertices of `vertex_format`,
+    /// e.g. `DXGI_FORMAT_R32G32B32_FLOAT`, every `vertex_stride` bytes.
+    pub fn triangles(
+        vertex_buffer: GpuAddress,
+        vertex_stride: u64,
+        vertex_format: Format,
+        vertex_count: u32,
+        flags: RaytracingGeometryFlags,
+    ) -> Self {
+        let mut desc = ffi::D3D12_RAYTRACING_GEOMETRY_DESC {
+            Type: ffi::D3D12_RAYTRACING_GEOMETRY_TYPE_TRIANGLES,
+            Flags: flags.bits(),
+            ..unsafe { mem::zeroed() }
+        };
+        desc.u.Triangles = ffi::D3D12_RAYTRACING_GEOMETRY_TRIANGLES_DESC {
+            Transform3x4: 0,
+            IndexFormat: dxgiformat::DXGI_FORMAT_UNKNOWN,
+            VertexFormat: vertex_format,
+            IndexCount: 0,
+            VertexCount: vertex_count,
+            IndexBuffer: 0,
+            VertexBuffer: ffi::D3D12_GPU_VIRTUAL_ADDRESS_AND_STRIDE {
+                StartAddress: vertex_buffer,
+                StrideInBytes: vertex_stride,
+            },
+        };
+        RaytracingGeometryDesc(desc)
+    }
+
+    /// Procedural primitives bounded by `count` `D3D12_RAYTRACING_AABB`s, every
+    /// `stride` bytes.
+    pub fn aabbs(
+        aabbs: GpuAddress,
+        stride: u64,
+        count: u64,
+        flags: RaytracingGeometryFlags,
+    ) -> Self {
+        let mut desc = ffi::D3D12_RAYTRACING_GEOMETRY_DESC {
+            Type: ffi::D3D12_RAYTRACING_GEOMETRY_TYPE_PROCEDURAL_PRIMITIVE_AABBS,
+            Flags: flags.bits(),
+            ..unsafe { mem::zeroed() }
+        };
+        desc.u.AABBs = ffi::D3D12_RAYTRACING_GEOMETRY_AABBS_DESC {
+            AABBCount: count,
+            AABBs: ffi::D3D12_GPU_VIRTUAL_ADDRESS_AND_STRIDE {
+                StartAddress: aabbs,
+                StrideInBytes: stride,
+            },
+        };
+        RaytracingGeometryDesc(desc)
+    }
+
+    /// Indexes the triangles with `index_count` indices of `index_format`, which
+    /// is `DXGI_FORMAT_R16_UINT` or `DXGI_FORMAT_R32_UINT`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the geometry is not made of triangles.
+    pub fn with_indices(
+        mut self,
+        index_buffer: GpuAddress,
+        index_format: Format,
+        index_count: u32,
+    ) -> Self {
+        let triangles = self.triangles_mut();
+        triangles.IndexBuffer = index_buffer;
+        triangles.IndexFormat = index_format;
+        triangles.IndexCount = index_count;
+        self
+    }
+
+    /// Transforms the vertices by the row-major 3x4 matrix at `transform`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the geometry is not made of triangles.
+    pub fn with_transform(mut self, transform: GpuAddress) -> Self {
+        self.triangles_mut().Transform3x4 = transform;
+        self
+    }
+
+    fn triangles_mut(&mut self) -> &mut ffi::D3D12_RAYTRACING_GEOMETRY_TRIANGLES_DESC {
+        assert_eq!(self.0.Type, ffi::D3D12_RAYTRACING_GEOMETRY_TYPE_TRIANGLES);
+        unsafe { &mut self.0.u.Triangles }
+    }
+}
+
+/// Inputs of an acceleration structure build, also used to query its memory requirements.
+#[repr(transparent)]
+#[derive(Clone, Copy)]
+pub struct BuildRaytracingAccelerationStructureInputs<'a>(
+    pub(crate) ffi::D3D12_BUILD_RAYTRACING_ACCELERATION_STRUCTURE_INPUTS,
+    PhantomData<&'a [RaytracingGeometryDesc]>,
+);
+
+impl<'a> BuildRaytracingAccelerationStructureInputs<'a> {
+    pub fn bottom_level(
+        geometries: &'a [RaytracingGeometryDesc],
+        flags: AccelerationStructureBuildFlags,
+    ) -> Self {
+        let mut inputs = ffi::D3D12_BUILD_RAYTRACING_ACCELERATION_STRUCTURE_INPUTS {
+            Type: ffi::D3D12_RAYTRACING_ACCELERATION_STRUCTURE_TYPE_BOTTOM_LEVEL,
+            Flags: flags.bits(),
+            NumDescs: geometries.len() as _,
+            DescsLayout: ffi::D3D12_ELEMENTS_LAYOUT_ARRAY,
+            ..unsafe { mem::zeroed() }
+        };
+        inputs.u.pGeometryDescs = geometries.as_ptr() as *const _; // matches representation
+        BuildRaytracingAccelerationStructureInputs(inputs, PhantomData)
+    }
+
+    /// Top level structure over `count` `RaytracingInstanceDesc`s at `instances`,
+    /// aligned to `INSTANCE_DESCS_ALIGNMENT`. Only the count matters when querying
+    /// the prebuild info.
+    pub fn top_level(
+        instances: GpuAddress,
+        count: u32,
+        flags: AccelerationStructureBuildFlags,
+    ) -> Self {
+        let mut inputs = ffi::D3D12_BUILD_RAYTRACING_ACCELERATION_STRUCTURE_INPUTS {
+            Type: ffi::D3D12_RAYTRACING_ACCELERATION_STRUCTURE_TYPE_TOP_LEVEL,
+            Flags: flags.bits(),
+            NumDescs: count,
+            DescsLayout: ffi::D3D12_ELEMENTS_LAYOUT_ARRAY,
+            ..unsafe { mem::zeroed() }
+        };
+        inputs.u.InstanceDescs = instances;
+        BuildRaytracingAccelerationStructureInputs(inputs, PhantomData)
+    }
+}
+
+/// Memory requirements of an acceleration structure build.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct RaytracingPrebuildInfo {
+    /// Upper bound of the size of the acceleration structure.
+    pub result_data_max_size: u64,
+    pub scratch_data_size: u64,
+    /// Scratch size of updates, zero without `ALLOW_UPDATE`.
+    pub update_scratch_data_size: u64,
+}
+
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum AccelerationStructureCopyMode {
+    Clone = ffi::D3D12_RAYTRACING_ACCELERATION_STRUCTURE_COPY_MODE_CLONE,
+    /// Requires a source built with `ALLOW_COMPACTION` and a destination of at
+    /// least the compacted size emitted after the build.
+    Compact = ffi::D3D12_RAYTRACING_ACCELERATION_STRUCTURE_COPY_MODE_COMPACT,
+    VisualizationDecodeForTools =
+        ffi::D3D12_RAYTRACING_ACCELERATION_STRUCTURE_COPY_MODE_VISUALIZATION_DECODE_FOR_TOOLS,
+    Serialize = ffi::D3D12_RAYTRACING_ACCELERATION_STRUCTURE_COPY_MODE_SERIALIZE,
+    Deserialize = ffi::D3D12_RAYTRACING_ACCELERATION_STRUCTURE_COPY_MODE_DESERIALIZE,
+}
+
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum AccelerationStructurePostbuildInfo {
+    /// A `u64` with the size after compaction.
+    CompactedSize = ffi::D3D12_RAYTRACING_ACCELERATION_STRUCTURE_POSTBUILD_INFO_COMPACTED_SIZE,
+    ToolsVisualization =
+        ffi::D3D12_RAYTRACING_ACCELERATION_STRUCTURE_POSTBUILD_INFO_TOOLS_VISUALIZATION,
+    /// Two `u64`s with the serialized size and the number of bottom level
+    /// structures referenced by a top level one.
+    Serialization = ffi::D3D12_RAYTRACING_ACCELERATION_STRUCTURE_POSTBUILD_INFO_SERIALIZATION,
+    /// A `u64` with the current size.
+    CurrentSize = ffi::D3D12_RAYTRACING_ACCELERATION_STRUCTURE_POSTBUILD_INFO_CURRENT_SIZE,
+}
+
+/// Destination of postbuild information, written by the GPU.
+#[repr(transparent)]
+#[derive(Clone, Copy)]
+pub struct AccelerationStructurePostbuildInfoDesc(
+    pub(crate) ffi::D3D12_RAYTRACING_ACCELERATION_STRUCTURE_POSTBUILD_INFO_DESC,
+);
+
+impl AccelerationStructurePostbuildInfoDesc {
+    /// `dest` must be 8 byte aligned and in the `UNORDERED_ACCESS` state.
+    pub fn new(dest: GpuAddress, info: AccelerationStructurePostbuildInfo) -> Self {
+        AccelerationStructurePostbuildInfoDesc(
+            ffi::D3D12_RAYTRACING_ACCELERATION_STRUCTURE_POSTBUILD_INFO_DESC {
+                DestBuffer: dest,
+                InfoType: info as _,
+            },
+        )
+    }
+}
+
+/// Instance of a bottom level acceleration structure in a top level one, laid
+/// out as the GPU expects in the instance buffer.
+///
+/// ```rust
+/// # use d3d12::{RaytracingInstanceDesc, RaytracingInstanceFlags};
+/// let instance = RaytracingInstanceDesc::new(
+///     RaytracingInstanceDesc::IDENTITY,
+///     0x12_3456,
+///     0xab,
+///     0x65_4321,
+///     RaytracingInstanceFlags::FORCE_OPAQUE,
+///     0x1000,
+/// );
+/// assert_eq!(instance.instance_id(), 0x12_3456);
+/// assert_eq!(instance.mask(), 0xab);
+/// assert_eq!(instance.hit_group_index(), 0x65_4321);
+/// assert_eq!(instance.flags(), RaytracingInstanceFlags::FORCE_OPAQUE);
+/// ```
+#[repr(transparent)]
+#[derive(Clone, Copy)]
+pub struct RaytracingInstanceDesc(pub(crate) ffi::D3D12_RAYTRACING_INSTANCE_DESC);
+
+impl RaytracingInstanceDesc {
+    /// Largest instance ID and hit group index, which are 24-bit.
+    pub const MAX_INDEX: u32 = (1 << 24) - 1;
+    pub const IDENTITY: [[f32; 4]; 3] = [
+        [1.0, 0.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0, 0.0],
+        [0.0, 0.0, 1.0, 0.0],
+    ];
+
+    /// Instance of the bottom level structure at `acceleration_structure`,
+    /// transformed by the row-major 3x4 matrix `transform`.
+    ///
+    /// The instance is hit by rays whose mask shares a bit with `mask`, and uses
+    /// the hit groups from `hit_group_index` in the hit group table.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `instance_id` or `hit_group_index` is larger than `MAX_INDEX`.
+    pub fn new(
+        transform: [[f32; 4]; 3],
+        instance_id: u32,
+        mask: u8,
+        hit_group_index: u32,
+        flags: RaytracingInstanceFlags,
+        acceleration_structure: GpuAddress,
+    ) -> Self {
+        assert!(instance_id <= Self::MAX_INDEX);
+        assert!(hit_group_index <= Self::MAX_INDEX);
+        RaytracingInstanceDesc(ffi::D3D12_RAYTRACING_INSTANCE_DESC {
+            Transform: transform,
+            InstanceID_InstanceMask: instance_id | (mask as u32) << 24,
+            InstanceContributionToHitGroupIndex_Flags: hit_group_index
+                | (flags.bits() as u32) << 24,
+            AccelerationStructure: acceleration_structure,
+        })
+    }
+
+    pub fn transform(&self) -> [[f32; 4]; 3] {
+        self.0.Transform
+    }
+
+    pub fn instance_id(&self) -> u32 {
+        self.0.InstanceID_InstanceMask & Self::MAX_INDEX
+    }
+
+    pub fn mask(&self) -> u8 {
+        (self.0.InstanceID_InstanceMask >> 24) as u8
+    }
+
+    pub fn hit_group_index(&self) -> u32 {
+        self.0.InstanceContributionToHitGroupIndex_Flags & Self::MAX_INDEX
+    }
+
+    pub fn flags(&self) -> RaytracingInstanceFlags {
+        RaytracingInstanceFlags::from_bits_truncate(
+            (self.0.InstanceContributionToHitGroupIndex_Flags >> 24) as u8,
+        )
+    }
+
+    pub fn acceleration_structure(&self) -> GpuAddress {
+        self.0.AccelerationStructure
+    }
+}
+
+impl Device5 {
+    pub fn get_raytracing_acceleration_structure_prebuild_info(
+        &self,
+        inputs: &BuildRaytracingAccelerationStructureInputs,
+    ) -> RaytracingPrebuildInfo {
+        let mut info = ffi::D3D12_RAYTRACING_ACCELERATION_STRUCTURE_PREBUILD_INFO {
+            ResultDataMaxSizeInBytes: 0,
+            ScratchDataSizeInBytes: 0,
+            UpdateScratchDataSizeInBytes: 0,
+        };
+        unsafe { self.GetRaytracingAccelerationStructurePrebuildInfo(&inputs.0, &mut info) };
+
+        RaytracingPrebuildInfo {
+            result_data_max_size: info.ResultDataMaxSizeInBytes,
+            scratch_data_size: info.ScratchDataSizeInBytes,
+            update_scratch_data_size: info.UpdateScratchDataSizeInBytes,
+        }
+    }
+}
+
+impl GraphicsCommandList4 {
+    /// Builds the acceleration structure described by `inputs` at `dest`, or updates
+    /// `source` into `dest` with `AccelerationStructureBuildFlags::PERFORM_UPDATE`.
+    ///
+    /// `dest` and `scratch` must be aligned to `ACCELERATION_STRUCTURE_ALIGNMENT` and
+    /// at least as large as reported by the prebuild info. The postbuild information
+    /// of `dest` is written once the build has completed.
+    pub fn build_raytracing_acceleration_structure(
+        &self,
+        dest: GpuAddress,
+        inputs: &BuildRaytracingAccelerationStructureInputs,
+        source: Option<GpuAddress>,
+        scratch: GpuAddress,
+        postbuild_info: &[AccelerationStructurePostbuildInfoDesc],
+    ) {
+        let desc = ffi::D3D12_BUILD_RAYTRACING_ACCELERATION_STRUCTURE_DESC {
+            DestAccelerationStructureData: dest,
+            Inputs: inputs.0,
+            SourceAccelerationStructureData: source.unwrap_or(0),
+            ScratchAccelerationStructureData: scratch,
+        };
+        unsafe {
+            self.BuildRaytracingAccelerationStructure(
+                &desc,
+                postbuild_info.len() as _,
+                if postbuild_info.is_empty() {
+                    ptr::null()
+                } else {
+                    postbuild_info.as_ptr() as *const _ // matches representation
+                },
+            )
+        }
+    }
+
+    /// Writes the postbuild information of each of `sources` consecutively to the
+    /// destination of `desc`.
+    pub fn emit_raytracing_acceleration_structure_postbuild_info(
+        &self,
+        desc: &AccelerationStructurePostbuildInfoDesc,
+        sources: &[GpuAddress],
+    ) {
+        unsafe {
+            self.EmitRaytracingAccelerationStructurePostbuildInfo(
+                &desc.0,
+                sources.len() as _,
+                sources.as_ptr(),
+            )
+        }
+    }
+
+    pub fn copy_raytracing_acceleration_structure(
+        &self,
+        dest: GpuAddress,
+        source: GpuAddress,
+        mode: AccelerationStructureCopyMode,
+    ) {
+        unsafe { self.CopyRaytracingAccelerationStructure(dest, source, mode as _) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::mem;
+
+    #[test]
+    fn instance_desc_bit_layout() {
+        assert_eq!(mem::size_of::<RaytracingInstanceDesc>(), 64);
+
+        let instance = RaytracingInstanceDesc::new(
+            RaytracingInstanceDesc::IDENTITY,
+            0x12_3456,
+            0xab,
+            0x65_4321,
+            RaytracingInstanceFlags::FORCE_OPAQUE,
+            0x1000,
+        );
+
+        // IDs and indices take the low 24 bits, the mask and flags the high 8 bits.
+        assert_eq!(instance.0.InstanceID_InstanceMask, 0xab12_3456);
+        assert_eq!(
+            instance.0.InstanceContributionToHitGroupIndex_Flags,
+            0x0465_4321
+        );
+        assert_eq!(instance.0.AccelerationStructure, 0x1000);
+    }
+
+    #[test]
+    fn instance_desc_max_index() {
+        let instance = RaytracingInstanceDesc::new(
+            RaytracingInstanceDesc::IDENTITY,
+            RaytracingInstanceDesc::MAX_INDEX,
+            0xff,
+            RaytracingInstanceDesc::MAX_INDEX,
+            RaytracingInstanceFlags::all(),
+            0,
+        );
+
+        assert_eq!(instance.instance_id(), RaytracingInstanceDesc::MAX_INDEX);
+        assert_eq!(instance.mask(), 0xff);
+        assert_eq!(
+            instance.hit_group_index(),
+            RaytracingInstanceDesc::MAX_INDEX
+        );
+        assert_eq!(instance.flags(), RaytracingInstanceFlags::all());
+    }
+
+    #[test]
+    #[should_panic]
+    fn instance_id_out_of_range_panics() {
+        RaytracingInstanceDesc::new(
+            RaytracingInstanceDesc::IDENTITY,
+            RaytracingInstanceDesc::MAX_INDEX + 1,
+            0xff,
+            0,
+            RaytracingInstanceFlags::empty(),
+            0,
+        );
+    }
+}
@@ -0,0 +1,263 @@
+//! Reading back GPU data
+
+use crate::{
+    heap::{CpuPageProperty, HeapFlags, HeapProperties, HeapType, MemoryPool},
+    resource::{CopyableFootprint, ResourceDesc, ResourceFlags, Subresource, TextureCopyLocation},
+    upload::{repack_rows, RowLayout},
+    Device, Fence, GraphicsCommandList, Resource, HRESULT,
+};
+use std::{ops::Range, slice};
+use winapi::{shared::winerror, um::d3d12};
+
+/// Returns the tightly packed data of the subresource described by `footprint`
+/// from `buffer`, stripping the row pitch padding.
+///
+/// ```rust
+/// # use winapi::{shared::dxgiformat, um::d3d12 as raw};
+/// // Two rows of 3 bytes, padded to a row pitch of 4 bytes.
+/// let footprint = d3d12::CopyableFootprint {
+///     layout: raw::D3D12_PLACED_SUBRESOURCE_FOOTPRINT {
+///         Offset: 0,
+///         Footprint: raw::D3D12_SUBRESOURCE_FOOTPRINT {
+///             Format: dxgiformat::DXGI_FORMAT_R8_UNORM,
+///             Width: 3,
+///             Height: 2,
+///             Depth: 1,
+///             RowPitch: 4,
+///         },
+///     },
+///     num_rows: 2,
+///     row_size: 3,
+/// };
+/// let buffer = [1, 2, 3, 0, 4, 5, 6, 0];
+/// assert_eq!(d3d12::unpack_subresource(&buffer, &footprint), [1, 2, 3, 4, 5, 6]);
+/// ```
+pub fn unpack_subresource(buffer: &[u8], footprint: &CopyableFootprint) -> Vec<u8> {
+    let row_size = footprint.row_size as usize;
+    let num_rows = footprint.num_rows as usize;
+    let depth = footprint.layout.Footprint.Depth as usize;
+    let row_pitch = footprint.layout.Footprint.RowPitch as usize;
+
+    let mut data = vec![0; row_size * num_rows * depth];
+    repack_rows(
+        &mut data,
+        RowLayout {
+            row_pitch: row_size,
+            slice_pitch: row_size * num_rows,
+        },
+        &buffer[footprint.layout.Offset as usize..],
+        RowLayout {
+            row_pitch,
+            slice_pitch: row_pitch * num_rows,
+        },
+        row_size,
+        num_rows,
+        depth,
+    );
+
+    data
+}
+
+/// Buffer in a readback heap which receives copies of buffers or texture subresources.
+pub struct ReadbackBuffer {
+    resource: Resource,
+    // Empty for copies of buffers.
+    footprints: Vec<CopyableFootprint>,
+    size: u64,
+}
+
+impl ReadbackBuffer {
+    fn create(
+        device: &Device,
+        size: u64,
+        footprints: Vec<CopyableFootprint>,
+    ) -> Result<Self, HRESULT> {
+        let (resource, hr) = device.create_committed_resource(
+            HeapProperties::new(
+                HeapType::Readback,
+                CpuPageProperty::Unknown,
+                MemoryPool::Unknown,
+                0,
+                0,
+            ),
+            HeapFlags::empty(),
+            &ResourceDesc::buffer(size, ResourceFlags::empty()),
+            d3d12::D3D12_RESOURCE_STATE_COPY_DEST,
+            None,
+        );
+        if !winerror::SUCCEEDED(hr) {
+            return Err(hr);
+        }
+
+        Ok(ReadbackBuffer {
+            resource,
+            footprints,
+            size,
+        })
+    }
+
+    /// Allocates a readback buffer for `subresources` of the texture `src` and
+    /// records the copies into `list`.
+    ///
+    /// `src` must be in the `COPY_SOURCE` state when `list` executes. Fails with
+    /// `E_INVALIDARG` if `subresources` is empty or out of range.
+    pub fn texture(
+        device: &Device,
+        list: &GraphicsCommandList,
+        src: &Resource,
+        subresources: Range<Subresource>,
+    ) -> Result<Self, HRESULT> {
+        if subresources.start >= subresources.end {
+            return Err(winerror::E_INVALIDARG);
+        }
+        let (footprints, size) =
+            device.get_copyable_footprints(&src.get_desc(), subresources.clone(), 0);
+        if size == u64::MAX {
+            return Err(winerror::E_INVALIDARG);
+        }
+        let readback = Self::create(device, size, footprints)?;
+
+        for (subresource, footprint) in subresources.zip(&readback.footprints) {
+            list.copy_texture_region(
+                &TextureCopyLocation::placed_footprint(&readback.resource, footprint.layout),
+                [0; 3],
+                &TextureCopyLocation::subresource(src, subresource),
+                None,
+            );
+        }
+
+        Ok(readback)
+    }
+
+    /// Allocates a readback buffer for `range` of the buffer `src` and records
+    /// the copy into `list`.
+    ///
+    /// `src` must be in the `COPY_SOURCE` state when `list` executes. Fails with
+    /// `E_INVALIDARG` if `range` is empty.
+    pub fn buffer(
+        device: &Device,
+        list: &GraphicsCommandList,
+        src: &Resource,
+        range: Range<u64>,
+    ) -> Result<Self, HRESULT> {
+        if range.start >= range.end {
+            return Err(winerror::E_INVALIDARG);
+        }
+        let size = range.end - range.start;
+        let readback = Self::create(device, size, Vec::new())?;
+
+        list.copy_buffer_region(&readback.resource, 0, src, range.start, size);
+
+        Ok(readback)
+    }
+
+    pub fn resource(&self) -> &Resource {
+        &self.resource
+    }
+
+    /// Footprints of the copied texture subresources, empty for buffers.
+    pub fn footprints(&self) -> &[CopyableFootprint] {
+        &self.footprints
+    }
+
+    /// Returns the tightly packed data of each copied subresource, or the copied
+    /// range of a buffer.
+    ///
+    /// `fence` must be signaled to `value` after the command list recording the copies.
+    /// Fails with `E_PENDING` while the fence has not reached `value`.
+    pub fn read(&self, fence: &Fence, value: u64) -> Result<Vec<Vec<u8>>, HRESULT> {
+        if fence.get_value() < value {
+            return Err(winerror::E_PENDING);
+        }
+
+        let (ptr, hr) = self.resource.map(0, Some(0..self.size as usize));
+        if !winerror::SUCCEEDED(hr) {
+            return Err(hr);
+        }
+
+        let buffer = unsafe { slice::from_raw_parts(ptr as *const u8, self.size as usize) };
+        let data = if self.footprints.is_empty() {
+            vec![buffer.to_vec()]
+        } else {
+            self.footprints
+                .iter()
+                .map(|footprint| unpack_subresource(buffer, footprint))
+                .collect()
+        };
+        self.resource.unmap(0, Some(0..0));
+
+        Ok(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use winapi::shared::dxgiformat;
+
+    fn footprint(
+        format: dxgiformat::DXGI_FORMAT,
+        [width, height, depth]: [u32; 3],
+        row_pitch: u32,
+        num_rows: u32,
+        row_size: u64,
+    ) -> CopyableFootprint {
+        CopyableFootprint {
+            layout: d3d12::D3D12_PLACED_SUBRESOURCE_FOOTPRINT {
+                Offset: 0,
+                Footprint: d3d12::D3D12_SUBRESOURCE_FOOTPRINT {
+                    Format: format,
+                    Width: width,
+                    Height: height,
+                    Depth: depth,
+                    RowPitch: row_pitch,
+                },
+            },
+            num_rows,
+            row_size,
+        }
+    }
+
+    #[test]
+    fn unpack_strips_row_padding() {
+        // 3 rows of 2 RGBA8 texels, padded to a row pitch of 256 bytes.
+        let footprint = footprint(dxgiformat::DXGI_FORMAT_R8G8B8A8_UNORM, [2, 3, 1], 256, 3, 8);
+        let mut buffer = vec![0xFF; 256 * 3];
+        for row in 0..3 {
+            for i in 0..8 {
+                buffer[row * 256 + i] = (row * 8 + i) as u8;
+            }
+        }
+
+        let data = unpack_subresource(&buffer, &footprint);
+        assert_eq!(data, (0..24).collect::<Vec<u8>>());
+    }
+
+    #[test]
+    fn unpack_depth_slices() {
+        // 2 slices of 2 rows of 4 bytes, slices are `num_rows` rows apart.
+        let footprint = footprint(dxgiformat::DXGI_FORMAT_R8_UINT, [4, 2, 2], 8, 2, 4);
+        let buffer: Vec<u8> = (0..32).collect();
+
+        let data = unpack_subresource(&buffer, &footprint);
+        assert_eq!(
+            data,
+            [0, 1, 2, 3, 8, 9, 10, 11, 16, 17, 18, 19, 24, 25, 26, 27]
+        );
+    }
+
+    #[test]
+    fn unpack_block_compressed_rows() {
+        // 8x8 BC1 texture: 2 rows of 2 blocks of 8 bytes.
+        let mut footprint = footprint(dxgiformat::DXGI_FORMAT_BC1_UNORM, [8, 8, 1], 256, 2, 16);
+        footprint.layout.Offset = 512;
+        let mut buffer = vec![0; 512 + 256 + 16];
+        buffer[512..528].copy_from_slice(&[1; 16]);
+        buffer[768..784].copy_from_slice(&[2; 16]);
+
+        let data = unpack_subresource(&buffer, &footprint);
+        assert_eq!(data.len(), 32);
+        assert_eq!(data[..16], [1; 16]);
+        assert_eq!(data[16..], [2; 16]);
+    }
+}
@@ -0,0 +1,256 @@
+//! Render passes
+
+use crate::{
+    command_list::ResolveMode, ffi, CpuDescriptor, Format, GraphicsCommandList4, Rect, Resource,
+    Subresource,
+};
+use std::{marker::PhantomData, mem, ptr};
+use winapi::{shared::minwindef::BOOL, um::d3d12};
+
+bitflags::bitflags! {
+    #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    pub struct RenderPassFlags: u32 {
+        const ALLOW_UAV_WRITES = ffi::D3D12_RENDER_PASS_FLAG_ALLOW_UAV_WRITES;
+        const SUSPENDING_PASS = ffi::D3D12_RENDER_PASS_FLAG_SUSPENDING_PASS;
+        const RESUMING_PASS = ffi::D3D12_RENDER_PASS_FLAG_RESUMING_PASS;
+    }
+}
+
+/// How the contents of a render pass attachment are initialized.
+#[repr(transparent)]
+#[derive(Clone, Copy)]
+pub struct RenderPassBeginningAccess(pub(crate) ffi::D3D12_RENDER_PASS_BEGINNING_ACCESS);
+
+impl RenderPassBeginningAccess {
+    fn new(ty: ffi::D3D12_RENDER_PASS_BEGINNING_ACCESS_TYPE) -> Self {
+        RenderPassBeginningAccess(ffi::D3D12_RENDER_PASS_BEGINNING_ACCESS {
+            Type: ty,
+            ..unsafe { mem::zeroed() }
+        })
+    }
+
+    /// The previous contents are not needed.
+    pub fn discard() -> Self {
+        Self::new(ffi::D3D12_RENDER_PASS_BEGINNING_ACCESS_TYPE_DISCARD)
+    }
+
+    pub fn preserve() -> Self {
+        Self::new(ffi::D3D12_RENDER_PASS_BEGINNING_ACCESS_TYPE_PRESERVE)
+    }
+
+    /// The attachment is not accessed, e.g. the stencil plane of a depth only pass.
+    pub fn no_access() -> Self {
+        Self::new(ffi::D3D12_RENDER_PASS_BEGINNING_ACCESS_TYPE_NO_ACCESS)
+    }
+
+    fn clear(value: d3d12::D3D12_CLEAR_VALUE) -> Self {
+        let mut access = Self::new(ffi::D3D12_RENDER_PASS_BEGINNING_ACCESS_TYPE_CLEAR);
+        access.0.u.Clear =
+            ffi::D3D12_RENDER_PASS_BEGINNING_ACCESS_CLEAR_PARAMETERS { ClearValue: value };
+        access
+    }
+
+    /// Clears a render target to `color`. Clears are fastest if `format` and `color`
+    /// match the optimized clear value of the resource.
+    pub fn clear_color(format: Format, color: [f32; 4]) -> Self {
+        let mut value = d3d12::D3D12_CLEAR_VALUE {
+            Format: format,
+            ..unsafe { mem::zeroed() }
+        };
+        *unsafe { value.u.Color_mut() } = color;
+        Self::clear(value)
+    }
+
+    /// Clears the depth plane of a depth stencil to `depth`.
+    pub fn clear_depth(format: Format, depth: f32) -> Self {
+        Self::clear_depth_stencil(format, depth, 0)
+    }
+
+    /// Clears the stencil plane of a depth stencil to `stencil`.
+    pub fn clear_stencil(format: Format, stencil: u8) -> Self {
+        Self::clear_depth_stencil(format, 0.0, stencil)
+    }
+
+    fn clear_depth_stencil(format: Format, depth: f32, stencil: u8) -> Self {
+        let mut value = d3d12::D3D12_CLEAR_VALUE {
+            Format: format,
+            ..unsafe { mem::zeroed() }
+        };
+        *unsafe { value.u.DepthStencil_mut() } = d3d12::D3D12_DEPTH_STENCIL_VALUE {
+            Depth: depth,
+            Stencil: stencil,
+        };
+        Self::clear(value)
+    }
+}
+
+/// Region of a multisampled subresource resolved at the end of a render pass.
+#[repr(transparent)]
+#[derive(Clone, Copy)]
+pub struct RenderPassResolveSubresource(
+    pub(crate) ffi::D3D12_RENDER_PASS_ENDING_ACCESS_RESOLVE_SUBRESOURCE_PARAMETERS,
+);
+
+impl RenderPassResolveSubresource {
+    pub fn new(
+        src_subresource: Subresource,
+        dst_subresource: Subresource,
+        dst_offset: [u32; 2],
+        src_rect: Rect,
+    ) -> Self {
+        RenderPassResolveSubresource(
+            ffi::D3D12_RENDER_PASS_ENDING_ACCESS_RESOLVE_SUBRESOURCE_PARAMETERS {
+                SrcSubresource: src_subresource,
+                DstSubresource: dst_subresource,
+                DstX: dst_offset[0],
+                DstY: dst_offset[1],
+                SrcRect: src_rect,
+            },
+        )
+    }
+}
+
+/// What happens to the contents of a render pass attachment when the pass ends.
+#[repr(transparent)]
+#[derive(Clone, Copy)]
+pub struct RenderPassEndingAccess<'a>(
+    pub(crate) ffi::D3D12_RENDER_PASS_ENDING_ACCESS,
+    PhantomData<&'a Resource>,
+);
+
+impl<'a> RenderPassEndingAccess<'a> {
+    fn new(ty: ffi::D3D12_RENDER_PASS_ENDING_ACCESS_TYPE) -> Self {
+        RenderPassEndingAccess(
+            ffi::D3D12_RENDER_PASS_ENDING_ACCESS {
+                Type: ty,
+                ..unsafe { mem::zeroed() }
+            },
+            PhantomData,
+        )
+    }
+
+    /// The contents are not needed after the pass.
+    pub fn discard() -> Self {
+        Self::new(ffi::D3D12_RENDER_PASS_ENDING_ACCESS_TYPE_DISCARD)
+    }
+
+    pub fn preserve() -> Self {
+        Self::new(ffi::D3D12_RENDER_PASS_ENDING_ACCESS_TYPE_PRESERVE)
+    }
+
+    pub fn no_access() -> Self {
+        Self::new(ffi::D3D12_RENDER_PASS_ENDING_ACCESS_TYPE_NO_ACCESS)
+    }
+
+    /// Resolves `subresources` of `src` into `dst`. The contents of `src` are
+    /// kept only if `preserve_source` is set.
+    pub fn resolve(
+        src: &'a Resource,
+        dst: &'a Resource,
+        subresources: &'a [RenderPassResolveSubresource],
+        format: Format,
+        mode: ResolveMode,
+        preserve_source: bool,
+    ) -> Self {
+        let mut access = Self::new(ffi::D3D12_RENDER_PASS_ENDING_ACCESS_TYPE_RESOLVE);
+        access.0.u.Resolve = ffi::D3D12_RENDER_PASS_ENDING_ACCESS_RESOLVE_PARAMETERS {
+            pSrcResource: src.as_mut_ptr(),
+            pDstResource: dst.as_mut_ptr(),
+            SubresourceCount: subresources.len() as _,
+            pSubresourceParameters: subresources.as_ptr() as *const _, // matches representation
+            Format: format,
+            ResolveMode: mode as _,
+            PreserveResolveSource: preserve_source as BOOL,
+        };
+        access
+    }
+}
+
+#[repr(transparent)]
+#[derive(Clone, Copy)]
+pub struct RenderPassRenderTarget<'a>(
+    pub(crate) ffi::D3D12_RENDER_PASS_RENDER_TARGET_DESC,
+    PhantomData<RenderPassEndingAccess<'a>>,
+);
+
+impl<'a> RenderPassRenderTarget<'a> {
+    pub fn new(
+        descriptor: CpuDescriptor,
+        beginning: RenderPassBeginningAccess,
+        ending: RenderPassEndingAccess<'a>,
+    ) -> Self {
+        RenderPassRenderTarget(
+            ffi::D3D12_RENDER_PASS_RENDER_TARGET_DESC {
+                cpuDescriptor: descriptor,
+                BeginningAccess: beginning.0,
+                EndingAccess: ending.0,
+            },
+            PhantomData,
+        )
+    }
+}
+
+#[repr(transparent)]
+#[derive(Clone, Copy)]
+pub struct RenderPassDepthStencil<'a>(
+    pub(crate) ffi::D3D12_RENDER_PASS_DEPTH_STENCIL_DESC,
+    PhantomData<RenderPassEndingAccess<'a>>,
+);
+
+impl<'a> RenderPassDepthStencil<'a> {
+    pub fn new(
+        descriptor: CpuDescriptor,
+        depth_beginning: RenderPassBeginningAccess,
+        stencil_beginning: RenderPassBeginningAccess,
+        depth_ending: RenderPassEndingAccess<'a>,
+        stencil_ending: RenderPassEndingAccess<'a>,
+    ) -> Self {
+        RenderPassDepthStencil(
+            ffi::D3D12_RENDER_PASS_DEPTH_STENCIL_DESC {
+                cpuDescriptor: descriptor,
+                DepthBeginningAccess: depth_beginning.0,
+                StencilBeginningAccess: stencil_beginning.0,
+                DepthEndingAccess: depth_ending.0,
+                StencilEndingAccess: stencil_ending.0,
+            },
+            PhantomData,
+        )
+    }
+
+    /// Depth stencil attachment for formats without a stencil plane.
+    pub fn depth_only(
+        descriptor: CpuDescriptor,
+        beginning: RenderPassBeginningAccess,
+        ending: RenderPassEndingAccess<'a>,
+    ) -> Self {
+        Self::new(
+            descriptor,
+            beginning,
+            RenderPassBeginningAccess::no_access(),
+            ending,
+            RenderPassEndingAccess::no_access(),
+        )
+    }
+}
+
+impl GraphicsCommandList4 {
+    pub fn begin_render_pass(
+        &self,
+        render_targets: &[RenderPassRenderTarget],
+        depth_stencil: Option<&RenderPassDepthStencil>,
+        flags: RenderPassFlags,
+    ) {
+        unsafe {
+            self.BeginRenderPass(
+                render_targets.len() as _,
+                render_targets.as_ptr() as *const _, // matches representation
+                depth_stencil.map_or(ptr::null(), |desc| &desc.0),
+                flags.bits(),
+            )
+        }
+    }
+
+    pub fn end_render_pass(&self) {
+        unsafe { self.EndRenderPass() }
+    }
+}
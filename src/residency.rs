@@ -0,0 +1,704 @@
+//! Residency management
+
+use crate::{
+    Adapter3, Device, Device1, Device3, Fence, Heap, MemorySegmentGroup, Resource, VersionedDevice,
+    VideoMemoryInfo, HRESULT,
+};
+use std::collections::HashSet;
+use winapi::{shared::winerror, um::d3d12};
+
+bitflags::bitflags! {
+    #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    pub struct ResidencyFlags: u32 {
+        /// Fails instead of exceeding the memory budget.
+        const DENY_OVERBUDGET = crate::ffi::D3D12_RESIDENCY_FLAG_DENY_OVERBUDGET;
+    }
+}
+
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum ResidencyPriority {
+    Minimum = d3d12::D3D12_RESIDENCY_PRIORITY_MINIMUM,
+    Low = d3d12::D3D12_RESIDENCY_PRIORITY_LOW,
+    Normal = d3d12::D3D12_RESIDENCY_PRIORITY_NORMAL,
+    High = d3d12::D3D12_RESIDENCY_PRIORITY_HIGH,
+    Maximum = d3d12::D3D12_RESIDENCY_PRIORITY_MAXIMUM,
+}
+
+/// Object whose residency is managed, a heap or a committed resource.
+#[derive(Clone, Debug, PartialEq, Hash)]
+pub enum Pageable {
+    Heap(Heap),
+    Resource(Resource),
+}
+
+impl Pageable {
+    pub fn as_mut_ptr(&self) -> *mut d3d12::ID3D12Pageable {
+        match *self {
+            Pageable::Heap(ref heap) => heap.as_mut_ptr() as *mut _,
+            Pageable::Resource(ref resource) => resource.as_mut_ptr() as *mut _,
+        }
+    }
+}
+
+fn pageable_ptrs(objects: &[Pageable]) -> Vec<*mut d3d12::ID3D12Pageable> {
+    objects.iter().map(Pageable::as_mut_ptr).collect()
+}
+
+impl Device {
+    pub fn make_resident(&self, objects: &[Pageable]) -> HRESULT {
+        let mut objects = pageable_ptrs(objects);
+        unsafe { self.MakeResident(objects.len() as _, objects.as_mut_ptr()) }
+    }
+
+    pub fn evict(&self, objects: &[Pageable]) -> HRESULT {
+        let mut objects = pageable_ptrs(objects);
+        unsafe { self.Evict(objects.len() as _, objects.as_mut_ptr()) }
+    }
+}
+
+impl Device1 {
+    /// Sets the priority of each object, which decides the order in which the
+    /// OS evicts them under memory pressure.
+    pub fn set_residency_priority(&self, objects: &[(Pageable, ResidencyPriority)]) -> HRESULT {
+        let pointers = objects
+            .iter()
+            .map(|(object, _)| object.as_mut_ptr())
+            .collect::<Vec<_>>();
+        let priorities = objects
+            .iter()
+            .map(|&(_, priority)| priority as d3d12::D3D12_RESIDENCY_PRIORITY)
+            .collect::<Vec<_>>();
+        unsafe {
+            self.SetResidencyPriority(objects.len() as _, pointers.as_ptr(), priorities.as_ptr())
+        }
+    }
+}
+
+impl Device3 {
+    /// Makes `objects` resident asynchronously, signaling `fence` with `value`
+    /// once done. Queues using the objects must wait for the fence.
+    pub fn enqueue_make_resident(
+        &self,
+        flags: ResidencyFlags,
+        objects: &[Pageable],
+        fence: &Fence,
+        value: u64,
+    ) -> HRESULT {
+        let objects = pageable_ptrs(objects);
+        unsafe {
+            self.EnqueueMakeResident(
+                flags.bits(),
+                objects.len() as _,
+                objects.as_ptr(),
+                fence.as_mut_ptr(),
+                value,
+            )
+        }
+    }
+}
+
+/// Operations of the `ResidencyManager` on the device, which can be replaced by
+/// a fake to exercise the residency policy without a GPU.
+pub trait ResidencyBackend {
+    type Object: Clone;
+
+    /// Budget and usage of the memory holding resident objects.
+    fn query_budget(&mut self) -> Result<VideoMemoryInfo, HRESULT>;
+    fn make_resident(&mut self, objects: &[Self::Object]) -> Result<(), HRESULT>;
+    fn evict(&mut self, objects: &[Self::Object]) -> Result<(), HRESULT>;
+    fn set_priority(
+        &mut self,
+        object: &Self::Object,
+        priority: ResidencyPriority,
+    ) -> Result<(), HRESULT>;
+}
+
+fn check(hr: HRESULT) -> Result<(), HRESULT> {
+    if winerror::SUCCEEDED(hr) {
+        Ok(())
+    } else {
+        Err(hr)
+    }
+}
+
+/// `ResidencyBackend` of a device, measuring the local memory budget of a node
+/// of its adapter.
+///
+/// On devices implementing `ID3D12Device3`, objects are made resident with
+/// `enqueue_make_resident`. Queues must then wait for `wait_fence` before executing
+/// command lists using them.
+#[derive(Debug)]
+pub struct DeviceResidency {
+    device: VersionedDevice,
+    adapter: Adapter3,
+    node_index: u32,
+    fence: Fence,
+    fence_value: u64,
+}
+
+impl DeviceResidency {
+    /// `fence` is signaled by `enqueue_make_resident` with increasing values
+    /// starting after its current value.
+    pub fn new(device: VersionedDevice, adapter: Adapter3, node_index: u32, fence: Fence) -> Self {
+        let fence_value = fence.get_value();
+        DeviceResidency {
+            device,
+            adapter,
+            node_index,
+            fence,
+            fence_value,
+        }
+    }
+
+    /// Fence and value signaled once all objects made resident so far are resident.
+    pub fn wait_fence(&self) -> (&Fence, u64) {
+        (&self.fence, self.fence_value)
+    }
+}
+
+impl ResidencyBackend for DeviceResidency {
+    type Object = Pageable;
+
+    fn query_budget(&mut self) -> Result<VideoMemoryInfo, HRESULT> {
+        let (info, hr) = self
+            .adapter
+            .query_video_memory_info(self.node_index, MemorySegmentGroup::Local);
+        check(hr).map(|()| info)
+    }
+
+    fn make_resident(&mut self, objects: &[Pageable]) -> Result<(), HRESULT> {
+        match self.device.as_device3() {
+            Some(device) => {
+                let fence_value = self.fence_value + 1;
+                check(device.enqueue_make_resident(
+                    ResidencyFlags::empty(),
+                    objects,
+                    &self.fence,
+                    fence_value,
+                ))?;
+                self.fence_value = fence_value;
+                Ok(())
+            }
+            None => check(self.device.device().make_resident(objects)),
+        }
+    }
+
+    fn evict(&mut self, objects: &[Pageable]) -> Result<(), HRESULT> {
+        check(self.device.device().evict(objects))
+    }
+
+    fn set_priority(
+        &mut self,
+        object: &Pageable,
+        priority: ResidencyPriority,
+    ) -> Result<(), HRESULT> {
+        match self.device.as_device1() {
+            Some(device) => check(device.set_residency_priority(&[(object.clone(), priority)])),
+            None => Err(winerror::E_NOINTERFACE),
+        }
+    }
+}
+
+/// Object tracked by a `ResidencyManager`.
+///
+/// Handles of untracked objects stay invalid when their slot is reused.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct ResidencyHandle {
+    index: usize,
+    generation: u32,
+}
+
+/// Objects used by the command lists of a submission.
+#[derive(Clone, Debug, Default)]
+pub struct ResidencySet {
+    handles: HashSet<ResidencyHandle>,
+}
+
+impl ResidencySet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, handle: ResidencyHandle) {
+        self.handles.insert(handle);
+    }
+
+    pub fn clear(&mut self) {
+        self.handles.clear();
+    }
+}
+
+#[derive(Debug)]
+struct Tracked<T> {
+    object: T,
+    size: u64,
+    resident: bool,
+    priority: ResidencyPriority,
+    // Fence value of the last submission using the object.
+    last_use: u64,
+}
+
+#[derive(Debug)]
+struct Slot<T> {
+    // Incremented when the object is untracked.
+    generation: u32,
+    tracked: Option<Tracked<T>>,
+}
+
+/// Keeps the objects used by each submission resident, evicting the least
+/// recently used objects when over the memory budget.
+///
+/// Lower priority objects are evicted first, and objects used by submissions
+/// which have not completed are never evicted.
+///
+/// ```rust
+/// # use d3d12::{ResidencyBackend, ResidencyManager, ResidencyPriority, ResidencySet, VideoMemoryInfo, HRESULT};
+/// #[derive(Default)]
+/// struct Fake {
+///     usage: u64,
+///     evicted: Vec<(&'static str, u64)>,
+/// }
+///
+/// impl ResidencyBackend for Fake {
+///     type Object = (&'static str, u64);
+///     fn query_budget(&mut self) -> Result<VideoMemoryInfo, HRESULT> {
+///         Ok(VideoMemoryInfo { budget: 100, current_usage: self.usage, ..Default::default() })
+///     }
+///     fn make_resident(&mut self, objects: &[Self::Object]) -> Result<(), HRESULT> {
+///         self.usage += objects.iter().map(|&(_, size)| size).sum::<u64>();
+///         Ok(())
+///     }
+///     fn evict(&mut self, objects: &[Self::Object]) -> Result<(), HRESULT> {
+///         self.usage -= objects.iter().map(|&(_, size)| size).sum::<u64>();
+///         self.evicted.extend_from_slice(objects);
+///         Ok(())
+///     }
+///     fn set_priority(&mut self, _: &Self::Object, _: ResidencyPriority) -> Result<(), HRESULT> {
+///         Ok(())
+///     }
+/// }
+///
+/// let mut manager = ResidencyManager::new(Fake::default());
+/// let a = manager.track(("a", 40), 40);
+/// let b = manager.track(("b", 40), 40);
+/// let c = manager.track(("c", 40), 40);
+/// manager.backend_mut().usage = 120;
+/// manager.evict(c).unwrap();
+///
+/// // Uses `a` then `b`, both submissions complete.
+/// let mut set = ResidencySet::new();
+/// set.insert(a);
+/// manager.prepare_submission(&set, 1, 0).unwrap();
+/// set.clear();
+/// set.insert(b);
+/// manager.prepare_submission(&set, 2, 0).unwrap();
+///
+/// // `c` doesn't fit, the least recently used `a` is evicted.
+/// set.clear();
+/// set.insert(c);
+/// manager.prepare_submission(&set, 3, 2).unwrap();
+/// assert_eq!(manager.backend().evicted, [("c", 40), ("a", 40)]);
+/// assert!(!manager.is_resident(a) && manager.is_resident(c));
+/// ```
+#[derive(Debug)]
+pub struct ResidencyManager<B: ResidencyBackend> {
+    backend: B,
+    slots: Vec<Slot<B::Object>>,
+    free_slots: Vec<usize>,
+}
+
+impl<B: ResidencyBackend> ResidencyManager<B> {
+    pub fn new(backend: B) -> Self {
+        ResidencyManager {
+            backend,
+            slots: Vec::new(),
+            free_slots: Vec::new(),
+        }
+    }
+
+    pub fn backend(&self) -> &B {
+        &self.backend
+    }
+
+    pub fn backend_mut(&mut self) -> &mut B {
+        &mut self.backend
+    }
+
+    fn get(&self, handle: ResidencyHandle) -> Option<&Tracked<B::Object>> {
+        let slot = self.slots.get(handle.index)?;
+        if slot.generation != handle.generation {
+            return None;
+        }
+        slot.tracked.as_ref()
+    }
+
+    fn tracked(&mut self, handle: ResidencyHandle) -> &mut Tracked<B::Object> {
+        self.slots
+            .get_mut(handle.index)
+            .filter(|slot| slot.generation == handle.generation)
+            .and_then(|slot| slot.tracked.as_mut())
+            .expect("Object is not tracked")
+    }
+
+    /// Tracks a resident object of `size` bytes, such as a newly created heap.
+    pub fn track(&mut self, object: B::Object, size: u64) -> ResidencyHandle {
+        let tracked = Tracked {
+            object,
+            size,
+            resident: true,
+            priority: ResidencyPriority::Normal,
+            last_use: 0,
+        };
+        let index = match self.free_slots.pop() {
+            Some(index) => index,
+            None => {
+                self.slots.push(Slot {
+                    generation: 0,
+                    tracked: None,
+                });
+                self.slots.len() - 1
+            }
+        };
+        let slot = &mut self.slots[index];
+        slot.tracked = Some(tracked);
+        ResidencyHandle {
+            index,
+            generation: slot.generation,
+        }
+    }
+
+    /// Stops tracking the object of `handle` and returns it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the object of `handle` is not tracked.
+    pub fn untrack(&mut self, handle: ResidencyHandle) -> B::Object {
+        let slot = self
+            .slots
+            .get_mut(handle.index)
+            .filter(|slot| slot.generation == handle.generation && slot.tracked.is_some())
+            .expect("Object is not tracked");
+        let tracked = slot.tracked.take().unwrap();
+        slot.generation = slot.generation.wrapping_add(1);
+        self.free_slots.push(handle.index);
+        tracked.object
+    }
+
+    /// Returns false for objects which are not tracked anymore.
+    pub fn is_resident(&self, handle: ResidencyHandle) -> bool {
+        matches!(self.get(handle), Some(tracked) if tracked.resident)
+    }
+
+    pub fn set_priority(
+        &mut self,
+        handle: ResidencyHandle,
+        priority: ResidencyPriority,
+    ) -> Result<(), HRESULT> {
+        let object = self.tracked(handle).object.clone();
+        self.backend.set_priority(&object, priority)?;
+        self.tracked(handle).priority = priority;
+        Ok(())
+    }
+
+    /// Evicts the object of `handle`, which must not be used by pending submissions.
+    pub fn evict(&mut self, handle: ResidencyHandle) -> Result<(), HRESULT> {
+        let tracked = self.tracked(handle);
+        if !tracked.resident {
+            return Ok(());
+        }
+        let object = tracked.object.clone();
+        self.backend.evict(&[object])?;
+        self.tracked(handle).resident = false;
+        Ok(())
+    }
+
+    /// Evicts resident objects unused since the submission of `completed_fence_value`
+    /// until `bytes` are freed, and returns the number of bytes freed.
+    fn evict_lru(
+        &mut self,
+        bytes: u64,
+        completed_fence_value: u64,
+        keep: &HashSet<ResidencyHandle>,
+    ) -> Result<u64, HRESULT> {
+        let mut candidates = self
+            .slots
+            .iter()
+            .enumerate()
+            .filter_map(|(index, slot)| {
+                let tracked = slot.tracked.as_ref()?;
+                let handle = ResidencyHandle {
+                    index,
+                    generation: slot.generation,
+                };
+                let candidate = tracked.resident
+                    && tracked.last_use <= completed_fence_value
+                    && !keep.contains(&handle);
+                if candidate {
+                    Some((tracked.priority, tracked.last_use, index, handle))
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+        candidates
+            .sort_unstable_by_key(|&(priority, last_use, index, _)| (priority, last_use, index));
+
+        let mut freed = 0;
+        let mut evicted = Vec::new();
+        for (_, _, _, handle) in candidates {
+            if freed >= bytes {
+                break;
+            }
+            freed += self.tracked(handle).size;
+            evicted.push(handle);
+        }
+        if evicted.is_empty() {
+            return Ok(0);
+        }
+
+        let objects = evicted
+            .iter()
+            .map(|&handle| self.tracked(handle).object.clone())
+            .collect::<Vec<_>>();
+        self.backend.evict(&objects)?;
+        for handle in evicted {
+            self.tracked(handle).resident = false;
+        }
+        Ok(freed)
+    }
+
+    /// Makes the objects of `set` resident before the submission signaling
+    /// `fence_value`, evicting least recently used objects to stay in the budget.
+    ///
+    /// `completed_fence_value` is the last completed submission, whose objects
+    /// may be evicted.
+    pub fn prepare_submission(
+        &mut self,
+        set: &ResidencySet,
+        fence_value: u64,
+        completed_fence_value: u64,
+    ) -> Result<(), HRESULT> {
+        let mut needed = 0;
+        let mut paged_out = Vec::new();
+        for &handle in &set.handles {
+            let tracked = self.tracked(handle);
+            tracked.last_use = fence_value;
+            if !tracked.resident {
+                needed += tracked.size;
+                paged_out.push(handle);
+            }
+        }
+        if paged_out.is_empty() {
+            return Ok(());
+        }
+
+        let budget = self.backend.query_budget()?;
+        let overflow = (budget.current_usage + needed).saturating_sub(budget.budget);
+        if overflow > 0 {
+            self.evict_lru(overflow, completed_fence_value, &set.handles)?;
+        }
+
+        let objects = paged_out
+            .iter()
+            .map(|&handle| self.tracked(handle).object.clone())
+            .collect::<Vec<_>>();
+        self.backend.make_resident(&objects)?;
+        for handle in paged_out {
+            self.tracked(handle).resident = true;
+        }
+        Ok(())
+    }
+
+    /// Evicts least recently used objects until the usage fits in the budget,
+    /// e.g. after a budget change notification.
+    pub fn trim(&mut self, completed_fence_value: u64) -> Result<(), HRESULT> {
+        let budget = self.backend.query_budget()?;
+        let overflow = budget.current_usage.saturating_sub(budget.budget);
+        if overflow > 0 {
+            self.evict_lru(overflow, completed_fence_value, &HashSet::new())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct Fake {
+        budget: u64,
+        usage: u64,
+        evicted: Vec<&'static str>,
+        made_resident: Vec<&'static str>,
+        fail: Option<HRESULT>,
+    }
+
+    impl Fake {
+        fn result(&self) -> Result<(), HRESULT> {
+            match self.fail {
+                Some(hr) => Err(hr),
+                None => Ok(()),
+            }
+        }
+    }
+
+    impl ResidencyBackend for Fake {
+        type Object = (&'static str, u64);
+
+        fn query_budget(&mut self) -> Result<VideoMemoryInfo, HRESULT> {
+            self.result()?;
+            Ok(VideoMemoryInfo {
+                budget: self.budget,
+                current_usage: self.usage,
+                ..Default::default()
+            })
+        }
+
+        fn make_resident(&mut self, objects: &[Self::Object]) -> Result<(), HRESULT> {
+            self.result()?;
+            for &(name, size) in objects {
+                self.usage += size;
+                self.made_resident.push(name);
+            }
+            Ok(())
+        }
+
+        fn evict(&mut self, objects: &[Self::Object]) -> Result<(), HRESULT> {
+            self.result()?;
+            for &(name, size) in objects {
+                self.usage -= size;
+                self.evicted.push(name);
+            }
+            Ok(())
+        }
+
+        fn set_priority(&mut self, _: &Self::Object, _: ResidencyPriority) -> Result<(), HRESULT> {
+            self.result()
+        }
+    }
+
+    /// Tracks resident objects of 40 bytes in a budget of 100 bytes.
+    fn manager(names: &[&'static str]) -> (ResidencyManager<Fake>, Vec<ResidencyHandle>) {
+        let mut manager = ResidencyManager::new(Fake {
+            budget: 100,
+            ..Default::default()
+        });
+        let handles = names
+            .iter()
+            .map(|&name| manager.track((name, 40), 40))
+            .collect();
+        manager.backend_mut().usage = 40 * names.len() as u64;
+        (manager, handles)
+    }
+
+    fn submit(
+        manager: &mut ResidencyManager<Fake>,
+        handles: &[ResidencyHandle],
+        fence_value: u64,
+        completed_fence_value: u64,
+    ) -> Result<(), HRESULT> {
+        let mut set = ResidencySet::new();
+        for &handle in handles {
+            set.insert(handle);
+        }
+        manager.prepare_submission(&set, fence_value, completed_fence_value)
+    }
+
+    #[test]
+    fn lower_priorities_are_evicted_first() {
+        let (mut manager, handles) = manager(&["a", "b", "c"]);
+        let (a, b, c) = (handles[0], handles[1], handles[2]);
+        manager.evict(c).unwrap();
+        manager.set_priority(a, ResidencyPriority::High).unwrap();
+        manager.set_priority(b, ResidencyPriority::Low).unwrap();
+        submit(&mut manager, &[a], 1, 0).unwrap();
+        submit(&mut manager, &[b], 2, 0).unwrap();
+
+        // `a` is the least recently used, but `b` has a lower priority.
+        submit(&mut manager, &[c], 3, 2).unwrap();
+        assert_eq!(manager.backend().evicted, ["c", "b"]);
+        assert!(manager.is_resident(a) && !manager.is_resident(b) && manager.is_resident(c));
+    }
+
+    #[test]
+    fn pending_objects_are_not_evicted() {
+        let (mut manager, handles) = manager(&["a", "b", "c"]);
+        let (a, b, c) = (handles[0], handles[1], handles[2]);
+        manager.evict(c).unwrap();
+        submit(&mut manager, &[a], 1, 0).unwrap();
+        submit(&mut manager, &[b], 2, 0).unwrap();
+
+        // Nothing can be evicted, `c` is made resident over the budget.
+        submit(&mut manager, &[c], 3, 0).unwrap();
+        assert_eq!(manager.backend().evicted, ["c"]);
+        assert_eq!(manager.backend().made_resident, ["c"]);
+        assert_eq!(manager.backend().usage, 120);
+
+        // Only `a` has completed.
+        manager.trim(1).unwrap();
+        assert_eq!(manager.backend().evicted, ["c", "a"]);
+        assert!(manager.is_resident(b) && manager.is_resident(c));
+    }
+
+    #[test]
+    fn trim_evicts_until_in_budget() {
+        let (mut manager, handles) = manager(&["a", "b", "c"]);
+        submit(&mut manager, &handles, 1, 0).unwrap();
+        manager.trim(0).unwrap();
+        assert!(manager.backend().evicted.is_empty());
+
+        manager.backend_mut().budget = 50;
+        manager.trim(1).unwrap();
+        assert_eq!(manager.backend().evicted, ["a", "b"]);
+        assert_eq!(manager.backend().usage, 40);
+        assert!(manager.is_resident(handles[2]));
+    }
+
+    #[test]
+    fn backend_errors_are_propagated() {
+        let (mut manager, handles) = manager(&["a", "b"]);
+        let (a, b) = (handles[0], handles[1]);
+        manager.evict(b).unwrap();
+        manager.backend_mut().fail = Some(winerror::E_OUTOFMEMORY);
+
+        assert_eq!(manager.evict(a), Err(winerror::E_OUTOFMEMORY));
+        assert!(manager.is_resident(a));
+        assert_eq!(
+            manager.set_priority(a, ResidencyPriority::High),
+            Err(winerror::E_OUTOFMEMORY)
+        );
+        assert_eq!(
+            submit(&mut manager, &[b], 1, 0),
+            Err(winerror::E_OUTOFMEMORY)
+        );
+        assert!(!manager.is_resident(b));
+        assert_eq!(manager.trim(0), Err(winerror::E_OUTOFMEMORY));
+
+        manager.backend_mut().fail = None;
+        submit(&mut manager, &[b], 2, 0).unwrap();
+        assert!(manager.is_resident(b));
+    }
+
+    #[test]
+    fn reused_slots_invalidate_old_handles() {
+        let (mut manager, handles) = manager(&["a"]);
+        let a = handles[0];
+        assert_eq!(manager.untrack(a), ("a", 40));
+        let b = manager.track(("b", 40), 40);
+
+        assert_ne!(a, b);
+        assert!(!manager.is_resident(a));
+        assert!(manager.is_resident(b));
+    }
+
+    #[test]
+    #[should_panic(expected = "Object is not tracked")]
+    fn stale_handles_panic() {
+        let (mut manager, handles) = manager(&["a"]);
+        manager.untrack(handles[0]);
+        manager.track(("b", 40), 40);
+        manager.evict(handles[0]).unwrap();
+    }
+}
@@ -1,16 +1,94 @@
 //! GPU Resource
 
-use crate::{com::ComPtr, D3DResult, Rect};
-use std::{ops::Range, ptr};
-use winapi::um::d3d12;
+use crate::{com::ComPtr, ffi, D3DResult, Device, Rect};
+use std::{mem, ops::Range, ptr};
+use winapi::{
+    shared::{dxgiformat, dxgitype},
+    um::d3d12,
+    Interface,
+};
 
 pub type Subresource = u32;
+pub type PlacedFootprint = d3d12::D3D12_PLACED_SUBRESOURCE_FOOTPRINT;
+
+/// Layout of a single subresource inside a buffer, as returned by
+/// `Device::get_copyable_footprints`.
+#[derive(Clone, Copy)]
+pub struct CopyableFootprint {
+    pub layout: PlacedFootprint,
+    /// Number of rows, which are rows of blocks for block-compressed formats.
+    pub num_rows: u32,
+    /// Size of a single row without the pitch padding.
+    pub row_size: u64,
+}
 
 pub struct DiscardRegion<'a> {
     pub rects: &'a [Rect],
     pub subregions: Range<Subresource>,
 }
 
+bitflags::bitflags! {
+    #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    pub struct ResourceFlags: u32 {
+        const ALLOW_RENDER_TARGET = d3d12::D3D12_RESOURCE_FLAG_ALLOW_RENDER_TARGET;
+        const ALLOW_DEPTH_STENCIL = d3d12::D3D12_RESOURCE_FLAG_ALLOW_DEPTH_STENCIL;
+        const ALLOW_UNORDERED_ACCESS = d3d12::D3D12_RESOURCE_FLAG_ALLOW_UNORDERED_ACCESS;
+        const DENY_SHADER_RESOURCE = d3d12::D3D12_RESOURCE_FLAG_DENY_SHADER_RESOURCE;
+        const ALLOW_CROSS_ADAPTER = d3d12::D3D12_RESOURCE_FLAG_ALLOW_CROSS_ADAPTER;
+        const ALLOW_SIMULTANEOUS_ACCESS = d3d12::D3D12_RESOURCE_FLAG_ALLOW_SIMULTANEOUS_ACCESS;
+    }
+}
+
+#[repr(transparent)]
+pub struct ResourceDesc(pub d3d12::D3D12_RESOURCE_DESC);
+impl ResourceDesc {
+    pub fn buffer(size_in_bytes: u64, flags: ResourceFlags) -> Self {
+        ResourceDesc(d3d12::D3D12_RESOURCE_DESC {
+            Dimension: d3d12::D3D12_RESOURCE_DIMENSION_BUFFER,
+            Alignment: 0,
+            Width: size_in_bytes,
+            Height: 1,
+            DepthOrArraySize: 1,
+            MipLevels: 1,
+            Format: dxgiformat::DXGI_FORMAT_UNKNOWN,
+            SampleDesc: dxgitype::DXGI_SAMPLE_DESC {
+                Count: 1,
+                Quality: 0,
+            },
+            Layout: d3d12::D3D12_TEXTURE_LAYOUT_ROW_MAJOR,
+            Flags: flags.bits(),
+        })
+    }
+}
+
+/// Resource description with the mip region of sampler feedback maps, used by
+/// `Device8::create_committed_resource2`.
+#[repr(transparent)]
+pub struct ResourceDesc1(pub ffi::D3D12_RESOURCE_DESC1);
+
+impl From<ResourceDesc> for ResourceDesc1 {
+    fn from(desc: ResourceDesc) -> Self {
+        let desc = desc.0;
+        ResourceDesc1(ffi::D3D12_RESOURCE_DESC1 {
+            Dimension: desc.Dimension,
+            Alignment: desc.Alignment,
+            Width: desc.Width,
+            Height: desc.Height,
+            DepthOrArraySize: desc.DepthOrArraySize,
+            MipLevels: desc.MipLevels,
+            Format: desc.Format,
+            SampleDesc: desc.SampleDesc,
+            Layout: desc.Layout,
+            Flags: desc.Flags,
+            SamplerFeedbackMipRegion: ffi::D3D12_MIP_REGION {
+                Width: 0,
+                Height: 0,
+                Depth: 0,
+            },
+        })
+    }
+}
+
 pub type Resource = ComPtr<d3d12::ID3D12Resource>;
 
 impl Resource {
@@ -50,4 +128,42 @@ impl Resource {
     pub fn gpu_virtual_address(&self) -> u64 {
         unsafe { self.GetGPUVirtualAddress() }
     }
+
+    pub fn get_desc(&self) -> ResourceDesc {
+        ResourceDesc(unsafe { self.GetDesc() })
+    }
+
+    pub fn get_device(&self) -> D3DResult<Device> {
+        let mut device = Device::null();
+        let hr = unsafe { self.GetDevice(&d3d12::ID3D12Device::uuidof(), device.mut_void()) };
+
+        (device, hr)
+    }
+}
+
+#[repr(transparent)]
+pub struct TextureCopyLocation(pub(crate) d3d12::D3D12_TEXTURE_COPY_LOCATION);
+
+impl TextureCopyLocation {
+    pub fn subresource(resource: &Resource, subresource: Subresource) -> Self {
+        let mut location = d3d12::D3D12_TEXTURE_COPY_LOCATION {
+            pResource: resource.as_mut_ptr(),
+            Type: d3d12::D3D12_TEXTURE_COPY_TYPE_SUBRESOURCE_INDEX,
+            ..unsafe { mem::zeroed() }
+        };
+        *unsafe { location.u.SubresourceIndex_mut() } = subresource;
+
+        TextureCopyLocation(location)
+    }
+
+    pub fn placed_footprint(buffer: &Resource, footprint: PlacedFootprint) -> Self {
+        let mut location = d3d12::D3D12_TEXTURE_COPY_LOCATION {
+            pResource: buffer.as_mut_ptr(),
+            Type: d3d12::D3D12_TEXTURE_COPY_TYPE_PLACED_FOOTPRINT,
+            ..unsafe { mem::zeroed() }
+        };
+        *unsafe { location.u.PlacedFootprint_mut() } = footprint;
+
+        TextureCopyLocation(location)
+    }
 }
@@ -0,0 +1,247 @@
+//! Sampler feedback
+
+use crate::{
+    ffi, CpuDescriptor, D3DResult, Device8, HeapFlags, HeapProperties, ProtectedResourceSession,
+    Resource, ResourceDesc, ResourceDesc1, ResourceFlags,
+};
+use std::ptr;
+use winapi::{shared::dxgiformat, um::d3d12, Interface};
+
+/// Format of the texture MinMip feedback is decoded into.
+pub const MIN_MIP_DECODE_FORMAT: dxgiformat::DXGI_FORMAT = dxgiformat::DXGI_FORMAT_R8_UINT;
+
+/// Value of decoded MinMip texels whose region has not been sampled.
+pub const MIN_MIP_NOT_REQUESTED: u8 = 0xFF;
+
+/// Kind of feedback recorded by a sampler feedback map.
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum SamplerFeedbackKind {
+    /// Most detailed mip sampled in each region.
+    MinMip = ffi::DXGI_FORMAT_SAMPLER_FEEDBACK_MIN_MIP_OPAQUE,
+    /// Whether each mip of each region has been sampled.
+    MipRegionUsed = ffi::DXGI_FORMAT_SAMPLER_FEEDBACK_MIP_REGION_USED_OPAQUE,
+}
+
+impl ResourceDesc1 {
+    /// Describes a feedback map for the texture of `paired`, with one texel per
+    /// `mip_region` of `[width, height]` texels of its most detailed mip.
+    ///
+    /// Mip regions must be powers of two, at least 4 by 4 and at most half the
+    /// size of the paired texture.
+    pub fn sampler_feedback_map(
+        paired: &ResourceDesc,
+        kind: SamplerFeedbackKind,
+        [width, height]: [u32; 2],
+    ) -> Self {
+        let paired = paired.0;
+        ResourceDesc1(ffi::D3D12_RESOURCE_DESC1 {
+            Dimension: d3d12::D3D12_RESOURCE_DIMENSION_TEXTURE2D,
+            Alignment: 0,
+            Width: paired.Width,
+            Height: paired.Height,
+            DepthOrArraySize: paired.DepthOrArraySize,
+            MipLevels: paired.MipLevels,
+            Format: kind as _,
+            SampleDesc: paired.SampleDesc,
+            Layout: d3d12::D3D12_TEXTURE_LAYOUT_UNKNOWN,
+            Flags: ResourceFlags::ALLOW_UNORDERED_ACCESS.bits(),
+            SamplerFeedbackMipRegion: ffi::D3D12_MIP_REGION {
+                Width: width,
+                Height: height,
+                Depth: 1,
+            },
+        })
+    }
+}
+
+impl Device8 {
+    pub fn create_committed_resource2(
+        &self,
+        properties: HeapProperties,
+        heap_flags: HeapFlags,
+        desc: &ResourceDesc1,
+        initial_state: d3d12::D3D12_RESOURCE_STATES,
+        clear_value: Option<&d3d12::D3D12_CLEAR_VALUE>,
+        protected_session: Option<&ProtectedResourceSession>,
+    ) -> D3DResult<Resource> {
+        let mut resource = Resource::null();
+        let clear_value = match clear_value {
+            Some(value) => value as *const _,
+            None => ptr::null(),
+        };
+
+        let hr = unsafe {
+            self.CreateCommittedResource2(
+                &properties.0,
+                heap_flags.bits(),
+                &desc.0,
+                initial_state,
+                clear_value,
+                protected_session.map_or(ptr::null_mut(), |session| session.as_mut_ptr()),
+                &d3d12::ID3D12Resource::uuidof(),
+                resource.mut_void(),
+            )
+        };
+
+        (resource, hr)
+    }
+
+    /// Creates the view written by `WriteSamplerFeedback` in shaders, recording
+    /// the sampling of `targeted` into the feedback map `feedback`.
+    pub fn create_sampler_feedback_unordered_access_view(
+        &self,
+        targeted: &Resource,
+        feedback: &Resource,
+        descriptor: CpuDescriptor,
+    ) {
+        unsafe {
+            self.CreateSamplerFeedbackUnorderedAccessView(
+                targeted.as_mut_ptr(),
+                feedback.as_mut_ptr(),
+                descriptor,
+            )
+        }
+    }
+}
+
+/// MinMip feedback decoded with `ResolveMode::DecodeSamplerFeedback` into an
+/// `R8_UINT` texture, and read back to the CPU.
+///
+/// ```rust
+/// # use d3d12::MinMipFeedback;
+/// // 1024x512 texture with 64x64 mip regions, read back with a 256 byte row pitch.
+/// let mut data = vec![0xFF; 256 * 8];
+/// data[3] = 2;
+/// data[256 + 5] = 0;
+/// let feedback = MinMipFeedback::new(&data, 256, [1024, 512], [64, 64]);
+/// assert_eq!(feedback.size(), (16, 8));
+/// assert_eq!(feedback.region(3, 0), Some(2));
+/// assert_eq!(feedback.region(4, 0), None);
+/// assert_eq!(feedback.texel(64 * 5 + 10, 70), Some(0));
+/// assert_eq!(feedback.min_requested_mip(), Some(0));
+/// assert_eq!(feedback.requested_regions().count(), 2);
+/// ```
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct MinMipFeedback {
+    width: u32,
+    height: u32,
+    mip_region: [u32; 2],
+    mips: Vec<u8>,
+}
+
+impl MinMipFeedback {
+    /// Copies the decoded regions of a texture of `[width, height]` texels out of
+    /// `data`, whose rows are `row_pitch` bytes apart.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `data` is too small to hold the decoded regions.
+    pub fn new(
+        data: &[u8],
+        row_pitch: usize,
+        [width, height]: [u32; 2],
+        mip_region: [u32; 2],
+    ) -> Self {
+        let (width, height) = min_mip_feedback_size(width, height, mip_region);
+        let mut mips = Vec::with_capacity((width * height) as usize);
+        for y in 0..height as usize {
+            let start = y * row_pitch;
+            mips.extend_from_slice(&data[start..start + width as usize]);
+        }
+
+        MinMipFeedback {
+            width,
+            height,
+            mip_region,
+            mips,
+        }
+    }
+
+    /// Number of mip regions in width and height.
+    pub fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Most detailed mip requested in the region at `x`, `y`, or `None` if the
+    /// region has not been sampled.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `x` or `y` is out of bounds of `size`.
+    pub fn region(&self, x: u32, y: u32) -> Option<u8> {
+        assert!(
+            x < self.width && y < self.height,
+            "region ({}, {}) out of bounds",
+            x,
+            y
+        );
+        match self.mips[(y * self.width + x) as usize] {
+            MIN_MIP_NOT_REQUESTED => None,
+            mip => Some(mip),
+        }
+    }
+
+    /// Most detailed mip requested in the region covering the texel at `x`, `y`
+    /// of the most detailed mip.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the region covering the texel is out of bounds of `size`.
+    pub fn texel(&self, x: u32, y: u32) -> Option<u8> {
+        self.region(x / self.mip_region[0], y / self.mip_region[1])
+    }
+
+    /// Most detailed mip requested over the whole texture.
+    pub fn min_requested_mip(&self) -> Option<u8> {
+        self.mips
+            .iter()
+            .copied()
+            .filter(|&mip| mip != MIN_MIP_NOT_REQUESTED)
+            .min()
+    }
+
+    /// Iterates over the sampled regions, as `(x, y, mip)`.
+    pub fn requested_regions(&self) -> impl Iterator<Item = (u32, u32, u8)> + '_ {
+        let width = self.width;
+        self.mips
+            .iter()
+            .enumerate()
+            .filter(|&(_, &mip)| mip != MIN_MIP_NOT_REQUESTED)
+            .map(move |(i, &mip)| (i as u32 % width, i as u32 / width, mip))
+    }
+}
+
+/// Returns the size in texels of the `MIN_MIP_DECODE_FORMAT` texture that MinMip
+/// feedback of a `width` by `height` texture is decoded into.
+pub fn min_mip_feedback_size(
+    width: u32,
+    height: u32,
+    [region_width, region_height]: [u32; 2],
+) -> (u32, u32) {
+    let regions = |size: u32, region: u32| match size {
+        0 => 0,
+        _ => (size - 1) / region + 1,
+    };
+    (regions(width, region_width), regions(height, region_height))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[should_panic(expected = "out of bounds")]
+    fn region_out_of_bounds() {
+        // 3x2 regions, column 3 would alias the start of the next row.
+        let feedback = MinMipFeedback::new(&[0; 6], 3, [12, 8], [4, 4]);
+        feedback.region(3, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "out of bounds")]
+    fn texel_out_of_bounds() {
+        let feedback = MinMipFeedback::new(&[0; 6], 3, [12, 8], [4, 4]);
+        feedback.texel(5, 8);
+    }
+}
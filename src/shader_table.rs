@@ -0,0 +1,279 @@
+//! Raytracing shader tables
+
+use crate::{align_up, ffi, GpuAddress, SHADER_IDENTIFIER_SIZE};
+
+/// Alignment of each shader record.
+pub const SHADER_RECORD_ALIGNMENT: u64 = ffi::D3D12_RAYTRACING_SHADER_RECORD_BYTE_ALIGNMENT;
+/// Alignment of the start of each shader table.
+pub const SHADER_TABLE_ALIGNMENT: u64 = ffi::D3D12_RAYTRACING_SHADER_TABLE_BYTE_ALIGNMENT;
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ShaderTable {
+    RayGeneration,
+    Miss,
+    HitGroup,
+    Callable,
+}
+
+/// Placement of the records of one table, relative to the start of the shader tables.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct ShaderTableRange {
+    pub offset: u64,
+    /// Size of each record, zero for empty tables.
+    pub stride: u64,
+    pub count: u32,
+}
+
+impl ShaderTableRange {
+    fn new(offset: u64, argument_sizes: &[u32]) -> Self {
+        let stride = match argument_sizes.iter().max() {
+            Some(&max) => align_up(
+                SHADER_IDENTIFIER_SIZE as u64 + max as u64,
+                SHADER_RECORD_ALIGNMENT,
+            ),
+            None => 0,
+        };
+        ShaderTableRange {
+            offset: align_up(offset, SHADER_TABLE_ALIGNMENT),
+            stride,
+            count: argument_sizes.len() as u32,
+        }
+    }
+
+    pub fn size(&self) -> u64 {
+        self.stride * self.count as u64
+    }
+
+    pub fn end(&self) -> u64 {
+        self.offset + self.size()
+    }
+}
+
+/// Layout of the ray generation, miss, hit group and callable shader tables in
+/// a single buffer.
+///
+/// Each record starts with a shader identifier followed by the local root
+/// arguments. Records of a table share a stride which fits the largest arguments.
+///
+/// ```rust
+/// # use d3d12::{ShaderTable, ShaderTableLayout, ShaderTableRange};
+/// // Ray generation with 8 bytes of arguments, two miss shaders without
+/// // arguments, three hit groups with up to 16 bytes and no callable shaders.
+/// let layout = ShaderTableLayout::new(8, &[0, 0], &[16, 4, 0], &[]);
+/// assert_eq!(layout.range(ShaderTable::RayGeneration), ShaderTableRange { offset: 0, stride: 64, count: 1 });
+/// assert_eq!(layout.range(ShaderTable::Miss), ShaderTableRange { offset: 64, stride: 32, count: 2 });
+/// assert_eq!(layout.range(ShaderTable::HitGroup), ShaderTableRange { offset: 128, stride: 64, count: 3 });
+/// assert_eq!(layout.range(ShaderTable::Callable).size(), 0);
+/// assert_eq!(layout.size(), 320);
+/// assert_eq!(layout.record_offset(ShaderTable::HitGroup, 2), 256);
+///
+/// let desc = layout.dispatch_rays_desc(0x10000, [1920, 1080, 1]);
+/// assert_eq!(desc.HitGroupTable.StartAddress, 0x10000 + 128);
+/// assert_eq!(desc.HitGroupTable.SizeInBytes, 192);
+/// assert_eq!(desc.CallableShaderTable.StartAddress, 0);
+///
+/// let mut data = vec![0; layout.size() as usize];
+/// layout.write_record(&mut data, ShaderTable::Miss, 1, &[7; 32], &[]);
+/// assert_eq!(data[96..128], [7; 32]);
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct ShaderTableLayout {
+    ray_generation: ShaderTableRange,
+    miss: ShaderTableRange,
+    hit_group: ShaderTableRange,
+    callable: ShaderTableRange,
+}
+
+impl ShaderTableLayout {
+    /// Lays out the tables from the size in bytes of the local root arguments of
+    /// each record, with a single ray generation record.
+    pub fn new(
+        ray_generation_arguments: u32,
+        miss_arguments: &[u32],
+        hit_group_arguments: &[u32],
+        callable_arguments: &[u32],
+    ) -> Self {
+        let ray_generation = ShaderTableRange::new(0, &[ray_generation_arguments]);
+        let miss = ShaderTableRange::new(ray_generation.end(), miss_arguments);
+        let hit_group = ShaderTableRange::new(miss.end(), hit_group_arguments);
+        let callable = ShaderTableRange::new(hit_group.end(), callable_arguments);
+
+        ShaderTableLayout {
+            ray_generation,
+            miss,
+            hit_group,
+            callable,
+        }
+    }
+
+    pub fn range(&self, table: ShaderTable) -> ShaderTableRange {
+        match table {
+            ShaderTable::RayGeneration => self.ray_generation,
+            ShaderTable::Miss => self.miss,
+            ShaderTable::HitGroup => self.hit_group,
+            ShaderTable::Callable => self.callable,
+        }
+    }
+
+    /// Size of the buffer holding all tables.
+    pub fn size(&self) -> u64 {
+        self.callable.end()
+    }
+
+    /// Offset of the record at `index` of `table`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds of the table.
+    pub fn record_offset(&self, table: ShaderTable, index: u32) -> u64 {
+        let range = self.range(table);
+        assert!(
+            index < range.count,
+            "record {} out of bounds of the {:?} table",
+            index,
+            table
+        );
+        range.offset + range.stride * index as u64
+    }
+
+    /// Writes the record at `index` of `table` into `data`, which holds the tables
+    /// starting at offset zero, e.g. a mapped upload buffer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the record is out of bounds, or if `arguments` is larger than
+    /// the space left in the record.
+    pub fn write_record(
+        &self,
+        data: &mut [u8],
+        table: ShaderTable,
+        index: u32,
+        identifier: &[u8; SHADER_IDENTIFIER_SIZE],
+        arguments: &[u8],
+    ) {
+        let offset = self.record_offset(table, index) as usize;
+        let stride = self.range(table).stride as usize;
+        assert!(SHADER_IDENTIFIER_SIZE + arguments.len() <= stride);
+
+        let record = &mut data[offset..offset + stride];
+        record[..SHADER_IDENTIFIER_SIZE].copy_from_slice(identifier);
+        record[SHADER_IDENTIFIER_SIZE..SHADER_IDENTIFIER_SIZE + arguments.len()]
+            .copy_from_slice(arguments);
+    }
+
+    /// Returns the description for `GraphicsCommandList4::dispatch_rays` of tables
+    /// placed at `base`, which must be aligned to `SHADER_TABLE_ALIGNMENT`.
+    pub fn dispatch_rays_desc(
+        &self,
+        base: GpuAddress,
+        [width, height, depth]: [u32; 3],
+    ) -> ffi::D3D12_DISPATCH_RAYS_DESC {
+        debug_assert_eq!(base % SHADER_TABLE_ALIGNMENT, 0);
+        let table = |range: ShaderTableRange| ffi::D3D12_GPU_VIRTUAL_ADDRESS_RANGE_AND_STRIDE {
+            StartAddress: if range.count == 0 {
+                0
+            } else {
+                base + range.offset
+            },
+            SizeInBytes: range.size(),
+            StrideInBytes: range.stride,
+        };
+
+        ffi::D3D12_DISPATCH_RAYS_DESC {
+            RayGenerationShaderRecord: ffi::D3D12_GPU_VIRTUAL_ADDRESS_RANGE {
+                StartAddress: base + self.ray_generation.offset,
+                SizeInBytes: self.ray_generation.size(),
+            },
+            MissShaderTable: table(self.miss),
+            HitGroupTable: table(self.hit_group),
+            CallableShaderTable: table(self.callable),
+            Width: width,
+            Height: height,
+            Depth: depth,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strides_round_up_to_whole_records() {
+        let layout = ShaderTableLayout::new(0, &[1], &[32], &[33]);
+        assert_eq!(layout.range(ShaderTable::RayGeneration).stride, 32);
+        assert_eq!(layout.range(ShaderTable::Miss).stride, 64);
+        assert_eq!(layout.range(ShaderTable::HitGroup).stride, 64);
+        assert_eq!(layout.range(ShaderTable::Callable).stride, 96);
+    }
+
+    #[test]
+    fn empty_tables() {
+        let layout = ShaderTableLayout::new(0, &[], &[8], &[]);
+        let empty = ShaderTableRange {
+            offset: 64,
+            stride: 0,
+            count: 0,
+        };
+        assert_eq!(layout.range(ShaderTable::Miss), empty);
+        assert_eq!(empty.size(), 0);
+        assert_eq!(layout.range(ShaderTable::HitGroup).offset, 64);
+        assert_eq!(
+            layout.range(ShaderTable::Callable),
+            ShaderTableRange {
+                offset: 128,
+                ..empty
+            }
+        );
+        assert_eq!(layout.size(), 128);
+
+        let desc = layout.dispatch_rays_desc(0x10000, [1, 1, 1]);
+        assert_eq!(desc.MissShaderTable.StartAddress, 0);
+        assert_eq!(desc.MissShaderTable.SizeInBytes, 0);
+        assert_eq!(desc.MissShaderTable.StrideInBytes, 0);
+        assert_eq!(desc.HitGroupTable.StartAddress, 0x10000 + 64);
+        assert_eq!(desc.CallableShaderTable.StartAddress, 0);
+        assert_eq!(desc.CallableShaderTable.SizeInBytes, 0);
+    }
+
+    #[test]
+    fn tables_aligned_after_odd_record_counts() {
+        let layout = ShaderTableLayout::new(0, &[0; 3], &[0], &[0]);
+        assert_eq!(layout.range(ShaderTable::Miss).offset, 64);
+        assert_eq!(layout.range(ShaderTable::Miss).end(), 160);
+        assert_eq!(layout.range(ShaderTable::HitGroup).offset, 192);
+        assert_eq!(layout.range(ShaderTable::Callable).offset, 256);
+        assert_eq!(layout.size(), 288);
+        for &table in &[
+            ShaderTable::Miss,
+            ShaderTable::HitGroup,
+            ShaderTable::Callable,
+        ] {
+            assert_eq!(layout.range(table).offset % SHADER_TABLE_ALIGNMENT, 0);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "out of bounds")]
+    fn write_record_index_out_of_bounds() {
+        let layout = ShaderTableLayout::new(0, &[0; 2], &[], &[]);
+        let mut data = vec![0; layout.size() as usize];
+        layout.write_record(&mut data, ShaderTable::Miss, 2, &[0; 32], &[]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn write_record_arguments_too_large() {
+        let layout = ShaderTableLayout::new(0, &[8], &[], &[]);
+        let mut data = vec![0; layout.size() as usize];
+        layout.write_record(&mut data, ShaderTable::Miss, 0, &[0; 32], &[0; 33]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn write_record_data_too_small() {
+        let layout = ShaderTableLayout::new(0, &[0], &[], &[]);
+        let mut data = vec![0; layout.size() as usize - 1];
+        layout.write_record(&mut data, ShaderTable::Miss, 0, &[0; 32], &[]);
+    }
+}
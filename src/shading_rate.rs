@@ -0,0 +1,151 @@
+//! Variable rate shading
+
+use crate::{ffi, Device, GraphicsCommandList5, Resource, VariableShadingRateTier, HRESULT};
+use std::ptr;
+use winapi::shared::{dxgiformat, minwindef::FALSE, winerror};
+
+/// Format of shading rate images, each texel holds a `ShadingRate`.
+pub const SHADING_RATE_IMAGE_FORMAT: dxgiformat::DXGI_FORMAT = dxgiformat::DXGI_FORMAT_R8_UINT;
+
+/// Size in pixels of the coarse pixels shaded once.
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ShadingRate {
+    R1x1 = ffi::D3D12_SHADING_RATE_1X1,
+    R1x2 = ffi::D3D12_SHADING_RATE_1X2,
+    R2x1 = ffi::D3D12_SHADING_RATE_2X1,
+    R2x2 = ffi::D3D12_SHADING_RATE_2X2,
+    /// Requires `VariableShadingRateSupport::additional_shading_rates`.
+    R2x4 = ffi::D3D12_SHADING_RATE_2X4,
+    /// Requires `VariableShadingRateSupport::additional_shading_rates`.
+    R4x2 = ffi::D3D12_SHADING_RATE_4X2,
+    /// Requires `VariableShadingRateSupport::additional_shading_rates`.
+    R4x4 = ffi::D3D12_SHADING_RATE_4X4,
+}
+
+impl ShadingRate {
+    /// Width and height of the coarse pixel.
+    pub fn dimensions(self) -> (u32, u32) {
+        let rate = self as u32;
+        (1 << (rate >> 2), 1 << (rate & 0x3))
+    }
+}
+
+/// How a shading rate is combined with the rate of the next stage, which is the
+/// per primitive rate for the first combiner and the shading rate image for the second.
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ShadingRateCombiner {
+    /// Keeps the incoming rate.
+    Passthrough = ffi::D3D12_SHADING_RATE_COMBINER_PASSTHROUGH,
+    /// Uses the rate of the next stage.
+    Override = ffi::D3D12_SHADING_RATE_COMBINER_OVERRIDE,
+    Min = ffi::D3D12_SHADING_RATE_COMBINER_MIN,
+    Max = ffi::D3D12_SHADING_RATE_COMBINER_MAX,
+    Sum = ffi::D3D12_SHADING_RATE_COMBINER_SUM,
+}
+
+/// Variable rate shading support of a device.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct VariableShadingRateSupport {
+    pub tier: VariableShadingRateTier,
+    /// Size in pixels of the tile covered by each texel of a shading rate image,
+    /// zero below `VariableShadingRateTier::Tier2`.
+    pub tile_size: u32,
+    pub additional_shading_rates: bool,
+    pub per_primitive_with_viewport_indexing: bool,
+    pub background_processing: bool,
+}
+
+impl VariableShadingRateSupport {
+    /// Queries the support of `device`, which reports no support on runtimes
+    /// which don't know about variable rate shading.
+    pub fn new(device: &Device) -> Result<Self, HRESULT> {
+        let (options, hr) = device.check_feature::<ffi::D3D12_FEATURE_DATA_D3D12_OPTIONS6>();
+        if hr == winerror::E_INVALIDARG {
+            return Ok(VariableShadingRateSupport {
+                tier: VariableShadingRateTier::NotSupported,
+                tile_size: 0,
+                additional_shading_rates: false,
+                per_primitive_with_viewport_indexing: false,
+                background_processing: false,
+            });
+        }
+        if !winerror::SUCCEEDED(hr) {
+            return Err(hr);
+        }
+
+        Ok(options.into())
+    }
+
+    /// Returns the size of the shading rate image for a render target of `width`
+    /// by `height` pixels, or `None` if shading rate images are not supported.
+    pub fn image_size(&self, width: u32, height: u32) -> Option<(u32, u32)> {
+        shading_rate_image_size(width, height, self.tile_size)
+    }
+}
+
+impl From<ffi::D3D12_FEATURE_DATA_D3D12_OPTIONS6> for VariableShadingRateSupport {
+    fn from(options: ffi::D3D12_FEATURE_DATA_D3D12_OPTIONS6) -> Self {
+        VariableShadingRateSupport {
+            tier: options.VariableShadingRateTier.into(),
+            tile_size: options.ShadingRateImageTileSize,
+            additional_shading_rates: options.AdditionalShadingRatesSupported != FALSE,
+            per_primitive_with_viewport_indexing: options
+                .PerPrimitiveShadingRateSupportedWithViewportIndexing
+                != FALSE,
+            background_processing: options.BackgroundProcessingSupported != FALSE,
+        }
+    }
+}
+
+/// Returns the width and height of the shading rate image covering a render target
+/// of `width` by `height` pixels, with one texel per `tile_size` square tile.
+///
+/// Returns `None` if `tile_size` is zero, which devices report when shading rate
+/// images are not supported.
+///
+/// ```rust
+/// assert_eq!(d3d12::shading_rate_image_size(1920, 1080, 16), Some((120, 68)));
+/// assert_eq!(d3d12::shading_rate_image_size(1920, 1080, 0), None);
+/// ```
+pub fn shading_rate_image_size(width: u32, height: u32, tile_size: u32) -> Option<(u32, u32)> {
+    if tile_size == 0 {
+        return None;
+    }
+    let tiles = |size: u32| match size {
+        0 => 0,
+        _ => (size - 1) / tile_size + 1,
+    };
+    Some((tiles(width), tiles(height)))
+}
+
+impl GraphicsCommandList5 {
+    /// Sets the base shading rate of the following draws, and how it combines
+    /// with the per primitive and image based rates. Combiners require
+    /// `VariableShadingRateTier::Tier2`, `None` passes the base rate through.
+    pub fn rs_set_shading_rate(
+        &self,
+        rate: ShadingRate,
+        combiners: Option<[ShadingRateCombiner; 2]>,
+    ) {
+        let combiners = combiners.map(|combiners| combiners.map(|combiner| combiner as u32));
+        unsafe {
+            self.RSSetShadingRate(
+                rate as _,
+                combiners
+                    .as_ref()
+                    .map_or(ptr::null(), |combiners| combiners.as_ptr()),
+            )
+        }
+    }
+
+    /// Sets the shading rate image, in `SHADING_RATE_IMAGE_FORMAT`, or unbinds it with `None`.
+    ///
+    /// The image must be in the `ffi::D3D12_RESOURCE_STATE_SHADING_RATE_SOURCE` state.
+    pub fn rs_set_shading_rate_image(&self, image: Option<&Resource>) {
+        unsafe {
+            self.RSSetShadingRateImage(image.map_or(ptr::null_mut(), |image| image.as_mut_ptr()))
+        }
+    }
+}
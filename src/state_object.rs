@@ -0,0 +1,534 @@
+//! State objects
+
+use crate::{com::ComPtr, ffi, D3DResult, Device5, Device7, GraphicsCommandList4, RootSignature};
+use std::{any::Any, ptr};
+use winapi::{ctypes::c_void, um::d3d12, Interface};
+
+pub type StateObject = ComPtr<ffi::ID3D12StateObject>;
+pub type StateObjectProperties = ComPtr<ffi::ID3D12StateObjectProperties>;
+
+/// Size of the shader identifiers at the start of each shader record.
+pub const SHADER_IDENTIFIER_SIZE: usize = ffi::D3D12_SHADER_IDENTIFIER_SIZE_IN_BYTES;
+
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum StateObjectType {
+    /// Subobjects which can be reused by other state objects.
+    Collection = ffi::D3D12_STATE_OBJECT_TYPE_COLLECTION,
+    RaytracingPipeline = ffi::D3D12_STATE_OBJECT_TYPE_RAYTRACING_PIPELINE,
+}
+
+bitflags::bitflags! {
+    #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    pub struct StateObjectFlags: u32 {
+        const ALLOW_LOCAL_DEPENDENCIES_ON_EXTERNAL_DEFINITIONS =
+            ffi::D3D12_STATE_OBJECT_FLAG_ALLOW_LOCAL_DEPENDENCIES_ON_EXTERNAL_DEFINITIONS;
+        const ALLOW_EXTERNAL_DEPENDENCIES_ON_LOCAL_DEFINITIONS =
+            ffi::D3D12_STATE_OBJECT_FLAG_ALLOW_EXTERNAL_DEPENDENCIES_ON_LOCAL_DEFINITIONS;
+        /// Required to grow the state object with `StateObjectBuilder::add_to`.
+        const ALLOW_STATE_OBJECT_ADDITIONS =
+            ffi::D3D12_STATE_OBJECT_FLAG_ALLOW_STATE_OBJECT_ADDITIONS;
+    }
+}
+
+bitflags::bitflags! {
+    #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    pub struct RaytracingPipelineFlags: u32 {
+        const SKIP_TRIANGLES = ffi::D3D12_RAYTRACING_PIPELINE_FLAG_SKIP_TRIANGLES;
+        const SKIP_PROCEDURAL_PRIMITIVES =
+            ffi::D3D12_RAYTRACING_PIPELINE_FLAG_SKIP_PROCEDURAL_PRIMITIVES;
+    }
+}
+
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum HitGroupType {
+    Triangles = ffi::D3D12_HIT_GROUP_TYPE_TRIANGLES,
+    ProceduralPrimitive = ffi::D3D12_HIT_GROUP_TYPE_PROCEDURAL_PRIMITIVE,
+}
+
+/// Index of a subobject added to a `StateObjectBuilder`, used to associate it with exports.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct SubobjectIndex(usize);
+
+type WideString = Vec<u16>;
+
+fn wide(name: &str) -> WideString {
+    name.encode_utf16().chain(Some(0)).collect()
+}
+
+fn wide_or_null(name: &Option<WideString>) -> *const u16 {
+    name.as_ref().map_or(ptr::null(), |name| name.as_ptr())
+}
+
+#[derive(Debug)]
+enum Subobject {
+    Config(StateObjectFlags),
+    GlobalRootSignature(RootSignature),
+    LocalRootSignature(RootSignature),
+    NodeMask(u32),
+    DxilLibrary {
+        library: Vec<u8>,
+        exports: Vec<WideString>,
+    },
+    ExistingCollection {
+        collection: StateObject,
+        exports: Vec<WideString>,
+    },
+    HitGroup {
+        name: WideString,
+        ty: HitGroupType,
+        any_hit: Option<WideString>,
+        closest_hit: Option<WideString>,
+        intersection: Option<WideString>,
+    },
+    ShaderConfig {
+        max_payload_size: u32,
+        max_attribute_size: u32,
+    },
+    PipelineConfig {
+        max_recursion_depth: u32,
+        flags: RaytracingPipelineFlags,
+    },
+    Association {
+        subobject: SubobjectIndex,
+        exports: Vec<WideString>,
+    },
+    DxilAssociation {
+        subobject: WideString,
+        exports: Vec<WideString>,
+    },
+}
+
+/// Owned description of a state object, e.g. a raytracing pipeline.
+///
+/// Subobjects are added in order. The pointer graph of `D3D12_STATE_OBJECT_DESC`
+/// is only assembled while creating the state object.
+#[derive(Debug)]
+pub struct StateObjectBuilder {
+    ty: StateObjectType,
+    subobjects: Vec<Subobject>,
+}
+
+impl StateObjectBuilder {
+    pub fn new(ty: StateObjectType) -> Self {
+        StateObjectBuilder {
+            ty,
+            subobjects: Vec::new(),
+        }
+    }
+
+    fn add(&mut self, subobject: Subobject) -> SubobjectIndex {
+        self.subobjects.push(subobject);
+        SubobjectIndex(self.subobjects.len() - 1)
+    }
+
+    pub fn add_config(&mut self, flags: StateObjectFlags) -> SubobjectIndex {
+        self.add(Subobject::Config(flags))
+    }
+
+    pub fn add_global_root_signature(&mut self, signature: &RootSignature) -> SubobjectIndex {
+        self.add(Subobject::GlobalRootSignature(signature.clone()))
+    }
+
+    /// Adds a local root signature, which applies to the exports it is associated with.
+    pub fn add_local_root_signature(&mut self, signature: &RootSignature) -> SubobjectIndex {
+        self.add(Subobject::LocalRootSignature(signature.clone()))
+    }
+
+    pub fn add_node_mask(&mut self, node_mask: u32) -> SubobjectIndex {
+        self.add(Subobject::NodeMask(node_mask))
+    }
+
+    /// Adds a DXIL library, exporting all of its shaders if `exports` is empty.
+    pub fn add_dxil_library(&mut self, library: &[u8], exports: &[&str]) -> SubobjectIndex {
+        self.add(Subobject::DxilLibrary {
+            library: library.to_vec(),
+            exports: exports.iter().map(|name| wide(name)).collect(),
+        })
+    }
+
+    /// Adds the subobjects of a collection, exporting all of them if `exports` is empty.
+    pub fn add_existing_collection(
+        &mut self,
+        collection: &StateObject,
+        exports: &[&str],
+    ) -> SubobjectIndex {
+        self.add(Subobject::ExistingCollection {
+            collection: collection.clone(),
+            exports: exports.iter().map(|name| wide(name)).collect(),
+        })
+    }
+
+    /// Adds a hit group exported as `name` from the given shader exports.
+    pub fn add_hit_group(
+        &mut self,
+        name: &str,
+        ty: HitGroupType,
+        any_hit: Option<&str>,
+        closest_hit: Option<&str>,
+        intersection: Option<&str>,
+    ) -> SubobjectIndex {
+        self.add(Subobject::HitGroup {
+            name: wide(name),
+            ty,
+            any_hit: any_hit.map(wide),
+            closest_hit: closest_hit.map(wide),
+            intersection: intersection.map(wide),
+        })
+    }
+
+    pub fn add_shader_config(
+        &mut self,
+        max_payload_size: u32,
+        max_attribute_size: u32,
+    ) -> SubobjectIndex {
+        self.add(Subobject::ShaderConfig {
+            max_payload_size,
+            max_attribute_size,
+        })
+    }
+
+    /// Adds a pipeline config, which uses `D3D12_RAYTRACING_PIPELINE_CONFIG1`
+    /// if `flags` is not empty.
+    pub fn add_pipeline_config(
+        &mut self,
+        max_recursion_depth: u32,
+        flags: RaytracingPipelineFlags,
+    ) -> SubobjectIndex {
+        self.add(Subobject::PipelineConfig {
+            max_recursion_depth,
+            flags,
+        })
+    }
+
+    /// Associates a previously added subobject, e.g. a local root signature, with `exports`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `subobject` has not been added to this builder.
+    pub fn associate(&mut self, subobject: SubobjectIndex, exports: &[&str]) -> SubobjectIndex {
+        assert!(subobject.0 < self.subobjects.len());
+        self.add(Subobject::Association {
+            subobject,
+            exports: exports.iter().map(|name| wide(name)).collect(),
+        })
+    }
+
+    /// Associates a subobject defined in a DXIL library by name with `exports`.
+    pub fn associate_dxil(&mut self, subobject: &str, exports: &[&str]) -> SubobjectIndex {
+        self.add(Subobject::DxilAssociation {
+            subobject: wide(subobject),
+            exports: exports.iter().map(|name| wide(name)).collect(),
+        })
+    }
+
+    /// Calls `f` with the raw description, whose pointers are only valid during the call.
+    fn with_raw<T>(&self, f: impl FnOnce(&ffi::D3D12_STATE_OBJECT_DESC) -> T) -> T {
+        // Boxed descriptions and arrays keep their address while the vectors grow.
+        let mut descs: Vec<Box<dyn Any>> = Vec::with_capacity(self.subobjects.len());
+        let mut export_descs: Vec<Vec<ffi::D3D12_EXPORT_DESC>> = Vec::new();
+        let mut export_names: Vec<Vec<*const u16>> = Vec::new();
+        // Associations point into this array, so it must not reallocate.
+        let mut subobjects = Vec::with_capacity(self.subobjects.len());
+        let subobjects_ptr: *const ffi::D3D12_STATE_SUBOBJECT = subobjects.as_ptr();
+
+        fn store<D: 'static>(descs: &mut Vec<Box<dyn Any>>, desc: D) -> *const c_void {
+            let desc = Box::new(desc);
+            let ptr = &*desc as *const D as *const c_void;
+            descs.push(desc);
+            ptr
+        }
+
+        fn export_list(
+            export_descs: &mut Vec<Vec<ffi::D3D12_EXPORT_DESC>>,
+            exports: &[WideString],
+        ) -> (u32, *mut ffi::D3D12_EXPORT_DESC) {
+            if exports.is_empty() {
+                return (0, ptr::null_mut());
+            }
+            let mut list: Vec<_> = exports
+                .iter()
+                .map(|name| ffi::D3D12_EXPORT_DESC {
+                    Name: name.as_ptr(),
+                    ExportToRename: ptr::null(),
+                    Flags: ffi::D3D12_EXPORT_FLAG_NONE,
+                })
+                .collect();
+            let ptr = list.as_mut_ptr();
+            export_descs.push(list);
+            (exports.len() as _, ptr)
+        }
+
+        fn name_list(
+            export_names: &mut Vec<Vec<*const u16>>,
+            exports: &[WideString],
+        ) -> (u32, *mut *const u16) {
+            let mut list: Vec<_> = exports.iter().map(|name| name.as_ptr()).collect();
+            let ptr = list.as_mut_ptr();
+            export_names.push(list);
+            (exports.len() as _, ptr)
+        }
+
+        for subobject in &self.subobjects {
+            let (ty, desc) = match *subobject {
+                Subobject::Config(flags) => (
+                    ffi::D3D12_STATE_SUBOBJECT_TYPE_STATE_OBJECT_CONFIG,
+                    store(
+                        &mut descs,
+                        ffi::D3D12_STATE_OBJECT_CONFIG {
+                            Flags: flags.bits(),
+                        },
+                    ),
+                ),
+                Subobject::GlobalRootSignature(ref signature) => (
+                    ffi::D3D12_STATE_SUBOBJECT_TYPE_GLOBAL_ROOT_SIGNATURE,
+                    store(
+                        &mut descs,
+                        ffi::D3D12_GLOBAL_ROOT_SIGNATURE {
+                            pGlobalRootSignature: signature.as_mut_ptr(),
+                        },
+                    ),
+                ),
+                Subobject::LocalRootSignature(ref signature) => (
+                    ffi::D3D12_STATE_SUBOBJECT_TYPE_LOCAL_ROOT_SIGNATURE,
+                    store(
+                        &mut descs,
+                        ffi::D3D12_LOCAL_ROOT_SIGNATURE {
+                            pLocalRootSignature: signature.as_mut_ptr(),
+                        },
+                    ),
+                ),
+                Subobject::NodeMask(node_mask) => (
+                    ffi::D3D12_STATE_SUBOBJECT_TYPE_NODE_MASK,
+                    store(
+                        &mut descs,
+                        ffi::D3D12_NODE_MASK {
+                            NodeMask: node_mask,
+                        },
+                    ),
+                ),
+                Subobject::DxilLibrary {
+                    ref library,
+                    ref exports,
+                } => {
+                    let (count, exports) = export_list(&mut export_descs, exports);
+                    (
+                        ffi::D3D12_STATE_SUBOBJECT_TYPE_DXIL_LIBRARY,
+                        store(
+                            &mut descs,
+                            ffi::D3D12_DXIL_LIBRARY_DESC {
+                                DXILLibrary: d3d12::D3D12_SHADER_BYTECODE {
+                                    pShaderBytecode: library.as_ptr() as *const _,
+                                    BytecodeLength: library.len() as _,
+                                },
+                                NumExports: count,
+                                pExports: exports,
+                            },
+                        ),
+                    )
+                }
+                Subobject::ExistingCollection {
+                    ref collection,
+                    ref exports,
+                } => {
+                    let (count, exports) = export_list(&mut export_descs, exports);
+                    (
+                        ffi::D3D12_STATE_SUBOBJECT_TYPE_EXISTING_COLLECTION,
+                        store(
+                            &mut descs,
+                            ffi::D3D12_EXISTING_COLLECTION_DESC {
+                                pExistingCollection: collection.as_mut_ptr(),
+                                NumExports: count,
+                                pExports: exports,
+                            },
+                        ),
+                    )
+                }
+                Subobject::HitGroup {
+                    ref name,
+                    ty,
+                    ref any_hit,
+                    ref closest_hit,
+                    ref intersection,
+                } => (
+                    ffi::D3D12_STATE_SUBOBJECT_TYPE_HIT_GROUP,
+                    store(
+                        &mut descs,
+                        ffi::D3D12_HIT_GROUP_DESC {
+                            HitGroupExport: name.as_ptr(),
+                            Type: ty as _,
+                            AnyHitShaderImport: wide_or_null(any_hit),
+                            ClosestHitShaderImport: wide_or_null(closest_hit),
+                            IntersectionShaderImport: wide_or_null(intersection),
+                        },
+                    ),
+                ),
+                Subobject::ShaderConfig {
+                    max_payload_size,
+                    max_attribute_size,
+                } => (
+                    ffi::D3D12_STATE_SUBOBJECT_TYPE_RAYTRACING_SHADER_CONFIG,
+                    store(
+                        &mut descs,
+                        ffi::D3D12_RAYTRACING_SHADER_CONFIG {
+                            MaxPayloadSizeInBytes: max_payload_size,
+                            MaxAttributeSizeInBytes: max_attribute_size,
+                        },
+                    ),
+                ),
+                Subobject::PipelineConfig {
+                    max_recursion_depth,
+                    flags,
+                } if flags.is_empty() => (
+                    ffi::D3D12_STATE_SUBOBJECT_TYPE_RAYTRACING_PIPELINE_CONFIG,
+                    store(
+                        &mut descs,
+                        ffi::D3D12_RAYTRACING_PIPELINE_CONFIG {
+                            MaxTraceRecursionDepth: max_recursion_depth,
+                        },
+                    ),
+                ),
+                Subobject::PipelineConfig {
+                    max_recursion_depth,
+                    flags,
+                } => (
+                    ffi::D3D12_STATE_SUBOBJECT_TYPE_RAYTRACING_PIPELINE_CONFIG1,
+                    store(
+                        &mut descs,
+                        ffi::D3D12_RAYTRACING_PIPELINE_CONFIG1 {
+                            MaxTraceRecursionDepth: max_recursion_depth,
+                            Flags: flags.bits(),
+                        },
+                    ),
+                ),
+                Subobject::Association {
+                    subobject,
+                    ref exports,
+                } => {
+                    let (count, exports) = name_list(&mut export_names, exports);
+                    (
+                        ffi::D3D12_STATE_SUBOBJECT_TYPE_SUBOBJECT_TO_EXPORTS_ASSOCIATION,
+                        store(
+                            &mut descs,
+                            ffi::D3D12_SUBOBJECT_TO_EXPORTS_ASSOCIATION {
+                                pSubobjectToAssociate: subobjects_ptr.wrapping_add(subobject.0),
+                                NumExports: count,
+                                pExports: exports,
+                            },
+                        ),
+                    )
+                }
+                Subobject::DxilAssociation {
+                    ref subobject,
+                    ref exports,
+                } => {
+                    let (count, exports) = name_list(&mut export_names, exports);
+                    (
+                        ffi::D3D12_STATE_SUBOBJECT_TYPE_DXIL_SUBOBJECT_TO_EXPORTS_ASSOCIATION,
+                        store(
+                            &mut descs,
+                            ffi::D3D12_DXIL_SUBOBJECT_TO_EXPORTS_ASSOCIATION {
+                                SubobjectToAssociate: subobject.as_ptr(),
+                                NumExports: count,
+                                pExports: exports,
+                            },
+                        ),
+                    )
+                }
+            };
+            subobjects.push(ffi::D3D12_STATE_SUBOBJECT {
+                Type: ty,
+                pDesc: desc,
+            });
+        }
+        debug_assert_eq!(subobjects.as_ptr(), subobjects_ptr);
+
+        f(&ffi::D3D12_STATE_OBJECT_DESC {
+            Type: self.ty as _,
+            NumSubobjects: subobjects.len() as _,
+            pSubobjects: subobjects.as_ptr(),
+        })
+    }
+
+    pub fn build(&self, device: &Device5) -> D3DResult<StateObject> {
+        let mut state_object = StateObject::null();
+        let hr = self.with_raw(|desc| unsafe {
+            device.CreateStateObject(
+                desc,
+                &ffi::ID3D12StateObject::uuidof(),
+                state_object.mut_void(),
+            )
+        });
+
+        (state_object, hr)
+    }
+
+    /// Creates a new state object from `existing` and the subobjects of the builder.
+    ///
+    /// `existing` must have been created with `ALLOW_STATE_OBJECT_ADDITIONS`, which
+    /// the builder also needs to include to allow further additions.
+    pub fn add_to(&self, device: &Device7, existing: &StateObject) -> D3DResult<StateObject> {
+        let mut state_object = StateObject::null();
+        let hr = self.with_raw(|desc| unsafe {
+            device.AddToStateObject(
+                desc,
+                existing.as_mut_ptr(),
+                &ffi::ID3D12StateObject::uuidof(),
+                state_object.mut_void(),
+            )
+        });
+
+        (state_object, hr)
+    }
+}
+
+impl StateObject {
+    pub fn properties(&self) -> D3DResult<StateObjectProperties> {
+        unsafe { self.cast() }
+    }
+}
+
+impl StateObjectProperties {
+    /// Returns the identifier of the shader or hit group exported as `export`, or
+    /// `None` if there is no such export.
+    pub fn get_shader_identifier(&self, export: &str) -> Option<[u8; SHADER_IDENTIFIER_SIZE]> {
+        let export = wide(export);
+        let identifier = unsafe { self.GetShaderIdentifier(export.as_ptr()) };
+        if identifier.is_null() {
+            return None;
+        }
+
+        let mut data = [0; SHADER_IDENTIFIER_SIZE];
+        unsafe {
+            ptr::copy_nonoverlapping(
+                identifier as *const u8,
+                data.as_mut_ptr(),
+                SHADER_IDENTIFIER_SIZE,
+            )
+        };
+        Some(data)
+    }
+
+    /// Stack size of a shader export, where hit group shaders are named
+    /// `hit_group::intersection`, `hit_group::anyhit` or `hit_group::closesthit`.
+    pub fn get_shader_stack_size(&self, export: &str) -> u64 {
+        let export = wide(export);
+        unsafe { self.GetShaderStackSize(export.as_ptr()) }
+    }
+
+    pub fn get_pipeline_stack_size(&self) -> u64 {
+        unsafe { self.GetPipelineStackSize() }
+    }
+
+    pub fn set_pipeline_stack_size(&self, size: u64) {
+        unsafe { self.SetPipelineStackSize(size) }
+    }
+}
+
+impl GraphicsCommandList4 {
+    /// Sets a raytracing pipeline for `dispatch_rays`.
+    pub fn set_pipeline_state1(&self, state_object: &StateObject) {
+        unsafe { self.SetPipelineState1(state_object.as_mut_ptr()) }
+    }
+}
@@ -2,7 +2,7 @@ use crate::{com::ComPtr, HRESULT};
 use std::ptr;
 use winapi::um::{d3d12, synchapi, winnt};
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug)]
 #[repr(transparent)]
 pub struct Event(pub winnt::HANDLE);
 impl Event {
@@ -0,0 +1,189 @@
+//! Tiled resources
+
+use crate::{bits_per_element, block_dimensions, Format, Subresource};
+use winapi::{
+    shared::minwindef::{FALSE, TRUE},
+    um::d3d12,
+};
+
+/// Size in bytes of a tile.
+pub const TILE_SIZE: u64 = d3d12::D3D12_TILED_RESOURCE_TILE_SIZE_IN_BYTES as _;
+
+bitflags::bitflags! {
+    #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    pub struct TileRangeFlags: u32 {
+        /// Unmaps the tiles of the range.
+        const NULL = d3d12::D3D12_TILE_RANGE_FLAG_NULL;
+        /// Leaves the mappings of the range unchanged.
+        const SKIP = d3d12::D3D12_TILE_RANGE_FLAG_SKIP;
+        /// Maps all tiles of the range to the single heap tile at the start of the range.
+        const REUSE_SINGLE_TILE = d3d12::D3D12_TILE_RANGE_FLAG_REUSE_SINGLE_TILE;
+    }
+}
+
+bitflags::bitflags! {
+    #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    pub struct TileMappingFlags: u32 {
+        const NO_HAZARD = d3d12::D3D12_TILE_MAPPING_FLAG_NO_HAZARD;
+    }
+}
+
+/// Location of a tile in a tiled resource, in tiles.
+#[repr(transparent)]
+#[derive(Clone, Copy)]
+pub struct TiledResourceCoordinate(pub(crate) d3d12::D3D12_TILED_RESOURCE_COORDINATE);
+
+impl TiledResourceCoordinate {
+    /// `x`, `y` and `z` are in tiles, inside `subresource`. For buffers and packed
+    /// mips, `x` is the index of the tile in the linear order of the tiles.
+    pub fn new(x: u32, y: u32, z: u32, subresource: Subresource) -> Self {
+        TiledResourceCoordinate(d3d12::D3D12_TILED_RESOURCE_COORDINATE {
+            X: x,
+            Y: y,
+            Z: z,
+            Subresource: subresource,
+        })
+    }
+}
+
+/// Size of a region of tiles, starting at a `TiledResourceCoordinate`.
+#[repr(transparent)]
+#[derive(Clone, Copy)]
+pub struct TileRegionSize(pub(crate) d3d12::D3D12_TILE_REGION_SIZE);
+
+impl TileRegionSize {
+    /// `count` tiles in linear order, which may span subresources.
+    pub fn tiles(count: u32) -> Self {
+        TileRegionSize(d3d12::D3D12_TILE_REGION_SIZE {
+            NumTiles: count,
+            UseBox: FALSE,
+            Width: 0,
+            Height: 0,
+            Depth: 0,
+        })
+    }
+
+    /// Box of `width` by `height` by `depth` tiles inside a single subresource.
+    pub fn boxed(width: u32, height: u16, depth: u16) -> Self {
+        TileRegionSize(d3d12::D3D12_TILE_REGION_SIZE {
+            NumTiles: width * height as u32 * depth as u32,
+            UseBox: TRUE,
+            Width: width,
+            Height: height,
+            Depth: depth,
+        })
+    }
+}
+
+/// Range of tiles in a heap mapped by `CommandQueue::update_tile_mappings`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct TileRange {
+    pub flags: TileRangeFlags,
+    /// Offset of the first tile in the heap, in tiles.
+    pub heap_start: u32,
+    pub count: u32,
+}
+
+/// Dimensions in texels of a tile.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct TileShape {
+    pub width: u32,
+    pub height: u32,
+    pub depth: u32,
+}
+
+/// Packing of the smallest mips, which share tiles.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct PackedMipInfo {
+    pub num_standard_mips: u8,
+    pub num_packed_mips: u8,
+    pub num_tiles: u32,
+    pub start_tile: u32,
+}
+
+/// Tiling of a subresource which is not a packed mip.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct SubresourceTiling {
+    pub width_in_tiles: u32,
+    pub height_in_tiles: u16,
+    pub depth_in_tiles: u16,
+    pub start_tile: u32,
+}
+
+/// Tiling of a reserved resource, as returned by `Device::get_resource_tiling`.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct ResourceTiling {
+    pub num_tiles: u32,
+    pub packed_mip_info: PackedMipInfo,
+    pub tile_shape: TileShape,
+    /// Tilings of each subresource, where packed mips are zeroed.
+    pub subresource_tilings: Vec<SubresourceTiling>,
+}
+
+/// Returns the standard shape of 64KB tiles of single sampled resources of
+/// `format` and `dimension`.
+///
+/// Returns `None` for formats which can't be tiled, and for block-compressed
+/// formats in 1D textures. The format is ignored for buffers.
+///
+/// ```rust
+/// # use winapi::{shared::dxgiformat, um::d3d12 as raw};
+/// # use d3d12::TileShape;
+/// let shape = d3d12::standard_tile_shape(
+///     dxgiformat::DXGI_FORMAT_R8G8B8A8_UNORM,
+///     raw::D3D12_RESOURCE_DIMENSION_TEXTURE2D,
+/// );
+/// assert_eq!(shape, Some(TileShape { width: 128, height: 128, depth: 1 }));
+///
+/// let shape = d3d12::standard_tile_shape(
+///     dxgiformat::DXGI_FORMAT_BC1_UNORM,
+///     raw::D3D12_RESOURCE_DIMENSION_TEXTURE3D,
+/// );
+/// assert_eq!(shape, Some(TileShape { width: 128, height: 64, depth: 16 }));
+/// ```
+pub fn standard_tile_shape(
+    format: Format,
+    dimension: d3d12::D3D12_RESOURCE_DIMENSION,
+) -> Option<TileShape> {
+    if dimension == d3d12::D3D12_RESOURCE_DIMENSION_BUFFER {
+        return Some(TileShape {
+            width: TILE_SIZE as u32,
+            height: 1,
+            depth: 1,
+        });
+    }
+
+    let bytes = match bits_per_element(format)? {
+        bits @ (8 | 16 | 32 | 64 | 128) => bits / 8,
+        _ => return None,
+    };
+    let (block_width, block_height) = block_dimensions(format);
+
+    // Shapes in elements, which are blocks for block-compressed formats.
+    let (width, height, depth) = match dimension {
+        d3d12::D3D12_RESOURCE_DIMENSION_TEXTURE1D if block_width == 1 => {
+            (TILE_SIZE as u32 / bytes, 1, 1)
+        }
+        d3d12::D3D12_RESOURCE_DIMENSION_TEXTURE2D => match bytes {
+            1 => (256, 256, 1),
+            2 => (256, 128, 1),
+            4 => (128, 128, 1),
+            8 => (128, 64, 1),
+            _ => (64, 64, 1),
+        },
+        d3d12::D3D12_RESOURCE_DIMENSION_TEXTURE3D => match bytes {
+            1 => (64, 32, 32),
+            2 => (32, 32, 32),
+            4 => (32, 32, 16),
+            8 => (32, 16, 16),
+            _ => (16, 16, 16),
+        },
+        _ => return None,
+    };
+
+    Some(TileShape {
+        width: width * block_width,
+        height: height * block_height,
+        depth,
+    })
+}
@@ -0,0 +1,531 @@
+//! Transient upload memory
+
+use crate::{
+    align_up,
+    heap::{CpuPageProperty, HeapFlags, HeapProperties, HeapType, MemoryPool},
+    resource::{ResourceDesc, ResourceFlags, TextureCopyLocation},
+    Device, Fence, Format, GpuAddress, GraphicsCommandList, Resource, HRESULT,
+};
+use std::{collections::VecDeque, ptr, slice};
+use winapi::{
+    shared::{dxgiformat, winerror},
+    um::d3d12,
+};
+
+/// Alignment required for constant buffer views into upload memory.
+pub const CONSTANT_BUFFER_ALIGNMENT: u64 =
+    d3d12::D3D12_CONSTANT_BUFFER_DATA_PLACEMENT_ALIGNMENT as _;
+/// Alignment required for placed texture footprints in upload memory.
+pub const TEXTURE_DATA_ALIGNMENT: u64 = d3d12::D3D12_TEXTURE_DATA_PLACEMENT_ALIGNMENT as _;
+
+/// Ring suballocation bookkeeping, independent of any GPU object.
+///
+/// Allocations made between two calls to `submit` are retired together once
+/// the fence value passed to `submit` has completed.
+#[derive(Debug)]
+pub struct RingAllocator {
+    capacity: u64,
+    // Monotonic positions, the offset into the ring is `position % capacity`.
+    head: u64,
+    tail: u64,
+    in_flight: VecDeque<(u64, u64)>,
+}
+
+impl RingAllocator {
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn new(capacity: u64) -> Self {
+        assert_ne!(capacity, 0, "ring capacity must not be zero");
+        RingAllocator {
+            capacity,
+            head: 0,
+            tail: 0,
+            in_flight: VecDeque::new(),
+        }
+    }
+
+    pub fn capacity(&self) -> u64 {
+        self.capacity
+    }
+
+    /// Number of bytes which are currently allocated, including wasted
+    /// padding and space skipped when wrapping around.
+    pub fn used(&self) -> u64 {
+        self.head - self.tail
+    }
+
+    /// Allocates `size` bytes and returns the offset into the ring.
+    ///
+    /// Allocations never straddle the end of the ring. `alignment` must be a power of two
+    /// which divides the capacity.
+    pub fn allocate(&mut self, size: u64, alignment: u64) -> Option<u64> {
+        debug_assert_eq!(self.capacity % alignment, 0);
+        if size > self.capacity {
+            return None;
+        }
+
+        if self.head == self.tail && self.in_flight.is_empty() {
+            // Nothing is in use, restart from the beginning to avoid a split.
+            self.head += (self.capacity - self.head % self.capacity) % self.capacity;
+            self.tail = self.head;
+        }
+
+        let mut start = align_up(self.head, alignment);
+        let offset = start % self.capacity;
+        if offset + size > self.capacity {
+            // Not enough space left before the end, continue at the beginning.
+            start += self.capacity - offset;
+        }
+        if start + size - self.tail > self.capacity {
+            return None;
+        }
+
+        self.head = start + size;
+        Some(start % self.capacity)
+    }
+
+    /// Associates all allocations since the previous submission with `fence_value`.
+    pub fn submit(&mut self, fence_value: u64) {
+        if let Some(&(last_value, _)) = self.in_flight.back() {
+            debug_assert!(last_value <= fence_value);
+        }
+        self.in_flight.push_back((fence_value, self.head));
+    }
+
+    /// Frees all allocations whose fence value is less or equal to `completed_value`.
+    pub fn reclaim(&mut self, completed_value: u64) {
+        while let Some(&(value, end)) = self.in_flight.front() {
+            if value > completed_value {
+                break;
+            }
+            self.tail = end;
+            self.in_flight.pop_front();
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct UploadAllocation {
+    /// Offset from the start of the ring buffer resource.
+    pub offset: u64,
+    pub size: u64,
+    pub gpu_address: GpuAddress,
+    pub cpu_ptr: *mut u8,
+}
+
+/// Persistently mapped upload heap buffer, suballocated as a ring.
+#[derive(Debug)]
+pub struct UploadRing {
+    resource: Resource,
+    data: *mut u8,
+    gpu_address: GpuAddress,
+    allocator: RingAllocator,
+}
+
+impl UploadRing {
+    /// Creates a committed upload buffer of at least `size` bytes and maps it.
+    pub fn new(device: &Device, size: u64) -> Result<Self, HRESULT> {
+        let (resource, hr) = device.create_committed_resource(
+            HeapProperties::new(
+                HeapType::Upload,
+                CpuPageProperty::Unknown,
+                MemoryPool::Unknown,
+                0,
+                0,
+            ),
+            HeapFlags::empty(),
+            &ResourceDesc::buffer(
+                align_up(size, TEXTURE_DATA_ALIGNMENT),
+                ResourceFlags::empty(),
+            ),
+            d3d12::D3D12_RESOURCE_STATE_GENERIC_READ,
+            None,
+        );
+        if !winerror::SUCCEEDED(hr) {
+            return Err(hr);
+        }
+
+        unsafe { Self::from_resource(resource) }
+    }
+
+    /// Fails with `E_INVALIDARG` if `resource` is smaller than `TEXTURE_DATA_ALIGNMENT`.
+    ///
+    /// # Safety
+    ///
+    /// - `resource` must be a buffer placed in an upload heap.
+    pub unsafe fn from_resource(resource: Resource) -> Result<Self, HRESULT> {
+        // Keep the capacity a multiple of every alignment we hand out.
+        let size = resource.get_desc().0.Width & !(TEXTURE_DATA_ALIGNMENT - 1);
+        if size == 0 {
+            return Err(winerror::E_INVALIDARG);
+        }
+        // The CPU never reads from the ring.
+        let (data, hr) = resource.map(0, Some(0..0));
+        if !winerror::SUCCEEDED(hr) {
+            return Err(hr);
+        }
+
+        Ok(UploadRing {
+            gpu_address: resource.gpu_virtual_address(),
+            resource,
+            data: data as *mut u8,
+            allocator: RingAllocator::new(size),
+        })
+    }
+
+    pub fn resource(&self) -> &Resource {
+        &self.resource
+    }
+
+    pub fn allocator(&self) -> &RingAllocator {
+        &self.allocator
+    }
+
+    pub fn allocate(&mut self, size: u64, alignment: u64) -> Option<UploadAllocation> {
+        let offset = self.allocator.allocate(size, alignment)?;
+        Some(UploadAllocation {
+            offset,
+            size,
+            gpu_address: self.gpu_address + offset,
+            cpu_ptr: unsafe { self.data.add(offset as usize) },
+        })
+    }
+
+    /// Allocates memory suitable for a constant buffer view.
+    pub fn allocate_constants(&mut self, size: u64) -> Option<UploadAllocation> {
+        self.allocate(
+            align_up(size, CONSTANT_BUFFER_ALIGNMENT),
+            CONSTANT_BUFFER_ALIGNMENT,
+        )
+    }
+
+    /// Allocates memory suitable as source of a texture copy.
+    pub fn allocate_texture_data(&mut self, size: u64) -> Option<UploadAllocation> {
+        self.allocate(size, TEXTURE_DATA_ALIGNMENT)
+    }
+
+    /// Copies `data` to the start of `allocation`.
+    pub fn write(&self, allocation: &UploadAllocation, data: &[u8]) {
+        assert!(data.len() as u64 <= allocation.size);
+        assert!(allocation.offset + allocation.size <= self.allocator.capacity());
+        unsafe {
+            ptr::copy_nonoverlapping(
+                data.as_ptr(),
+                self.data.add(allocation.offset as usize),
+                data.len(),
+            );
+        }
+    }
+
+    /// Marks all allocations since the previous submission as used by the GPU
+    /// until `fence_value` is signaled.
+    pub fn submit(&mut self, fence_value: u64) {
+        self.allocator.submit(fence_value);
+    }
+
+    /// Frees all allocations whose submission has been completed by `fence`.
+    pub fn reclaim(&mut self, fence: &Fence) {
+        self.allocator.reclaim(fence.get_value());
+    }
+}
+
+impl Drop for UploadRing {
+    fn drop(&mut self) {
+        self.resource.unmap(0, None);
+    }
+}
+
+/// Returns the width and height in texels of a block of `format`.
+///
+/// This is `(4, 4)` for block-compressed formats and `(1, 1)` otherwise.
+pub fn block_dimensions(format: Format) -> (u32, u32) {
+    match format {
+        dxgiformat::DXGI_FORMAT_BC1_TYPELESS..=dxgiformat::DXGI_FORMAT_BC5_SNORM
+        | dxgiformat::DXGI_FORMAT_BC6H_TYPELESS..=dxgiformat::DXGI_FORMAT_BC7_UNORM_SRGB => (4, 4),
+        _ => (1, 1),
+    }
+}
+
+/// Returns the size in bits of a texel of `format`, or of a block for
+/// block-compressed formats.
+///
+/// Returns `None` for `DXGI_FORMAT_UNKNOWN`, `R1_UNORM`, packed `R8G8_B8G8` formats
+/// and video formats.
+pub fn bits_per_element(format: Format) -> Option<u32> {
+    use dxgiformat::*;
+
+    Some(match format {
+        DXGI_FORMAT_R32G32B32A32_TYPELESS..=DXGI_FORMAT_R32G32B32A32_SINT => 128,
+        DXGI_FORMAT_R32G32B32_TYPELESS..=DXGI_FORMAT_R32G32B32_SINT => 96,
+        DXGI_FORMAT_R16G16B16A16_TYPELESS..=DXGI_FORMAT_X32_TYPELESS_G8X24_UINT => 64,
+        DXGI_FORMAT_R10G10B10A2_TYPELESS..=DXGI_FORMAT_X24_TYPELESS_G8_UINT
+        | DXGI_FORMAT_R9G9B9E5_SHAREDEXP
+        | DXGI_FORMAT_B8G8R8A8_UNORM..=DXGI_FORMAT_B8G8R8X8_UNORM_SRGB => 32,
+        DXGI_FORMAT_R8G8_TYPELESS..=DXGI_FORMAT_R16_SINT
+        | DXGI_FORMAT_B5G6R5_UNORM
+        | DXGI_FORMAT_B5G5R5A1_UNORM
+        | DXGI_FORMAT_B4G4R4A4_UNORM => 16,
+        DXGI_FORMAT_R8_TYPELESS..=DXGI_FORMAT_A8_UNORM => 8,
+        DXGI_FORMAT_BC1_TYPELESS..=DXGI_FORMAT_BC1_UNORM_SRGB
+        | DXGI_FORMAT_BC4_TYPELESS..=DXGI_FORMAT_BC4_SNORM => 64,
+        DXGI_FORMAT_BC2_TYPELESS..=DXGI_FORMAT_BC3_UNORM_SRGB
+        | DXGI_FORMAT_BC5_TYPELESS..=DXGI_FORMAT_BC5_SNORM
+        | DXGI_FORMAT_BC6H_TYPELESS..=DXGI_FORMAT_BC7_UNORM_SRGB => 128,
+        _ => return None,
+    })
+}
+
+/// Placement of rows and depth slices of a subresource in memory.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RowLayout {
+    pub row_pitch: usize,
+    pub slice_pitch: usize,
+}
+
+/// Copies `num_slices` slices of `num_rows` rows, each `row_size` bytes long,
+/// from `src` to `dst`, which may use different row and slice pitches.
+///
+/// For block-compressed formats a row is a row of blocks.
+///
+/// # Panics
+///
+/// Panics if any row is out of bounds of `src` or `dst`.
+pub fn repack_rows(
+    dst: &mut [u8],
+    dst_layout: RowLayout,
+    src: &[u8],
+    src_layout: RowLayout,
+    row_size: usize,
+    num_rows: usize,
+    num_slices: usize,
+) {
+    for slice in 0..num_slices {
+        for row in 0..num_rows {
+            let dst_start = slice * dst_layout.slice_pitch + row * dst_layout.row_pitch;
+            let src_start = slice * src_layout.slice_pitch + row * src_layout.row_pitch;
+            dst[dst_start..dst_start + row_size]
+                .copy_from_slice(&src[src_start..src_start + row_size]);
+        }
+    }
+}
+
+/// CPU data of a single subresource.
+#[derive(Clone, Copy, Debug)]
+pub struct SubresourceData<'a> {
+    pub data: &'a [u8],
+    /// Distance between rows, which are rows of blocks for block-compressed formats.
+    pub row_pitch: usize,
+    /// Distance between depth slices.
+    pub slice_pitch: usize,
+}
+
+/// Stages `subresource_data` in `staging` and records copies into the subresources
+/// of `dst`, starting at subresource 0.
+///
+/// `dst` must be in the `COPY_DEST` state when `list` executes. The returned allocation
+/// stays in use until the next submission of `staging` has completed. Fails with
+/// `E_INVALIDARG` if the layout of `dst` can't be queried and with `E_OUTOFMEMORY`
+/// if `staging` does not have enough space left.
+pub fn upload_texture(
+    list: &GraphicsCommandList,
+    dst: &Resource,
+    subresource_data: &[SubresourceData],
+    staging: &mut UploadRing,
+) -> Result<UploadAllocation, HRESULT> {
+    let (device, hr) = dst.get_device();
+    if !winerror::SUCCEEDED(hr) {
+        return Err(hr);
+    }
+
+    let desc = dst.get_desc();
+    let (footprints, total_bytes) =
+        device.get_copyable_footprints(&desc, 0..subresource_data.len() as _, 0);
+    if total_bytes == u64::MAX {
+        // `GetCopyableFootprints` rejected the description or subresource range.
+        return Err(winerror::E_INVALIDARG);
+    }
+    let allocation = staging
+        .allocate_texture_data(total_bytes)
+        .ok_or(winerror::E_OUTOFMEMORY)?;
+    let mapped = unsafe { slice::from_raw_parts_mut(allocation.cpu_ptr, total_bytes as usize) };
+
+    let (_, block_height) = block_dimensions(desc.0.Format);
+    for (index, (footprint, data)) in footprints.iter().zip(subresource_data).enumerate() {
+        let layout = footprint.layout;
+        debug_assert!(footprint.num_rows * block_height >= layout.Footprint.Height);
+
+        let row_pitch = layout.Footprint.RowPitch as usize;
+        repack_rows(
+            &mut mapped[layout.Offset as usize..],
+            RowLayout {
+                row_pitch,
+                slice_pitch: row_pitch * footprint.num_rows as usize,
+            },
+            data.data,
+            RowLayout {
+                row_pitch: data.row_pitch,
+                slice_pitch: data.slice_pitch,
+            },
+            footprint.row_size as usize,
+            footprint.num_rows as usize,
+            layout.Footprint.Depth as usize,
+        );
+
+        let src_layout = d3d12::D3D12_PLACED_SUBRESOURCE_FOOTPRINT {
+            Offset: allocation.offset + layout.Offset,
+            Footprint: layout.Footprint,
+        };
+        list.copy_texture_region(
+            &TextureCopyLocation::subresource(dst, index as _),
+            [0; 3],
+            &TextureCopyLocation::placed_footprint(staging.resource(), src_layout),
+            None,
+        );
+    }
+
+    Ok(allocation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ring_wraps_without_straddling() {
+        let mut ring = RingAllocator::new(256);
+        assert_eq!(ring.allocate(100, 1), Some(0));
+        ring.submit(1);
+        assert_eq!(ring.allocate(100, 1), Some(100));
+        ring.submit(2);
+        ring.reclaim(1);
+
+        // 56 bytes are left before the end, the allocation restarts at 0.
+        assert_eq!(ring.allocate(100, 1), Some(0));
+        assert_eq!(ring.used(), 256);
+    }
+
+    #[test]
+    fn full_ring_returns_none() {
+        let mut ring = RingAllocator::new(256);
+        assert_eq!(ring.allocate(257, 1), None);
+        assert_eq!(ring.allocate(200, 1), Some(0));
+        assert_eq!(ring.allocate(100, 1), None);
+        assert_eq!(ring.allocate(56, 1), Some(200));
+        assert_eq!(ring.allocate(1, 1), None);
+    }
+
+    #[test]
+    fn reclaim_frees_in_fence_order() {
+        let mut ring = RingAllocator::new(256);
+        for fence_value in 1..=3 {
+            ring.allocate(64, 64).unwrap();
+            ring.submit(fence_value);
+        }
+        assert_eq!(ring.used(), 192);
+
+        ring.reclaim(0);
+        assert_eq!(ring.used(), 192);
+        ring.reclaim(2);
+        assert_eq!(ring.used(), 64);
+        ring.reclaim(3);
+        assert_eq!(ring.used(), 0);
+    }
+
+    #[test]
+    fn idle_ring_restarts_at_zero() {
+        let mut ring = RingAllocator::new(256);
+        assert_eq!(ring.allocate(100, 1), Some(0));
+        ring.submit(1);
+        ring.reclaim(1);
+
+        assert_eq!(ring.allocate(50, 1), Some(0));
+        assert_eq!(ring.used(), 50);
+    }
+
+    #[test]
+    #[should_panic]
+    fn zero_capacity_panics() {
+        RingAllocator::new(0);
+    }
+
+    #[test]
+    fn repack_into_larger_pitch() {
+        let src: Vec<u8> = (0..12).collect();
+        let mut dst = vec![0xFF; 24];
+        repack_rows(
+            &mut dst,
+            RowLayout {
+                row_pitch: 8,
+                slice_pitch: 24,
+            },
+            &src,
+            RowLayout {
+                row_pitch: 4,
+                slice_pitch: 12,
+            },
+            4,
+            3,
+            1,
+        );
+
+        for row in 0..3 {
+            assert_eq!(dst[row * 8..row * 8 + 4], src[row * 4..row * 4 + 4]);
+            assert!(dst[row * 8 + 4..row * 8 + 8].iter().all(|&b| b == 0xFF));
+        }
+    }
+
+    #[test]
+    fn repack_depth_slices() {
+        // 2 slices of 2 rows of 3 bytes, with padding after each row and slice.
+        let src: Vec<u8> = (0..20).collect();
+        let mut dst = vec![0; 12];
+        repack_rows(
+            &mut dst,
+            RowLayout {
+                row_pitch: 3,
+                slice_pitch: 6,
+            },
+            &src,
+            RowLayout {
+                row_pitch: 4,
+                slice_pitch: 10,
+            },
+            3,
+            2,
+            2,
+        );
+
+        assert_eq!(dst, [0, 1, 2, 4, 5, 6, 10, 11, 12, 14, 15, 16]);
+    }
+
+    #[test]
+    fn repack_block_compressed_rows() {
+        // 8x12 BC1 texture: 3 rows of 2 blocks of 8 bytes.
+        let format = dxgiformat::DXGI_FORMAT_BC1_UNORM;
+        let (block_width, block_height) = block_dimensions(format);
+        assert_eq!((block_width, block_height), (4, 4));
+        let row_size = (8 / block_width * bits_per_element(format).unwrap() / 8) as usize;
+        let num_rows = (12 / block_height) as usize;
+        assert_eq!((row_size, num_rows), (16, 3));
+
+        let src: Vec<u8> = (0..48).collect();
+        let mut dst = vec![0; 256 * 3];
+        let dst_layout = RowLayout {
+            row_pitch: 256,
+            slice_pitch: 256 * 3,
+        };
+        let src_layout = RowLayout {
+            row_pitch: 16,
+            slice_pitch: 48,
+        };
+        repack_rows(
+            &mut dst, dst_layout, &src, src_layout, row_size, num_rows, 1,
+        );
+
+        for row in 0..num_rows {
+            assert_eq!(dst[row * 256..row * 256 + 16], src[row * 16..row * 16 + 16]);
+        }
+        assert!(dst[256 * 2 + 16..].iter().all(|&b| b == 0));
+    }
+}